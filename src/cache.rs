@@ -0,0 +1,256 @@
+use dirs::home_dir;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("I/O error")]
+    InputOutputError(#[from] std::io::Error),
+    #[error("Cache database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+    #[error("JSON error")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Cannot determine the home directory")]
+    NoHomeDirectory,
+}
+
+/// The kinds of records kept in the cache
+pub const FOLDERS: &str = "folders";
+pub const MODEL: &str = "model";
+pub const MODELS: &str = "models";
+pub const METADATA: &str = "metadata";
+
+/// Returns the default location of the cache database (~/.pcli/cache.db)
+pub fn default_path() -> Result<PathBuf, CacheError> {
+    match home_dir() {
+        Some(home) => Ok(home.join(".pcli").join("cache.db")),
+        None => Err(CacheError::NoHomeDirectory),
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// An on-disk cache of API records (models, folders, metadata), stored per tenant and kind.
+/// Records older than the time-to-live are treated as missing
+#[derive(Debug)]
+pub struct Cache {
+    connection: Connection,
+    ttl: Duration,
+    /// Kinds of records that expire sooner (or later) than the others
+    ttl_overrides: HashMap<String, Duration>,
+    refresh: bool,
+}
+
+impl Cache {
+    /// Opens (or creates) the cache database
+    pub fn open(path: &Path, ttl: Duration) -> Result<Cache, CacheError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        log::trace!("Opening cache {}...", path.to_string_lossy());
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entries (
+                tenant TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                stored_at INTEGER NOT NULL,
+                etag TEXT,
+                PRIMARY KEY (tenant, kind, key)
+            )",
+        )?;
+
+        // caches created by earlier versions have no ETags
+        let has_etag = connection
+            .prepare("SELECT name FROM pragma_table_info('entries') WHERE name = 'etag'")?
+            .exists([])?;
+        if !has_etag {
+            connection.execute_batch("ALTER TABLE entries ADD COLUMN etag TEXT")?;
+        }
+
+        Ok(Cache {
+            connection,
+            ttl,
+            ttl_overrides: HashMap::new(),
+            refresh: false,
+        })
+    }
+
+    /// Sets the time-to-live for one kind of records
+    pub fn set_ttl(&mut self, kind: &str, ttl: Duration) {
+        self.ttl_overrides.insert(kind.to_owned(), ttl);
+    }
+
+    /// When refreshing, cached records are ignored, but the cache is still updated with the fresh data
+    pub fn set_refresh(&mut self, refresh: bool) {
+        self.refresh = refresh;
+    }
+
+    /// Returns the cached record, if it exists and has not expired
+    pub fn get<T: DeserializeOwned>(&self, tenant: &str, kind: &str, key: &str) -> Option<T> {
+        if self.refresh {
+            return None;
+        }
+
+        let ttl = self.ttl_overrides.get(kind).unwrap_or(&self.ttl);
+        let oldest = now() - ttl.as_secs() as i64;
+        let value: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT value FROM entries WHERE tenant = ?1 AND kind = ?2 AND key = ?3 AND stored_at >= ?4",
+                params![tenant, kind, key, oldest],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to read from the cache: {}", e);
+                None
+            });
+
+        match value {
+            Some(value) => match serde_json::from_str(&value) {
+                Ok(value) => {
+                    log::trace!("Cache hit for {} {}", kind, key);
+                    Some(value)
+                }
+                Err(e) => {
+                    log::warn!("Ignoring invalid cache entry for {} {}: {}", kind, key, e);
+                    None
+                }
+            },
+            None => None,
+        }
+    }
+
+    /// Returns the cached record together with the ETag the API sent for it, even if the record has expired, so that
+    /// the API can be asked whether it is still current. Records without an ETag are not returned
+    pub fn get_tagged<T: DeserializeOwned>(
+        &self,
+        tenant: &str,
+        kind: &str,
+        key: &str,
+    ) -> Option<(T, String)> {
+        if self.refresh {
+            return None;
+        }
+
+        let entry: Option<(String, String)> = self
+            .connection
+            .query_row(
+                "SELECT value, etag FROM entries WHERE tenant = ?1 AND kind = ?2 AND key = ?3 AND etag IS NOT NULL",
+                params![tenant, kind, key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to read from the cache: {}", e);
+                None
+            });
+
+        let (value, etag) = entry?;
+        match serde_json::from_str(&value) {
+            Ok(value) => Some((value, etag)),
+            Err(e) => {
+                log::warn!("Ignoring invalid cache entry for {} {}: {}", kind, key, e);
+                None
+            }
+        }
+    }
+
+    /// Stores the record, replacing any previous version
+    pub fn put<T: Serialize>(
+        &self,
+        tenant: &str,
+        kind: &str,
+        key: &str,
+        value: &T,
+    ) -> Result<(), CacheError> {
+        self.put_tagged(tenant, kind, key, value, None)
+    }
+
+    /// Stores the record with the ETag the API sent for it, replacing any previous version
+    pub fn put_tagged<T: Serialize>(
+        &self,
+        tenant: &str,
+        kind: &str,
+        key: &str,
+        value: &T,
+        etag: Option<&str>,
+    ) -> Result<(), CacheError> {
+        let value = serde_json::to_string(value)?;
+        self.connection.execute(
+            "INSERT OR REPLACE INTO entries (tenant, kind, key, value, stored_at, etag) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![tenant, kind, key, value, now(), etag],
+        )?;
+        Ok(())
+    }
+
+    /// Restarts the time-to-live of the record, after the API confirmed that it is still current
+    pub fn touch(&self, tenant: &str, kind: &str, key: &str) -> Result<(), CacheError> {
+        self.connection.execute(
+            "UPDATE entries SET stored_at = ?4 WHERE tenant = ?1 AND kind = ?2 AND key = ?3",
+            params![tenant, kind, key, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Removes the record with the given key or, if there is no key, all records of the kind
+    pub fn invalidate(
+        &self,
+        tenant: &str,
+        kind: &str,
+        key: Option<&str>,
+    ) -> Result<(), CacheError> {
+        match key {
+            Some(key) => self.connection.execute(
+                "DELETE FROM entries WHERE tenant = ?1 AND kind = ?2 AND key = ?3",
+                params![tenant, kind, key],
+            )?,
+            None => self.connection.execute(
+                "DELETE FROM entries WHERE tenant = ?1 AND kind = ?2",
+                params![tenant, kind],
+            )?,
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_tagged_entries() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache =
+            Cache::open(&directory.path().join("cache.db"), Duration::from_secs(60)).unwrap();
+
+        cache.put("t", MODEL, "a", &"untagged").unwrap();
+        cache
+            .put_tagged("t", MODEL, "b", &"tagged", Some("\"v1\""))
+            .unwrap();
+
+        // only the records stored with an ETag can be revalidated
+        assert_eq!(cache.get_tagged::<String>("t", MODEL, "a"), None);
+        assert_eq!(
+            cache.get_tagged::<String>("t", MODEL, "b"),
+            Some(("tagged".to_owned(), "\"v1\"".to_owned()))
+        );
+
+        cache.touch("t", MODEL, "b").unwrap();
+        assert!(cache.get_tagged::<String>("t", MODEL, "b").is_some());
+    }
+}
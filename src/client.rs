@@ -1,25 +1,33 @@
+use crate::configuration::HttpTimeouts;
 use crate::model::{
-    FolderCreateResponse, GeoMatch, ImageMatch, ListOfModels, ListOfUsers, Model,
+    FolderCreateResponse, ImageMatch, ListOfModels, ListOfUsers, Model,
     ModelCreateMetadataResponse, ModelMetadata, ModelMetadataItem, Property, PropertyCollection,
     VisualMatchItem,
 };
+use crate::recording::{Exchange, Recording};
+use crate::token::TokenError;
+use async_trait::async_trait;
 use core::str::FromStr;
-use log;
+use hyper::client::connect::HttpInfo;
 use reqwest::{
     self,
-    blocking::Client,
-    blocking::Response,
     header::{HeaderMap, HeaderName, HeaderValue},
-    StatusCode,
+    Client, Request, RequestBuilder, Response, StatusCode,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 use std::{
+    cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
-    time::Duration,
+    fmt,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use std::{fs::File, path::Path};
-use std::{io::Read, path::PathBuf};
 use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::Semaphore;
 use url::{self, Url};
 use uuid::Uuid;
 
@@ -27,6 +35,87 @@ fn urlencode<T: AsRef<str>>(s: T) -> String {
     url::form_urlencoded::byte_serialize(s.as_ref().as_bytes()).collect()
 }
 
+/// The environment variable that stands for the access token in the curl commands printed for debugging
+pub const CURL_TOKEN_VARIABLE: &str = "PCLI_TOKEN";
+
+/// Quotes the text for a POSIX shell
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Returns a curl command that sends the same request. The access token is replaced by a reference to the
+/// CURL_TOKEN_VARIABLE environment variable, so that the command can be shared. A body that is streamed from a file or
+/// is not text is read from STDIN instead
+pub(crate) fn curl_command(request: &Request) -> String {
+    let mut command = format!(
+        "curl -X {} {}",
+        request.method(),
+        shell_quote(request.url().as_str())
+    );
+    for (name, value) in request.headers() {
+        if name == reqwest::header::AUTHORIZATION {
+            let scheme = value
+                .to_str()
+                .ok()
+                .and_then(|value| value.split_once(' '))
+                .map(|(scheme, _)| scheme)
+                .unwrap_or("Bearer");
+            command.push_str(&format!(
+                " \\\n  -H \"{}: {} ${}\"",
+                name, scheme, CURL_TOKEN_VARIABLE
+            ));
+        } else {
+            let header = format!("{}: {}", name, String::from_utf8_lossy(value.as_bytes()));
+            command.push_str(&format!(" \\\n  -H {}", shell_quote(&header)));
+        }
+    }
+    if let Some(timeout) = request.timeout() {
+        command.push_str(&format!(" \\\n  --max-time {}", timeout.as_secs()));
+    }
+    match request.body().map(|body| body.as_bytes()) {
+        Some(Some([])) => (),
+        Some(Some(body)) => match std::str::from_utf8(body) {
+            Ok(body) => command.push_str(&format!(" \\\n  --data-raw {}", shell_quote(body))),
+            Err(_) => command.push_str(" \\\n  --data-binary @-"),
+        },
+        Some(None) => command.push_str(" \\\n  --data-binary @-"),
+        None => (),
+    }
+    command
+}
+
+/// Makes the request conditional on the resource having changed since the version with the ETag was read
+fn if_none_match(builder: RequestBuilder, etag: Option<String>) -> RequestBuilder {
+    match etag {
+        Some(etag) => builder.header(reqwest::header::IF_NONE_MATCH, etag),
+        None => builder,
+    }
+}
+
+/// Returns how long to wait before the given retry attempt. The delay requested by the server via the
+/// Retry-After header takes precedence; otherwise, it grows exponentially with some random jitter added
+fn retry_delay(response: Option<&Response>, attempt: u32) -> Duration {
+    let retry_after = response
+        .and_then(|response| response.headers().get(reqwest::header::RETRY_AFTER))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
+    let delay = match retry_after {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => {
+            let delay = RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt - 1));
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or_default();
+            let jitter = delay.mul_f64((nanos % 1000) as f64 / 2000.0);
+            delay + jitter
+        }
+    };
+
+    delay.min(RETRY_MAX_DELAY)
+}
+
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("Parsing error")]
@@ -41,7 +130,7 @@ pub enum ClientError {
     FailedToDeleteFolder(String),
     #[error("No valid folder name/ID provided")]
     InvalidFolderName,
-    #[error("Unsupported operation")]
+    #[error("Unsupported operation: {0}")]
     Unsupported(String),
     #[error("{0}")]
     ServerError(String),
@@ -74,7 +163,7 @@ struct ServerErrorDetails {
 }
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct ServerError {
+pub(crate) struct ServerError {
     #[serde(rename = "error")]
     error_details: ServerErrorDetails,
 }
@@ -82,7 +171,7 @@ pub struct ServerError {
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct PageData {
+pub(crate) struct PageData {
     #[serde(rename = "total")]
     pub total: u32,
     #[serde(rename = "perPage")]
@@ -98,54 +187,15 @@ pub struct PageData {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct PartToPartMatch {
+pub(crate) struct PartToPartMatch {
     #[serde(rename = "matchedModel")]
     pub matched_model: Model,
     #[serde(rename = "matchPercentage")]
     pub match_percentage: f64,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct FolderFilterData {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "id")]
-    pub id: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "name")]
-    pub name: Option<String>,
-}
-
-impl Default for FolderFilterData {
-    fn default() -> Self {
-        Self {
-            id: None,
-            name: None,
-        }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq, Default, Serialize)]
-pub struct PropertyFilterData {
-    #[serde(rename = "id")]
-    pub id: u32,
-    #[serde(rename = "name")]
-    pub name: String,
-    #[serde(rename = "values")]
-    pub values: Vec<String>,
-}
-
-#[derive(Clone, Debug, PartialEq, Serialize)]
-pub struct FilterData {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "folders")]
-    pub folders: Option<Vec<FolderFilterData>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "properties")]
-    pub properties: Option<Vec<PropertyFilterData>>,
-}
-
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct PartToPartMatchResponse {
+pub(crate) struct PartToPartMatchResponse {
     #[serde(rename = "matches")]
     pub matches: Vec<PartToPartMatch>,
     #[serde(rename = "pageData")]
@@ -153,7 +203,7 @@ pub struct PartToPartMatchResponse {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct Folder {
+pub(crate) struct Folder {
     #[serde(rename = "id")]
     pub id: u32,
     #[serde(rename = "createdAt")]
@@ -165,7 +215,7 @@ pub struct Folder {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct FolderListPageResponse {
+pub(crate) struct FolderListPageResponse {
     #[serde(rename = "folders")]
     pub folders: Vec<Folder>,
     #[serde(rename = "pageData")]
@@ -173,7 +223,7 @@ pub struct FolderListPageResponse {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct FolderListResponse {
+pub(crate) struct FolderListResponse {
     #[serde(rename = "folders")]
     pub folders: Vec<Folder>,
 }
@@ -187,20 +237,23 @@ impl From<FolderListPageResponse> for FolderListResponse {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct SingleModelResponse {
+pub(crate) struct SingleModelResponse {
     #[serde(rename = "model")]
     pub model: Box<Model>,
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ModelMetadataResponse {
+pub(crate) struct ModelMetadataResponse {
     #[serde(rename = "metadata")]
     pub metadata: Vec<ModelMetadataItem>,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Default,
+)]
 pub enum Type {
     #[serde(rename = "assemblyTree")]
+    #[default]
     AssemblyTree,
     #[serde(rename = "assemblyPart")]
     AssemblyPart,
@@ -208,12 +261,6 @@ pub enum Type {
     SubAssembly,
 }
 
-impl Default for Type {
-    fn default() -> Type {
-        Self::AssemblyTree
-    }
-}
-
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct AssemblyTree {
     #[serde(rename = "type")]
@@ -225,7 +272,7 @@ pub struct AssemblyTree {
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ModelListResponse {
+pub(crate) struct ModelListResponse {
     #[serde(rename = "models")]
     pub models: Vec<Model>,
     #[serde(rename = "pageData")]
@@ -261,57 +308,8 @@ struct PropertyResponse {
     property: Property,
 }
 
-#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ModelFilter {
-    #[serde(rename = "containerIds")]
-    pub container_ids: Vec<u32>,
-}
-
-impl ModelFilter {
-    pub fn new(folders: Vec<u32>) -> Self {
-        ModelFilter {
-            container_ids: folders,
-        }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct ImageClassifierCreateRequest {
-    #[serde(rename = "name")]
-    pub name: String,
-    #[serde(rename = "modelFilter")]
-    pub filter: ModelFilter,
-}
-
-impl ImageClassifierCreateRequest {
-    pub fn new(name: String, folders: Vec<u32>) -> Self {
-        let filter = ModelFilter::new(folders);
-        ImageClassifierCreateRequest { name, filter }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct ImageClassifierCreateResponse {
-    #[serde(rename = "id")]
-    pub id: Uuid,
-    #[serde(rename = "name")]
-    pub name: String,
-    #[serde(rename = "status")]
-    pub status: String,
-    #[serde(rename = "modelFilter")]
-    pub model_filter: ModelFilter,
-}
-
-#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct GeoMatchPageResponse {
-    #[serde(rename = "matches")]
-    pub matches: Vec<GeoMatch>,
-    #[serde(rename = "pageData")]
-    pub page_data: PageData,
-}
-
 #[derive(Clone, Debug, PartialEq, Serialize)]
-pub struct ImageUploadSpecsRequest {
+pub(crate) struct ImageUploadSpecsRequest {
     filename: String,
 }
 
@@ -322,7 +320,7 @@ impl ImageUploadSpecsRequest {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
-pub struct ImageUploadSizeRequirements {
+pub(crate) struct ImageUploadSizeRequirements {
     #[serde(rename = "minSizeInBytes")]
     pub min_size_in_bytes: u64,
     #[serde(rename = "maxSizeInBytes")]
@@ -330,7 +328,7 @@ pub struct ImageUploadSizeRequirements {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
-pub struct ImageUploadHeaders {
+pub(crate) struct ImageUploadHeaders {
     #[serde(rename = "Content-Type")]
     pub content_type: String,
     #[serde(rename = "X-Goog-Content-Length-Range")]
@@ -338,7 +336,7 @@ pub struct ImageUploadHeaders {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
-pub struct ImageUploadResponse {
+pub(crate) struct ImageUploadResponse {
     #[serde(rename = "id")]
     pub id: String,
     #[serde(rename = "uploadUrl")]
@@ -350,12 +348,12 @@ pub struct ImageUploadResponse {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
-pub struct ImageUploadSpecsResponse {
+pub(crate) struct ImageUploadSpecsResponse {
     pub image: ImageUploadResponse,
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ImageMatchPageResponse {
+pub(crate) struct ImageMatchPageResponse {
     #[serde(rename = "matches")]
     pub matches: Vec<ImageMatch>,
     #[serde(rename = "pageData")]
@@ -424,7 +422,7 @@ impl CustomHeaderName {
         Self(name.to_owned())
     }
 
-    fn into_header_name(&self) -> Option<HeaderName> {
+    fn into_header_name(self) -> Option<HeaderName> {
         HeaderName::from_str(self.0.as_str()).ok()
     }
 }
@@ -435,9 +433,9 @@ impl From<String> for CustomHeaderName {
     }
 }
 
-impl ToString for CustomHeaderName {
-    fn to_string(&self) -> String {
-        self.0.to_owned()
+impl fmt::Display for CustomHeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
@@ -448,40 +446,841 @@ struct SourceFileResponse {
 }
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct ModelVisualMatchResponse {
+pub(crate) struct ModelVisualMatchResponse {
     #[serde(rename = "matches")]
     pub matches: Vec<VisualMatchItem>,
     #[serde(rename = "pageData")]
     pub page_data: PageData,
 }
 
+/// Obtains a fresh access token when the current one is no longer accepted by the server
+#[derive(Clone)]
+pub struct TokenRefresher(Arc<dyn Fn() -> Result<String, TokenError> + Send + Sync>);
+
+impl TokenRefresher {
+    pub fn new(refresh: impl Fn() -> Result<String, TokenError> + Send + Sync + 'static) -> Self {
+        TokenRefresher(Arc::new(refresh))
+    }
+
+    /// Requests the new token on the current thread
+    pub fn token(&self) -> Result<String, TokenError> {
+        (self.0)()
+    }
+
+    /// Requests the new token on a blocking thread, because the identity provider is called synchronously
+    async fn refresh(&self) -> Result<String, TokenError> {
+        let refresh = self.0.clone();
+        match tokio::task::spawn_blocking(move || refresh()).await {
+            Ok(result) => result,
+            Err(_) => Err(TokenError::FailedToObtainTokenFromProvider),
+        }
+    }
+}
+
+impl std::fmt::Debug for TokenRefresher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TokenRefresher")
+    }
+}
+
+/// The file, the number of bytes sent so far and the size of the file
+type ReportUploadProgress = dyn Fn(&Path, u64, u64) + Send + Sync;
+
+/// Receives the progress of file uploads
+#[derive(Clone)]
+pub struct UploadProgress(Arc<ReportUploadProgress>);
+
+impl UploadProgress {
+    pub fn new(report: impl Fn(&Path, u64, u64) + Send + Sync + 'static) -> Self {
+        UploadProgress(Arc::new(report))
+    }
+}
+
+impl std::fmt::Debug for UploadProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UploadProgress")
+    }
+}
+
+/// Receives the curl command equivalent to each request sent
+#[derive(Clone)]
+pub struct CurlCommands(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl CurlCommands {
+    pub fn new(report: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        CurlCommands(Arc::new(report))
+    }
+}
+
+impl std::fmt::Debug for CurlCommands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CurlCommands")
+    }
+}
+
+/// How many requests may be in flight at the same time by default
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// How many times a request rejected with a transient error is retried by default
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// The delay before the first retry. It doubles with every following attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest time to wait between two attempts, including any delay requested by the server
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// The size of the chunks in which large files are uploaded by default
+pub const DEFAULT_UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Chunks of a resumable upload must be a multiple of this size (except the last one)
+const UPLOAD_CHUNK_GRANULARITY: usize = 256 * 1024;
+
+/// The number of folders requested per page by default
+pub const DEFAULT_FOLDERS_PAGE_SIZE: u32 = 1000;
+
+/// The number of metadata properties requested for a model by default
+pub const DEFAULT_METADATA_PAGE_SIZE: u32 = 10000;
+
+/// Returns true if a file transfer that failed with this status may succeed when retried
+fn is_transient_transfer(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// Returns how many bytes of a resumable upload the storage has received, as confirmed in the Range header
+/// (e.g. "bytes=0-8388607") of its response
+fn committed_bytes(response: &Response) -> u64 {
+    // without a Range header, the storage has not received any bytes yet
+    response
+        .headers()
+        .get(reqwest::header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit('-').next())
+        .and_then(|last| last.trim().parse::<u64>().ok())
+        .map(|last| last + 1)
+        .unwrap_or_default()
+}
+
+/// The last request that did not succeed, kept to report failures with their HTTP status and endpoint
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestFailure {
+    /// None if no response was received (e.g. the connection was refused or timed out)
+    pub status: Option<StatusCode>,
+    pub endpoint: String,
+    pub timeout: bool,
+}
+
+impl RequestFailure {
+    fn from_response(response: &Response) -> RequestFailure {
+        RequestFailure {
+            status: Some(response.status()),
+            endpoint: response.url().path().to_owned(),
+            timeout: false,
+        }
+    }
+
+    fn from_error(error: &reqwest::Error) -> RequestFailure {
+        RequestFailure {
+            status: error.status(),
+            endpoint: error
+                .url()
+                .map(|url| url.path().to_owned())
+                .unwrap_or_default(),
+            timeout: error.is_timeout(),
+        }
+    }
+}
+
+/// How many HTTP requests were sent and how many connections had to be opened for them
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConnectionStatistics {
+    pub requests: u64,
+    pub connections: u64,
+}
+
+impl ConnectionStatistics {
+    /// The number of requests sent over a connection that was already open
+    pub fn reused(&self) -> u64 {
+        self.requests.saturating_sub(self.connections)
+    }
+}
+
+impl std::fmt::Display for ConnectionStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ratio = if self.requests > 0 {
+            self.reused() as f64 * 100.0 / self.requests as f64
+        } else {
+            0.0
+        };
+        write!(
+            f,
+            "HTTP requests: {}, connections opened: {}, connections reused: {} ({:.1}%)",
+            self.requests,
+            self.connections,
+            self.reused(),
+            ratio
+        )
+    }
+}
+
+/// Creates the HTTP client. All requests of a run share its connection pool, so that connections are kept alive and reused
+pub(crate) fn build_http_client(proxy: Option<reqwest::Proxy>) -> Result<Client, reqwest::Error> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(180))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60));
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    builder.build()
+}
+
+/// The answer to a conditional request, which carries the ETag of a cached copy in the If-None-Match header
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Conditional<T> {
+    /// The resource changed (or there was no ETag to compare with). The ETag of the new version, if the API sent one
+    Modified { value: T, etag: Option<String> },
+    /// The cached copy is still current (304 Not Modified)
+    NotModified,
+}
+
+impl<T> Conditional<T> {
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> Conditional<U> {
+        match self {
+            Conditional::Modified { value, etag } => Conditional::Modified {
+                value: f(value),
+                etag,
+            },
+            Conditional::NotModified => Conditional::NotModified,
+        }
+    }
+
+    /// The resource read by a request without an ETag, which the API has nothing to compare with
+    pub(crate) fn into_modified(self) -> Result<T, ClientError> {
+        match self {
+            Conditional::Modified { value, .. } => Ok(value),
+            Conditional::NotModified => Err(ClientError::ServerError(String::from(
+                "Unexpected response 304 Not Modified",
+            ))),
+        }
+    }
+}
+
+/// The Physna API operations used by the `Api`. `ApiClient` implements them over HTTP; tests substitute an
+/// in-memory implementation
+#[async_trait(?Send)]
+pub(crate) trait PhysnaClient {
+    fn tenant(&self) -> &str;
+
+    /// Returns how many requests were sent so far and over how many connections
+    fn statistics(&self) -> ConnectionStatistics {
+        ConnectionStatistics::default()
+    }
+
+    /// Returns the last request that failed, if any
+    fn last_failure(&self) -> Option<RequestFailure> {
+        None
+    }
+
+    async fn get_model_match_page(
+        &self,
+        uuid: &Uuid,
+        threshold: f64,
+        per_page: u32,
+        page: u32,
+    ) -> Result<PartToPartMatchResponse, ClientError>;
+
+    async fn get_model_scan_match_page(
+        &self,
+        uuid: &Uuid,
+        threshold: f64,
+        per_page: u32,
+        page: u32,
+    ) -> Result<PartToPartMatchResponse, ClientError>;
+
+    async fn get_model_visual_match_page(
+        &self,
+        uuid: &Uuid,
+        per_page: u32,
+        page: u32,
+    ) -> Result<ModelVisualMatchResponse, ClientError>;
+
+    /// Returns the folders with the given names or, if there are no names, all folders
+    async fn get_list_of_folders(
+        &self,
+        desired_folders: Option<HashSet<String>>,
+    ) -> Result<FolderListResponse, ClientError>;
+
+    /// Reads the list of all folders unless the copy with the ETag is still current
+    async fn get_list_of_folders_conditional(
+        &self,
+        _etag: Option<String>,
+    ) -> Result<Conditional<FolderListResponse>, ClientError> {
+        Ok(Conditional::Modified {
+            value: self.get_list_of_folders(None).await?,
+            etag: None,
+        })
+    }
+
+    async fn delete_folder(&self, folders: &HashSet<u32>) -> Result<(), ClientError>;
+
+    async fn create_folder(&self, name: &str) -> Result<FolderCreateResponse, ClientError>;
+
+    async fn rename_folder(
+        &self,
+        id: &u32,
+        name: &str,
+    ) -> Result<FolderCreateResponse, ClientError>;
+
+    async fn get_model(&self, uuid: &Uuid) -> Result<SingleModelResponse, ClientError>;
+
+    /// Reads the model unless the copy with the ETag is still current
+    async fn get_model_conditional(
+        &self,
+        uuid: &Uuid,
+        _etag: Option<String>,
+    ) -> Result<Conditional<SingleModelResponse>, ClientError> {
+        Ok(Conditional::Modified {
+            value: self.get_model(uuid).await?,
+            etag: None,
+        })
+    }
+
+    async fn delete_model(&self, uuid: &Uuid) -> Result<(), ClientError>;
+
+    async fn reprocess_model(&self, uuid: &Uuid) -> Result<(), ClientError>;
+
+    async fn get_model_metadata(&self, uuid: &Uuid) -> Result<Option<ModelMetadata>, ClientError>;
+
+    /// Reads the metadata of the model unless the copy with the ETag is still current
+    async fn get_model_metadata_conditional(
+        &self,
+        uuid: &Uuid,
+        _etag: Option<String>,
+    ) -> Result<Conditional<Option<ModelMetadata>>, ClientError> {
+        Ok(Conditional::Modified {
+            value: self.get_model_metadata(uuid).await?,
+            etag: None,
+        })
+    }
+
+    async fn get_assembly_tree_for_model(&self, uuid: &Uuid) -> Result<AssemblyTree, ClientError>;
+
+    /// Returns a single-page response for list of models
+    ///
+    /// Parameters:
+    ///
+    /// folders - a list of folder IDs. If the list is empty, models from all folders will be included
+    /// search - a search clause (e.g. part number)
+    /// per_page - how many records to return per page
+    /// page - the current page number
+    async fn get_list_of_models_page(
+        &self,
+        folders: Option<HashSet<u32>>,
+        search: Option<&String>,
+        per_page: u32,
+        page: u32,
+    ) -> Result<ModelListResponse, ClientError>;
+
+    async fn upload_model(&self, folder: &str, path: &Path) -> Result<Option<Model>, ClientError>;
+
+    async fn download_model(&self, uuid: &Uuid) -> Result<(), ClientError>;
+
+    /// Downloads the source file of a model into a directory
+    ///
+    /// Parameters:
+    ///
+    /// uuid - the model UUID
+    /// directory - the directory where the source file will be written
+    ///
+    /// Returns the path to the downloaded file
+    async fn download_model_to(
+        &self,
+        uuid: &Uuid,
+        directory: &Path,
+    ) -> Result<PathBuf, ClientError>;
+
+    async fn get_list_of_properties(&self) -> Result<PropertyCollection, ClientError>;
+
+    async fn post_property(&self, name: &str) -> Result<Property, ClientError>;
+
+    async fn put_model_property(
+        &self,
+        model_uuid: &Uuid,
+        id: &u64,
+        item: &ModelMetadataItem,
+    ) -> Result<ModelMetadataItem, ClientError>;
+
+    async fn delete_model_property(&self, model_uuid: &Uuid, id: &u64) -> Result<(), ClientError>;
+
+    async fn delete_property(&self, id: &u64) -> Result<(), ClientError>;
+
+    async fn get_image_upload_specs(&self, path: &Path)
+        -> Result<ImageUploadResponse, ClientError>;
+
+    async fn upload_image_file(
+        &self,
+        url: Url,
+        upload_size_requirements: ImageUploadSizeRequirements,
+        path: &Path,
+        mime: String,
+        content_range: String,
+    ) -> Result<(), ClientError>;
+
+    async fn get_image_search_maches(
+        &self,
+        id: Vec<String>,
+        search: Option<&String>,
+        filter: Option<&String>,
+        max_matches: u32,
+        per_page: u32,
+    ) -> Result<ListOfModels, ClientError>;
+
+    async fn get_list_of_users(&self) -> Result<ListOfUsers, ClientError>;
+}
+
 #[derive(Clone, Debug)]
-pub struct ApiClient {
+pub(crate) struct ApiClient {
     pub client: Client,
     pub base_url: String,
     pub tenant: String,
-    pub access_token: String,
+    access_token: RefCell<String>,
+    token_refresher: Option<TokenRefresher>,
+    max_retries: u32,
+    timeouts: HttpTimeouts,
+    permits: Arc<Semaphore>,
+    requests: Cell<u64>,
+    connections: RefCell<HashSet<SocketAddr>>,
+    last_failure: RefCell<Option<RequestFailure>>,
+    recording: Option<Arc<Recording>>,
+    upload_chunk_size: usize,
+    upload_progress: Option<UploadProgress>,
+    folders_page_size: u32,
+    metadata_page_size: u32,
+    curl_commands: Option<CurlCommands>,
 }
 
 impl ApiClient {
-    pub fn connect(base_url: &String, tenant: &String, access_token: &String) -> ApiClient {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(180))
-            .build()
-            .unwrap();
-
+    pub fn connect(
+        client: Client,
+        base_url: &String,
+        tenant: &String,
+        access_token: &String,
+    ) -> ApiClient {
         ApiClient {
             client,
             base_url: base_url.to_owned(),
             tenant: tenant.to_owned(),
-            access_token: access_token.to_owned(),
+            access_token: RefCell::new(access_token.to_owned()),
+            token_refresher: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            timeouts: HttpTimeouts::default(),
+            permits: Arc::new(Semaphore::new(DEFAULT_CONCURRENCY)),
+            requests: Cell::new(0),
+            connections: RefCell::new(HashSet::new()),
+            last_failure: RefCell::new(None),
+            recording: None,
+            upload_chunk_size: DEFAULT_UPLOAD_CHUNK_SIZE,
+            upload_progress: None,
+            folders_page_size: DEFAULT_FOLDERS_PAGE_SIZE,
+            metadata_page_size: DEFAULT_METADATA_PAGE_SIZE,
+            curl_commands: None,
+        }
+    }
+
+    /// Records all HTTP exchanges to disk, or answers the requests from such a recording instead of sending them
+    pub fn set_recording(&mut self, recording: Recording) {
+        self.recording = Some(Arc::new(recording));
+    }
+
+    /// Sets how many requests may be in flight at the same time
+    pub fn set_concurrency(&mut self, concurrency: usize) {
+        self.permits = Arc::new(Semaphore::new(concurrency.max(1)));
+    }
+
+    /// Overrides the built-in request timeouts
+    pub fn set_timeouts(&mut self, timeouts: HttpTimeouts) {
+        self.timeouts = timeouts;
+    }
+
+    /// Sets how many times a request rejected with a transient error (429, 502, 503 or 504) is retried
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+
+    /// Passes an equivalent curl command for every request sent to the receiver
+    pub fn set_curl_commands(&mut self, curl_commands: CurlCommands) {
+        self.curl_commands = Some(curl_commands);
+    }
+
+    /// Sets the function used to obtain a fresh access token when a request is rejected as unauthorized
+    pub fn set_token_refresher(&mut self, token_refresher: TokenRefresher) {
+        self.token_refresher = Some(token_refresher);
+    }
+
+    /// Sets the size of the chunks in which files are sent to a resumable upload session
+    pub fn set_upload_chunk_size(&mut self, chunk_size: usize) {
+        // rounded up to the granularity required by the storage
+        self.upload_chunk_size = chunk_size
+            .div_ceil(UPLOAD_CHUNK_GRANULARITY)
+            .max(1)
+            .saturating_mul(UPLOAD_CHUNK_GRANULARITY);
+    }
+
+    /// Sets the function that receives the progress of file uploads
+    pub fn set_upload_progress(&mut self, upload_progress: UploadProgress) {
+        self.upload_progress = Some(upload_progress);
+    }
+
+    /// Sets the number of folders requested per page
+    pub fn set_folders_page_size(&mut self, page_size: u32) {
+        self.folders_page_size = page_size.max(1);
+    }
+
+    /// Sets the number of metadata properties requested for a model
+    pub fn set_metadata_page_size(&mut self, page_size: u32) {
+        self.metadata_page_size = page_size.max(1);
+    }
+
+    /// Returns the timeout for an API query, falling back to the given default (in seconds)
+    fn query_timeout(&self, default: u64) -> Duration {
+        Duration::from_secs(self.timeouts.query.unwrap_or(default))
+    }
+
+    /// Returns the timeout for a file transfer, falling back to the given default (in seconds)
+    fn upload_timeout(&self, default: u64) -> Duration {
+        Duration::from_secs(self.timeouts.upload.unwrap_or(default))
+    }
+
+    /// Authorizes and executes the request. If the server rejects the access token (e.g. because it expired
+    /// during a long-running operation), a fresh token is obtained and the request is retried once
+    async fn execute(&self, builder: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let retry = builder.try_clone();
+
+        let response = self.send(builder).await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        match (&self.token_refresher, retry) {
+            (Some(token_refresher), Some(retry)) => match token_refresher.refresh().await {
+                Ok(token) => {
+                    log::debug!("Access token refreshed. Retrying the request...");
+                    self.access_token.replace(token);
+                    self.send(retry).await
+                }
+                Err(e) => {
+                    log::warn!("Failed to refresh the access token: {}", e);
+                    Ok(response)
+                }
+            },
+            _ => Ok(response),
+        }
+    }
+
+    /// Sends the request with the current access token. Transient failures are retried with exponential
+    /// backoff up to the configured number of times. Requests with a streamed body cannot be retried.
+    /// The number of requests in flight is bounded by the concurrency limit
+    async fn send(&self, mut builder: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let mut attempt: u32 = 0;
+
+        loop {
+            let next = builder.try_clone();
+            let request = builder
+                .bearer_auth(self.access_token.borrow().to_owned())
+                .build()?;
+            log::trace!("{} {}", request.method(), request.url());
+            let method = request.method().to_owned();
+            let url = request.url().to_owned();
+            let response = self.dispatch(request, false).await?;
+
+            let status = response.status();
+            let transient = matches!(
+                status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            );
+
+            match next {
+                Some(next) if transient && attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = retry_delay(Some(&response), attempt);
+                    log::warn!(
+                        "{} {} failed with status {}. Retrying in {:.1}s (attempt {} of {})...",
+                        method,
+                        url,
+                        status,
+                        delay.as_secs_f64(),
+                        attempt,
+                        self.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                    builder = next;
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    /// Sends a request that must not carry the access token (e.g. to a pre-signed storage URL)
+    async fn transfer(&self, builder: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let request = builder.build()?;
+        log::trace!("{} {}", request.method(), request.url());
+        self.dispatch(request, true).await
+    }
+
+    /// Executes the request on the shared connection pool, once a permit is available, and records which
+    /// connection it was sent over. When replaying a recording, the request is answered from it instead. The query
+    /// of a transfer URL carries its signature, so it is not recorded
+    async fn dispatch(&self, request: Request, transfer: bool) -> Result<Response, reqwest::Error> {
+        let recorder = match self.recording.as_deref() {
+            Some(Recording::Replay(replay)) => {
+                self.requests.set(self.requests.get() + 1);
+                return Ok(replay.respond(&request, transfer));
+            }
+            Some(Recording::Record(recorder)) => {
+                Some((recorder, Exchange::new(&request, transfer)))
+            }
+            None => None,
+        };
+        if let Some(curl_commands) = &self.curl_commands {
+            (curl_commands.0)(&curl_command(&request));
+        }
+
+        let _permit = self.permits.acquire().await;
+        let response = self.client.execute(request).await.inspect_err(|e| {
+            self.last_failure
+                .replace(Some(RequestFailure::from_error(e)));
+        })?;
+        self.last_failure.replace(None);
+
+        self.requests.set(self.requests.get() + 1);
+        if let Some(info) = response.extensions().get::<HttpInfo>() {
+            self.connections.borrow_mut().insert(info.local_addr());
+        }
+
+        match recorder {
+            Some((recorder, exchange)) => recorder.record(exchange, response).await,
+            None => Ok(response),
+        }
+    }
+
+    /// Uploads the source file of a new model to the signed URL. If the URL starts a resumable upload session, the
+    /// file is sent in chunks and, after a failure, the upload continues from the last byte the storage received.
+    /// Otherwise, the whole file is sent at once. Failed transfers are retried up to the configured number of times
+    async fn upload_source_file(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        path: &Path,
+    ) -> Result<(), ClientError> {
+        let total = tokio::fs::metadata(path).await?.len();
+        let resumable = headers
+            .get("x-goog-resumable")
+            .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"start"));
+
+        self.report_progress(path, 0, total);
+        if resumable && total > 0 {
+            let session = self.start_upload_session(url, headers).await?;
+            self.upload_chunks(&session, path, total).await?;
+        } else {
+            self.upload_whole_file(url, headers, path).await?;
+        }
+        self.report_progress(path, total, total);
+        Ok(())
+    }
+
+    async fn upload_whole_file(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        path: &Path,
+    ) -> Result<(), ClientError> {
+        let buffer = tokio::fs::read(path).await?;
+        let mut builder = self
+            .client
+            .put(url)
+            .timeout(self.upload_timeout(180))
+            .headers(headers)
+            .body(buffer);
+
+        let mut attempt: u32 = 0;
+        loop {
+            let next = builder.try_clone();
+            let result = self.transfer(builder).await;
+            let failure = match &result {
+                Ok(response) if is_transient_transfer(response.status()) => {
+                    response.status().to_string()
+                }
+                Ok(_) => String::new(),
+                Err(e) => e.to_string(),
+            };
+
+            match next {
+                Some(next) if !failure.is_empty() && attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = retry_delay(result.as_ref().ok(), attempt);
+                    log::warn!(
+                        "Uploading {} failed ({}). Retrying in {:.1}s (attempt {} of {})...",
+                        path.display(),
+                        failure,
+                        delay.as_secs_f64(),
+                        attempt,
+                        self.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                    builder = next;
+                }
+                _ => return self.evaluate_response(&result?),
+            }
+        }
+    }
+
+    /// Starts a resumable upload session and returns the URL to which the chunks are sent
+    async fn start_upload_session(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<String, ClientError> {
+        let builder = self
+            .client
+            .post(url)
+            .timeout(self.query_timeout(60))
+            .headers(headers)
+            .header(reqwest::header::CONTENT_LENGTH, 0);
+        let response = self.transfer(builder).await?;
+        self.evaluate_response(&response)?;
+
+        response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_owned())
+            .ok_or_else(|| ClientError::Parsing("The upload session URL is missing".to_string()))
+    }
+
+    async fn upload_chunks(
+        &self,
+        session: &str,
+        path: &Path,
+        total: u64,
+    ) -> Result<(), ClientError> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut offset: u64 = 0;
+        let mut attempt: u32 = 0;
+
+        loop {
+            let end = (offset + self.upload_chunk_size as u64).min(total);
+            let mut chunk = vec![0; (end - offset) as usize];
+            file.seek(SeekFrom::Start(offset)).await?;
+            file.read_exact(&mut chunk).await?;
+
+            let builder = self
+                .client
+                .put(session)
+                .timeout(self.upload_timeout(180))
+                .header(
+                    reqwest::header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", offset, end.saturating_sub(1), total),
+                )
+                .body(chunk);
+
+            let delay = match self.transfer(builder).await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) if response.status() == StatusCode::PERMANENT_REDIRECT => {
+                    // the storage expects the next chunk
+                    let committed = committed_bytes(&response);
+                    if committed > offset {
+                        offset = committed;
+                        attempt = 0;
+                        self.report_progress(path, offset, total);
+                        continue;
+                    }
+
+                    // none of the chunk was received, which counts as a failed attempt
+                    offset = committed;
+                    if attempt >= self.max_retries {
+                        return Err(ClientError::ServerError(format!(
+                            "The storage did not accept the upload of {} after {} of {} bytes",
+                            path.display(),
+                            offset,
+                            total
+                        )));
+                    }
+                    retry_delay(Some(&response), attempt + 1)
+                }
+                Ok(response)
+                    if is_transient_transfer(response.status()) && attempt < self.max_retries =>
+                {
+                    retry_delay(Some(&response), attempt + 1)
+                }
+                Ok(response) => return self.evaluate_response(&response),
+                Err(e) if attempt < self.max_retries => {
+                    log::debug!("Uploading a chunk of {} failed: {}", path.display(), e);
+                    retry_delay(None, attempt + 1)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            attempt += 1;
+            log::warn!(
+                "Uploading {} was interrupted after {} of {} bytes. Resuming in {:.1}s (attempt {} of {})...",
+                path.display(),
+                offset,
+                total,
+                delay.as_secs_f64(),
+                attempt,
+                self.max_retries
+            );
+            tokio::time::sleep(delay).await;
+
+            // part of the failed chunk may have been received
+            match self.query_upload_offset(session, total).await {
+                Ok(Some(committed)) => offset = committed,
+                Ok(None) => return Ok(()),
+                Err(e) => log::warn!("Failed to query the status of the upload: {}", e),
+            }
+        }
+    }
+
+    /// Asks the storage how many bytes of the resumable upload it has received. Returns None if the upload is complete
+    async fn query_upload_offset(
+        &self,
+        session: &str,
+        total: u64,
+    ) -> Result<Option<u64>, ClientError> {
+        let builder = self
+            .client
+            .put(session)
+            .timeout(self.query_timeout(60))
+            .header(reqwest::header::CONTENT_RANGE, format!("bytes */{}", total))
+            .header(reqwest::header::CONTENT_LENGTH, 0);
+        let response = self.transfer(builder).await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(None),
+            StatusCode::PERMANENT_REDIRECT => Ok(Some(committed_bytes(&response))),
+            _ => self.evaluate_response(&response).map(|_| None),
+        }
+    }
+
+    fn report_progress(&self, path: &Path, sent: u64, total: u64) {
+        if let Some(upload_progress) = &self.upload_progress {
+            (upload_progress.0)(path, sent, total);
         }
     }
 
     fn evaluate_response(&self, response: &Response) -> Result<(), ClientError> {
         let status = response.status();
         if status.is_success() {
-            ()
+        } else {
+            self.last_failure
+                .replace(Some(RequestFailure::from_response(response)));
         }
 
         match status {
@@ -573,28 +1372,253 @@ impl ApiClient {
             let mut builder = self
                 .client
                 .request(reqwest::Method::GET, url)
-                .timeout(Duration::from_secs(180))
+                .timeout(self.query_timeout(180))
                 .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
                 .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-            match query_parameters {
-                Some(query_parametes) => {
-                    for (key, value) in query_parametes {
-                        builder = builder.query(&[(key.to_owned(), value.to_owned())]);
-                    }
-                }
-                None => (),
-            }
+            match query_parameters {
+                Some(query_parametes) => {
+                    for (key, value) in query_parametes {
+                        builder = builder.query(&[(key.to_owned(), value.to_owned())]);
+                    }
+                }
+                None => (),
+            }
+
+            let response = self.execute(builder).await;
+
+            self.handle_response::<String>(response).await
+        }
+    */
+
+    async fn get_list_of_folders_page(
+        &self,
+        page: u32,
+        per_page: u32,
+        filter: Option<String>,
+        etag: Option<String>,
+    ) -> Result<Conditional<FolderListPageResponse>, ClientError> {
+        let url = format!("{}/v2/folders", self.base_url);
+
+        let mut query: Vec<(&str, String)> = Vec::new();
+        query.push(("page", page.to_string()));
+        query.push(("perPage", per_page.to_string()));
+        if filter.is_some() {
+            query.push(("filter", filter.unwrap_or_default()));
+        };
+
+        let builder = self
+            .client
+            .get(url)
+            .timeout(self.query_timeout(30))
+            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
+            .header(
+                reqwest::header::ACCEPT,
+                HeaderValue::from_static("application/json"),
+            )
+            .header("X-PHYSNA-TENANTID", self.tenant.to_owned())
+            .query(&query);
+        let builder = if_none_match(builder, etag);
+
+        let response = self.execute(builder).await;
+        self.handle_conditional_response::<FolderListPageResponse>(response)
+            .await
+    }
+
+    /// Reads the folders that pass the filter. The API can only tell whether the whole list is unchanged if it fits
+    /// into a single page, so the ETag is only sent for and returned with the first page
+    async fn list_folders(
+        &self,
+        filter: Option<String>,
+        etag: Option<String>,
+    ) -> Result<Conditional<FolderListResponse>, ClientError> {
+        let mut current_page: u32 = 1;
+        let per_page = self.folders_page_size;
+
+        let mut folders: Vec<Folder> = Vec::new();
+        let mut etag = etag;
+        loop {
+            let page = match self
+                .get_list_of_folders_page(current_page, per_page, filter.to_owned(), etag.take())
+                .await?
+            {
+                Conditional::Modified { value, etag: tag } => {
+                    etag = tag;
+                    value
+                }
+                Conditional::NotModified => return Ok(Conditional::NotModified),
+            };
+            folders.extend(page.folders);
+            if current_page >= page.page_data.last_page {
+                break;
+            }
+            current_page += 1;
+        }
+
+        Ok(Conditional::Modified {
+            value: FolderListResponse { folders },
+            etag: etag.filter(|_| current_page == 1),
+        })
+    }
+
+    /// Checks the response from an HTTP operation for errors and if none, parses the response body into specific type
+    ///
+    /// Parameters:
+    ///
+    /// response - thre result from the response
+    async fn handle_response<T>(
+        &self,
+        response: Result<Response, reqwest::Error>,
+    ) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        log::trace!("Analyzing HTTP response...");
+        match response {
+            Ok(response) => {
+                log::trace!("Evaluating the HTTP status ({})...", response.status());
+
+                match self.evaluate_response(&response) {
+                    Ok(_) => {
+                        // normal exit status from the HTTP operation
+                        log::trace!("The exit status code indicates normal operation");
+
+                        let exit_status = &response.status();
+                        let json = &response.text().await;
+
+                        match json {
+                            Ok(json) => {
+                                log::trace!(
+                                    "HTTP response [{}]: \"{}\"",
+                                    exit_status.to_string(),
+                                    json.to_owned()
+                                );
+                                if std::any::TypeId::of::<T>() == std::any::TypeId::of::<()>() {
+                                    // Correctly return `()` for `T`
+                                    unsafe { Ok(std::mem::transmute_copy(&())) }
+                                } else {
+                                    let object = serde_json::from_str::<T>(json)?;
+                                    Ok(object)
+                                }
+                            }
+                            Err(e) => Err(ClientError::ServerError(e.to_string())),
+                        }
+                    }
+                    Err(e) => {
+                        // the response status indicates an error
+                        let exit_status = &response.status();
+                        // attempting to get the message sent by the server...
+                        let json = response.text().await;
+                        match json {
+                            Ok(json) => {
+                                // the response has a payload
+                                log::trace!(
+                                    "HTTP response [{}]: {}",
+                                    exit_status.to_string(),
+                                    json.to_owned()
+                                );
+
+                                match serde_json::from_str::<ServerError>(&json) {
+                                    Ok(server_error) => Err(ClientError::ServerError(
+                                        server_error.error_details.message,
+                                    )),
+                                    Err(_) => Err(e),
+                                }
+                            }
+                            Err(_) => Err(e),
+                        }
+                    }
+                }
+            }
+            Err(e) => Err(ClientError::ServerError(e.to_string())),
+        }
+    }
+
+    /// Like `handle_response`, but for a request that may have carried the ETag of a cached copy. A 304 Not Modified
+    /// status means that the copy is still current. Otherwise, the ETag of the new version is returned with it
+    async fn handle_conditional_response<T>(
+        &self,
+        response: Result<Response, reqwest::Error>,
+    ) -> Result<Conditional<T>, ClientError>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let response = match response {
+            Ok(response) if response.status() == StatusCode::NOT_MODIFIED => {
+                log::trace!("The cached copy is still current");
+                return Ok(Conditional::NotModified);
+            }
+            response => response,
+        };
+        let etag = response
+            .as_ref()
+            .ok()
+            .and_then(|response| response.headers().get(reqwest::header::ETAG))
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_owned());
+
+        Ok(Conditional::Modified {
+            value: self.handle_response::<T>(response).await?,
+            etag,
+        })
+    }
+
+    async fn get_image_search_matches_page(
+        &self,
+        ids: Vec<String>,
+        search: Option<&String>,
+        filter: Option<&String>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<ImageMatchPageResponse, ClientError> {
+        let url = format!("{}/v2/images/model-matches", self.base_url);
+        let mut query_parameters: Vec<(String, String)> = Vec::new();
+        for id in ids {
+            query_parameters.push(("id".to_string(), id));
+        }
+        query_parameters.push(("perPage".to_string(), per_page.to_string()));
+        query_parameters.push(("page".to_string(), page.to_string()));
+
+        if let Some(search) = search {
+            query_parameters.push(("search".to_string(), search.to_owned()))
+        }
+        if let Some(filter) = filter {
+            query_parameters.push(("filter".to_string(), filter.to_owned()))
+        }
+
+        let builder = self
+            .client
+            .request(reqwest::Method::GET, url)
+            .query(&query_parameters)
+            .timeout(self.query_timeout(180))
+            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
+            .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-            let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-            log::trace!("GET {}", request.url());
-            let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-            self.handle_response::<String>(response)
+        self.handle_response::<ImageMatchPageResponse>(response)
+            .await
+    }
+}
+
+#[async_trait(?Send)]
+impl PhysnaClient for ApiClient {
+    fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    fn statistics(&self) -> ConnectionStatistics {
+        ConnectionStatistics {
+            requests: self.requests.get(),
+            connections: self.connections.borrow().len() as u64,
         }
-    */
+    }
+
+    fn last_failure(&self) -> Option<RequestFailure> {
+        self.last_failure.borrow().clone()
+    }
 
-    pub fn get_model_match_page(
+    async fn get_model_match_page(
         &self,
         uuid: &Uuid,
         threshold: f64,
@@ -610,7 +1634,7 @@ impl ApiClient {
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .query(&[
                 ("threshold", threshold.to_string().as_str()),
                 ("perPage", per_page.to_string().as_str()),
@@ -619,14 +1643,14 @@ impl ApiClient {
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-        Ok(self.handle_response::<PartToPartMatchResponse>(response)?)
+        Ok(self
+            .handle_response::<PartToPartMatchResponse>(response)
+            .await?)
     }
 
-    pub fn get_model_scan_match_page(
+    async fn get_model_scan_match_page(
         &self,
         uuid: &Uuid,
         threshold: f64,
@@ -642,7 +1666,7 @@ impl ApiClient {
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .query(&[
                 ("threshold", threshold.to_string().as_str()),
                 ("perPage", per_page.to_string().as_str()),
@@ -651,14 +1675,14 @@ impl ApiClient {
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-        Ok(self.handle_response::<PartToPartMatchResponse>(response)?)
+        Ok(self
+            .handle_response::<PartToPartMatchResponse>(response)
+            .await?)
     }
 
-    pub fn get_model_visual_match_page(
+    async fn get_model_visual_match_page(
         &self,
         uuid: &Uuid,
         per_page: u32,
@@ -673,7 +1697,7 @@ impl ApiClient {
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .query(&[
                 ("perPage", per_page.to_string().as_str()),
                 ("page", page.to_string().as_str()),
@@ -681,54 +1705,19 @@ impl ApiClient {
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
-
-        Ok(self.handle_response::<ModelVisualMatchResponse>(response)?)
-    }
-
-    fn get_list_of_folders_page(
-        &self,
-        page: u32,
-        per_page: u32,
-        filter: Option<String>,
-    ) -> Result<FolderListPageResponse, ClientError> {
-        let url = format!("{}/v2/folders", self.base_url);
-
-        let mut query: Vec<(&str, String)> = Vec::new();
-        query.push(("page", page.to_string()));
-        query.push(("perPage", per_page.to_string()));
-        if filter.is_some() {
-            query.push(("filter", filter.unwrap_or_default()));
-        };
-
-        let builder = self
-            .client
-            .get(url)
-            .timeout(Duration::from_secs(30))
-            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
-            .header(
-                reqwest::header::ACCEPT,
-                HeaderValue::from_static("application/json"),
-            )
-            .header("X-PHYSNA-TENANTID", self.tenant.to_owned())
-            .query(&query);
+        let response = self.execute(builder).await;
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
-        Ok(self.handle_response::<FolderListPageResponse>(response)?)
+        Ok(self
+            .handle_response::<ModelVisualMatchResponse>(response)
+            .await?)
     }
 
-    pub fn get_list_of_folders(
+    async fn get_list_of_folders(
         &self,
         desired_folders: Option<HashSet<String>>,
     ) -> Result<FolderListResponse, ClientError> {
         log::trace!("Reading list of folders...");
 
-        let mut current_page: u32 = 1;
-        let per_page: u32 = 1000;
         let filter: Option<String> = match desired_folders {
             Some(desired_folders) => {
                 let folder_filter: Vec<String> = desired_folders
@@ -742,20 +1731,18 @@ impl ApiClient {
             None => Some(String::default()),
         };
 
-        let mut folders: Vec<Folder> = Vec::new();
-        loop {
-            let page = self.get_list_of_folders_page(current_page, per_page, filter.to_owned())?;
-            folders.extend(page.folders);
-            if current_page >= page.page_data.last_page {
-                break;
-            }
-            current_page += 1;
-        }
+        self.list_folders(filter, None).await?.into_modified()
+    }
 
-        Ok(FolderListResponse { folders })
+    async fn get_list_of_folders_conditional(
+        &self,
+        etag: Option<String>,
+    ) -> Result<Conditional<FolderListResponse>, ClientError> {
+        log::trace!("Reading list of folders...");
+        self.list_folders(Some(String::default()), etag).await
     }
 
-    pub fn delete_folder(&self, folders: &HashSet<u32>) -> Result<(), ClientError> {
+    async fn delete_folder(&self, folders: &HashSet<u32>) -> Result<(), ClientError> {
         log::trace!("Deleting folder {:?}...", folders);
         let url = format!("{}/v2/folders", self.base_url);
         let mut query_parameters: Vec<(String, String)> = Vec::new();
@@ -764,46 +1751,85 @@ impl ApiClient {
             query_parameters.push(("ids".to_string(), folder.to_string()));
         }
 
-        if query_parameters.len() > 0 {
+        if !query_parameters.is_empty() {
             let builder = self
                 .client
                 .delete(url)
-                .timeout(Duration::from_secs(180))
+                .timeout(self.query_timeout(180))
                 .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
                 .header("X-PHYSNA-TENANTID", self.tenant.to_owned())
                 .query(&query_parameters)
                 .json(&folders);
 
-            let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-            log::trace!("DELETE {}", request.url());
-            let response = self.client.execute(request);
-            self.handle_response::<()>(response)
+            let response = self.execute(builder).await;
+            self.handle_response::<()>(response).await
         } else {
             Err(ClientError::InvalidFolderName)
         }
     }
 
-    pub fn create_folder(&self, name: &String) -> Result<FolderCreateResponse, ClientError> {
+    async fn create_folder(&self, name: &str) -> Result<FolderCreateResponse, ClientError> {
         log::trace!("Creating folder {}...", &name);
         let url = format!("{}/v2/folders", self.base_url);
 
         let builder = self
             .client
             .post(url)
-            .timeout(Duration::from_secs(30))
+            .timeout(self.query_timeout(30))
+            .header("cache-control", "no-cache")
+            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
+            .header("X-PHYSNA-TENANTID", &self.tenant)
+            .header("Content-Length", 0)
+            .query(&[("name", name.to_owned())]);
+
+        let response = self.execute(builder).await;
+        self.handle_response::<FolderCreateResponse>(response).await
+    }
+
+    async fn rename_folder(
+        &self,
+        id: &u32,
+        name: &str,
+    ) -> Result<FolderCreateResponse, ClientError> {
+        log::trace!("Renaming folder {} to {}...", id, &name);
+        let url = format!("{}/v2/folders/{}", self.base_url, id);
+
+        let builder = self
+            .client
+            .put(url)
+            .timeout(self.query_timeout(30))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
             .header("Content-Length", 0)
             .query(&[("name", name.to_owned())]);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("POST {}", request.url());
-        let response = self.client.execute(request);
-        self.handle_response::<FolderCreateResponse>(response)
+        let response = self.execute(builder).await;
+        // the folder exists, so Not Found means that the API has no endpoint to rename it
+        if let Ok(response) = &response {
+            if matches!(
+                response.status(),
+                StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            ) {
+                return Err(ClientError::Unsupported(String::from(
+                    "the API of the tenant does not support renaming folders",
+                )));
+            }
+        }
+        self.handle_response::<FolderCreateResponse>(response).await
+    }
+
+    async fn get_model(&self, uuid: &Uuid) -> Result<SingleModelResponse, ClientError> {
+        self.get_model_conditional(uuid, None)
+            .await?
+            .into_modified()
     }
 
-    pub fn get_model(&self, uuid: &Uuid) -> Result<SingleModelResponse, ClientError> {
+    async fn get_model_conditional(
+        &self,
+        uuid: &Uuid,
+        etag: Option<String>,
+    ) -> Result<Conditional<SingleModelResponse>, ClientError> {
         let url = format!(
             "{}/v2/models/{id}",
             self.base_url,
@@ -814,18 +1840,18 @@ impl ApiClient {
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
+        let builder = if_none_match(builder, etag);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-        Ok(self.handle_response::<SingleModelResponse>(response)?)
+        self.handle_conditional_response::<SingleModelResponse>(response)
+            .await
     }
 
-    pub fn delete_model(&self, uuid: &Uuid) -> Result<(), ClientError> {
+    async fn delete_model(&self, uuid: &Uuid) -> Result<(), ClientError> {
         let url = format!(
             "{}/v2/models/{id}",
             self.base_url,
@@ -836,63 +1862,69 @@ impl ApiClient {
         let builder = self
             .client
             .delete(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("DELETE {}", request.url());
-        let response = self.client.execute(request);
-        self.handle_response::<()>(response)
+        let response = self.execute(builder).await;
+        self.handle_response::<()>(response).await
     }
 
-    pub fn reprocess_model(&self, uuid: &Uuid) -> Result<(), ClientError> {
-        let url = format!("{}/v2/models/{}/reprocess", self.base_url, uuid.to_string());
+    async fn reprocess_model(&self, uuid: &Uuid) -> Result<(), ClientError> {
+        let url = format!("{}/v2/models/{}/reprocess", self.base_url, uuid);
         log::trace!("Reprocessing model {}", url);
 
         let builder = self
             .client
             .post(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("Content-Length", 0)
             .header("X-PHYSNA-TENANTID", &self.tenant);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("POST {}", request.url());
-        let response = self.client.execute(request);
-        self.handle_response::<()>(response)
+        let response = self.execute(builder).await;
+        self.handle_response::<()>(response).await
     }
 
-    pub fn get_model_metadata(&self, uuid: &Uuid) -> Result<Option<ModelMetadata>, ClientError> {
+    async fn get_model_metadata(&self, uuid: &Uuid) -> Result<Option<ModelMetadata>, ClientError> {
+        self.get_model_metadata_conditional(uuid, None)
+            .await?
+            .into_modified()
+    }
+
+    async fn get_model_metadata_conditional(
+        &self,
+        uuid: &Uuid,
+        etag: Option<String>,
+    ) -> Result<Conditional<Option<ModelMetadata>>, ClientError> {
         let url = format!(
             "{}/v2/models/{id}/metadata",
             self.base_url,
             id = urlencode(uuid.to_string())
         );
-        let per_page = 10000;
+        let per_page = self.metadata_page_size;
         let page = 1;
 
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .query(&[
                 ("perPage", per_page.to_string().as_str()),
                 ("page", page.to_string().as_str()),
             ])
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
+        let builder = if_none_match(builder, etag);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-        let response: Option<ModelMetadataResponse> =
-            self.handle_response::<Option<ModelMetadataResponse>>(response)?;
+        let response = self
+            .handle_conditional_response::<Option<ModelMetadataResponse>>(response)
+            .await?;
 
-        match response {
+        Ok(response.map(|response| match response {
             Some(response) => {
                 if !response.metadata.is_empty() {
                     let props: Vec<ModelMetadataItem> = response
@@ -902,16 +1934,16 @@ impl ApiClient {
                             ModelMetadataItem::new(property.key_id, property.name, property.value)
                         })
                         .collect();
-                    return Ok(Some(ModelMetadata::new(props)));
+                    Some(ModelMetadata::new(props))
                 } else {
-                    return Ok(None);
+                    None
                 }
             }
-            None => Ok(None),
-        }
+            None => None,
+        }))
     }
 
-    pub fn get_assembly_tree_for_model(&self, uuid: &Uuid) -> Result<AssemblyTree, ClientError> {
+    async fn get_assembly_tree_for_model(&self, uuid: &Uuid) -> Result<AssemblyTree, ClientError> {
         let url = format!(
             "{}/v2/models/{id}/assembly-tree",
             self.base_url,
@@ -921,28 +1953,18 @@ impl ApiClient {
         let builder = self
             .client
             .post(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
             .header("scope", "tenantApp")
             .header("Content-Length", 0);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("POST {}", request.url());
-        let response = self.client.execute(request);
-        Ok(self.handle_response::<AssemblyTree>(response)?)
+        let response = self.execute(builder).await;
+        Ok(self.handle_response::<AssemblyTree>(response).await?)
     }
 
-    /// Returns a single-page response for list of models
-    ///
-    /// Parameters:
-    ///
-    /// folders - a list of folder IDs. If the list is empty, models from all folders will be included
-    /// search - a search clause (e.g. part number)
-    /// per_page - how many records to return per page
-    /// page - the current page number
-    pub fn get_list_of_models_page(
+    async fn get_list_of_models_page(
         &self,
         folders: Option<HashSet<u32>>,
         search: Option<&String>,
@@ -953,21 +1975,16 @@ impl ApiClient {
 
         let mut query_parameters: Vec<(String, String)> = Vec::new();
 
-        match folders {
-            Some(folders) => {
-                if folders.len() > 0 {
-                    let filter: Vec<String> = folders.iter().map(|f| f.to_string()).collect();
-                    let filter_operations = format!("folderId(in({}))", filter.join(","));
+        if let Some(folders) = folders.filter(|folders| !folders.is_empty()) {
+            let filter: Vec<String> = folders.iter().map(|f| f.to_string()).collect();
+            let filter_operations = format!("folderId(in({}))", filter.join(","));
 
-                    log::trace!("Filter Operations: {}", filter_operations.to_owned());
-                    query_parameters.push(("filter".to_string(), filter_operations));
-                }
-            }
-            None => (),
+            log::trace!("Filter Operations: {}", filter_operations);
+            query_parameters.push(("filter".to_string(), filter_operations));
         }
 
-        if search.is_some() {
-            query_parameters.push(("search".to_string(), search.unwrap().to_owned()));
+        if let Some(search) = search {
+            query_parameters.push(("search".to_string(), search.to_owned()));
         }
 
         query_parameters.push(("perPage".to_string(), per_page.to_string()));
@@ -977,7 +1994,7 @@ impl ApiClient {
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(60))
+            .timeout(self.query_timeout(60))
             .header("Cache-Control", "no-cache")
             .header("Content-Length", "0")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
@@ -985,93 +2002,17 @@ impl ApiClient {
             .header("scope", "tenantApp")
             .query(&query_parameters);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
-        self.handle_response::<ModelListResponse>(response)
-    }
-
-    /// Checks the response from an HTTP operation for errors and if none, parses the response body into specific type
-    ///
-    /// Parameters:
-    ///
-    /// response - thre result from the response
-    fn handle_response<'de, T>(
-        &self,
-        response: Result<Response, reqwest::Error>,
-    ) -> Result<T, ClientError>
-    where
-        T: DeserializeOwned + 'static,
-    {
-        log::trace!("Analyzing HTTP response...");
-        match response {
-            Ok(response) => {
-                log::trace!("Evaluating the HTTP status ({})...", response.status());
-
-                match self.evaluate_response(&response) {
-                    Ok(_) => {
-                        // normal exit status from the HTTP operation
-                        log::trace!("The exit status code indicates normal operation");
-
-                        let exit_status = &response.status();
-                        let json = &response.text();
-
-                        match json {
-                            Ok(json) => {
-                                log::trace!(
-                                    "HTTP response [{}]: \"{}\"",
-                                    exit_status.to_string(),
-                                    json.to_owned()
-                                );
-                                if std::any::TypeId::of::<T>() == std::any::TypeId::of::<()>() {
-                                    // Correctly return `()` for `T`
-                                    unsafe { return Ok(std::mem::transmute_copy(&())) }
-                                } else {
-                                    let object = serde_json::from_str::<T>(&json)?;
-                                    Ok(object)
-                                }
-                            }
-                            Err(e) => Err(ClientError::ServerError(e.to_string())),
-                        }
-                    }
-                    Err(e) => {
-                        // the response status indicates an error
-                        let exit_status = &response.status();
-                        // attempting to get the message sent by the server...
-                        let json = response.text();
-                        match json {
-                            Ok(json) => {
-                                // the response has a payload
-                                log::trace!(
-                                    "HTTP response [{}]: {}",
-                                    exit_status.to_string(),
-                                    json.to_owned()
-                                );
-
-                                match serde_json::from_str::<ServerError>(&json) {
-                                    Ok(server_error) => Err(ClientError::ServerError(
-                                        server_error.error_details.message,
-                                    )),
-                                    Err(_) => Err(e),
-                                }
-                            }
-                            Err(_) => Err(e),
-                        }
-                    }
-                }
-            }
-            Err(e) => Err(ClientError::ServerError(e.to_string())),
-        }
+        let response = self.execute(builder).await;
+        self.handle_response::<ModelListResponse>(response).await
     }
 
-    pub fn upload_model(&self, folder: &str, path: &PathBuf) -> Result<Option<Model>, ClientError> {
+    async fn upload_model(&self, folder: &str, path: &Path) -> Result<Option<Model>, ClientError> {
         let url = format!("{}/v2/models", self.base_url);
 
-        let name = path.file_name();
-        if name.is_none() {
-            return Err(ClientError::InvalidInputFile);
-        }
-        let name = String::from(name.unwrap().to_string_lossy());
+        let name = match path.file_name() {
+            Some(name) => String::from(name.to_string_lossy()),
+            None => return Err(ClientError::InvalidInputFile),
+        };
 
         log::trace!("Uploading model data...");
         let request = ModelUploadRequest::new(folder, name.as_str());
@@ -1080,7 +2021,7 @@ impl ApiClient {
         let builder = self
             .client
             .post(url)
-            .timeout(Duration::from_secs(360))
+            .timeout(self.upload_timeout(360))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
@@ -1088,40 +2029,34 @@ impl ApiClient {
             //.header("Content-Range", range_value.to_owned())
             .json(&request);
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
-        let response: ModelUploadResponse =
-            self.handle_response::<ModelUploadResponse>(response)?;
+        let response = self.execute(builder).await;
+        let response: ModelUploadResponse = self
+            .handle_response::<ModelUploadResponse>(response)
+            .await?;
 
         log::trace!("Response: {:?}", response);
 
-        let response_model = response.models.get(0);
+        let response_model = response.models.first();
         match response_model {
             Some(response_model) => {
                 let response_model = response_model.to_owned();
                 let url = response_model.info.url;
                 let model = response_model.model;
                 let mut headers: HeaderMap = HeaderMap::new();
-                response_model.info.headers.into_iter().for_each(|(k, v)| {
+                for (k, v) in response_model.info.headers.into_iter() {
                     let header_name: HeaderName = CustomHeaderName::from(k.to_owned())
                         .into_header_name()
-                        .unwrap();
-                    let header_value: HeaderValue = HeaderValue::from_str(v.as_str()).unwrap();
+                        .ok_or_else(|| {
+                            ClientError::Parsing(format!("Invalid header name {}", k))
+                        })?;
+                    let header_value: HeaderValue =
+                        HeaderValue::from_str(v.as_str()).map_err(|_| {
+                            ClientError::Parsing(format!("Invalid value of header {}", k))
+                        })?;
                     headers.append(header_name, header_value);
-                });
-
-                let mut file = std::fs::File::open(path)?;
-                let mut buffer = Vec::new();
-                file.read_to_end(&mut buffer)?;
+                }
 
-                let _ = self
-                    .client
-                    .put(url)
-                    .timeout(Duration::from_secs(180))
-                    .headers(headers)
-                    .body(buffer)
-                    .send();
+                self.upload_source_file(&url, headers, path).await?;
 
                 Ok(Some(model.to_owned()))
             }
@@ -1129,180 +2064,202 @@ impl ApiClient {
         }
     }
 
-    pub fn download_model(&self, uuid: &Uuid) -> Result<(), ClientError> {
-        let url = format!(
-            "{}/v2/models/{}/source-file",
-            self.base_url,
-            uuid.to_string()
-        );
-        let bearer: String = format!("Bearer {}", self.access_token);
+    async fn download_model(&self, uuid: &Uuid) -> Result<(), ClientError> {
+        let path = dirs::download_dir().ok_or(ClientError::Unsupported(
+            "Cannot determine the download directory".to_string(),
+        ))?;
+        self.download_model_to(uuid, &path).await?;
+        Ok(())
+    }
+
+    async fn download_model_to(
+        &self,
+        uuid: &Uuid,
+        directory: &Path,
+    ) -> Result<PathBuf, ClientError> {
+        let url = format!("{}/v2/models/{}/source-file", self.base_url, uuid);
         log::trace!("Downloading model source file...");
 
         log::trace!("GET {}", url.to_string());
-        let response = self
+        let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(360))
-            .header("Authorization", bearer)
+            .timeout(self.upload_timeout(360))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
-            .header("scope", "tenantApp")
-            .send();
+            .header("scope", "tenantApp");
+        let response = self.execute(builder).await;
 
-        let response_source_file = self.handle_response::<SourceFileResponse>(response)?;
+        let response_source_file = self.handle_response::<SourceFileResponse>(response).await?;
         let url = response_source_file.source_file_url;
 
         let url_for_path = url.clone();
-        let file_name = url_for_path.path_segments().unwrap().next_back().unwrap();
+        let file_name = url_for_path
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .ok_or(ClientError::CannotExtractFileNameFromPath)?;
         log::trace!("Extraced file name is {}", file_name.to_owned());
 
         log::trace!("GET {}", url.to_string());
-        let response = self
+        let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(120))
+            .timeout(self.upload_timeout(120))
             .header("cache-control", "no-cache")
-            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
-            .send()?;
+            .header(reqwest::header::USER_AGENT, APP_USER_AGENT);
+        let response = self.transfer(builder).await?;
 
         log::trace!("Download request is a success");
 
-        let path = dirs::download_dir().unwrap();
-        let path = path.join(file_name);
+        let path = directory.join(file_name);
 
         log::trace!("Downloading file {}", path.to_string_lossy());
 
-        let body = response.bytes()?;
-        std::fs::write(path, &body)?;
+        let body = response.bytes().await?;
+        std::fs::write(&path, &body)?;
 
         log::trace!("File downloaded");
 
-        Ok(())
+        Ok(path)
     }
 
-    pub fn get_list_of_properties(&self) -> Result<PropertyCollection, ClientError> {
+    async fn get_list_of_properties(&self) -> Result<PropertyCollection, ClientError> {
         let url = format!("{}/v2/metadata-keys", self.base_url);
 
         let builder = self
             .client
             .request(reqwest::Method::GET, url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-        Ok(self.handle_response::<PropertyCollection>(response)?)
+        Ok(self.handle_response::<PropertyCollection>(response).await?)
     }
 
-    pub fn post_property(&self, name: &String) -> Result<Property, ClientError> {
+    async fn post_property(&self, name: &str) -> Result<Property, ClientError> {
         let url = format!("{}/v2/metadata-keys", self.base_url);
-        let bearer: String = format!("Bearer {}", self.access_token);
 
-        log::trace!(
-            "Registering a new property with name of \"{}\"...",
-            name.clone()
-        );
+        log::trace!("Registering a new property with name of \"{}\"...", name);
         log::trace!("POST {}", url);
 
         let request = PropertyRequest::new(name.to_owned());
         log::trace!("Request: {:?}", &request);
 
-        let response = self
+        let builder = self
             .client
             .post(url)
-            .timeout(Duration::from_secs(180))
-            .header("Authorization", bearer)
+            .timeout(self.query_timeout(180))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
             .header("scope", "tenantApp")
             //.header("Content-Range", range_value.to_owned())
-            .json(&request)
-            .send();
+            .json(&request);
+
+        let response = self.execute(builder).await;
 
-        let result = self.handle_response::<PropertyResponse>(response)?;
+        let result = self.handle_response::<PropertyResponse>(response).await?;
         Ok(result.property)
     }
 
-    pub fn put_model_property(
+    async fn put_model_property(
         &self,
         model_uuid: &Uuid,
         id: &u64,
         item: &ModelMetadataItem,
     ) -> Result<ModelMetadataItem, ClientError> {
         let url = format!("{}/v2/models/{}/metadata/{}", self.base_url, model_uuid, id);
-        let bearer: String = format!("Bearer {}", self.access_token);
 
         log::trace!("PUT {}", url);
 
-        let response = self
+        let builder = self
             .client
             .put(url)
-            .timeout(Duration::from_secs(180))
-            .header("Authorization", bearer)
+            .timeout(self.query_timeout(180))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
             .header("scope", "tenantApp")
             //.header("Content-Range", range_value.to_owned())
-            .json(&PropertyValueRequest::new(item.value.to_owned()))
-            .send();
+            .json(&PropertyValueRequest::new(item.value.to_owned()));
 
-        let result = self.handle_response::<ModelCreateMetadataResponse>(response)?;
+        let response = self.execute(builder).await;
+
+        let result = self
+            .handle_response::<ModelCreateMetadataResponse>(response)
+            .await?;
         Ok(result.metadata)
     }
 
-    pub fn delete_model_property(&self, model_uuid: &Uuid, id: &u64) -> Result<(), ClientError> {
+    async fn delete_model_property(&self, model_uuid: &Uuid, id: &u64) -> Result<(), ClientError> {
         let url = format!("{}/v2/models/{}/metadata/{}", self.base_url, model_uuid, id);
-        let bearer: String = format!("Bearer {}", self.access_token);
 
         log::trace!("DELETE {}", url);
 
-        let response = self
+        let builder = self
             .client
             .delete(url)
-            .timeout(Duration::from_secs(180))
-            .header("Authorization", bearer)
+            .timeout(self.query_timeout(180))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
-            .header("scope", "tenantApp")
-            //.header("Content-Range", range_value.to_owned())
-            .send();
+            .header("scope", "tenantApp");
+        //.header("Content-Range", range_value.to_owned())
+
+        let response = self.execute(builder).await;
+
+        self.handle_response::<()>(response).await
+    }
+
+    async fn delete_property(&self, id: &u64) -> Result<(), ClientError> {
+        let url = format!("{}/v2/metadata-keys/{}", self.base_url, id);
+
+        log::trace!("DELETE {}", url);
+
+        let builder = self
+            .client
+            .delete(url)
+            .timeout(self.query_timeout(180))
+            .header("cache-control", "no-cache")
+            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
+            .header("X-PHYSNA-TENANTID", &self.tenant)
+            .header("scope", "tenantApp");
 
-        self.handle_response::<()>(response)
+        let response = self.execute(builder).await;
+
+        self.handle_response::<()>(response).await
     }
 
-    pub fn get_image_upload_specs(&self, path: &Path) -> Result<ImageUploadResponse, ClientError> {
+    async fn get_image_upload_specs(
+        &self,
+        path: &Path,
+    ) -> Result<ImageUploadResponse, ClientError> {
         if !path.is_file() {
             return Err(ClientError::InputNotFile);
         }
 
         let url = format!("{}/v2/images", self.base_url);
-        let bearer: String = format!("Bearer {}", self.access_token);
 
         let filename = match path.file_name() {
-            Some(filename) => filename.to_str().unwrap(),
+            Some(filename) => filename.to_string_lossy(),
             None => return Err(ClientError::CannotExtractFileNameFromPath),
         };
 
         log::trace!("Requesting upload specs for image {}", &filename);
-        let response = self
+        let builder = self
             .client
             .post(url)
-            .timeout(Duration::from_secs(180))
-            .header("Authorization", bearer)
+            .timeout(self.query_timeout(180))
             .header("cache-control", "no-cache")
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", &self.tenant)
             .header("scope", "tenantApp")
-            .json(&ImageUploadSpecsRequest::new(filename.to_string()))
-            .send()?;
-        let json = response.text().unwrap();
+            .json(&ImageUploadSpecsRequest::new(filename.to_string()));
+        let response = self.execute(builder).await?;
+        let json = response.text().await?;
         //trace!("{}", json);
 
         let file_size_requirements: ImageUploadSpecsResponse = serde_json::from_str(&json)?;
@@ -1311,7 +2268,7 @@ impl ApiClient {
         Ok(file_size_requirements.image)
     }
 
-    pub fn upload_image_file(
+    async fn upload_image_file(
         &self,
         url: Url,
         upload_size_requirements: ImageUploadSizeRequirements,
@@ -1319,69 +2276,29 @@ impl ApiClient {
         mime: String,
         content_range: String,
     ) -> Result<(), ClientError> {
-        log::trace!("Uploading image file {}...", path.to_str().unwrap());
+        log::trace!("Uploading image file {}...", path.display());
         //trace!("Upload URL: {}", url.to_string());
 
         let max_size = upload_size_requirements.max_size_in_bytes;
-        let file = File::open(path)?;
-        let file_size = file.metadata().unwrap().len();
+        let file = tokio::fs::File::open(path).await?;
+        let file_size = file.metadata().await?.len();
         if file_size > max_size {
             return Err(ClientError::FileTooLarge);
         }
 
-        let _response = self
+        let builder = self
             .client
             .put(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.upload_timeout(180))
             .header("Content-Type", mime)
             .header("X-Goog-Content-Length-Range", content_range)
-            .body(file)
-            .send()?;
+            .body(file);
+        let _response = self.transfer(builder).await?;
 
         Ok(())
     }
 
-    fn get_image_search_matches_page(
-        &self,
-        ids: Vec<String>,
-        search: Option<&String>,
-        filter: Option<&String>,
-        page: u32,
-        per_page: u32,
-    ) -> Result<ImageMatchPageResponse, ClientError> {
-        let url = format!("{}/v2/images/model-matches", self.base_url);
-        let mut query_parameters: Vec<(String, String)> = Vec::new();
-        for id in ids {
-            query_parameters.push(("id".to_string(), id));
-        }
-        query_parameters.push(("perPage".to_string(), per_page.to_string()));
-        query_parameters.push(("page".to_string(), page.to_string()));
-
-        match search {
-            Some(search) => query_parameters.push(("search".to_string(), search.to_owned())),
-            None => (),
-        }
-        match filter {
-            Some(filter) => query_parameters.push(("filter".to_string(), filter.to_owned())),
-            None => (),
-        }
-
-        let builder = self
-            .client
-            .request(reqwest::Method::GET, url)
-            .query(&query_parameters)
-            .timeout(Duration::from_secs(180))
-            .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
-            .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
-
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
-
-        Ok(self.handle_response::<ImageMatchPageResponse>(response)?)
-    }
-
-    pub fn get_image_search_maches(
+    async fn get_image_search_maches(
         &self,
         id: Vec<String>,
         search: Option<&String>,
@@ -1396,18 +2313,11 @@ impl ApiClient {
         log::trace!("Limit={}", max_matches);
 
         loop {
-            let page_result = self.get_image_search_matches_page(
-                id.clone(),
-                search.clone(),
-                filter.clone(),
-                page,
-                per_page,
-            )?;
-            let page_models: Vec<Model> = page_result
-                .matches
-                .into_iter()
-                .map(|m| Model::from(m.model))
-                .collect();
+            let page_result = self
+                .get_image_search_matches_page(id.clone(), search, filter, page, per_page)
+                .await?;
+            let page_models: Vec<Model> =
+                page_result.matches.into_iter().map(|m| m.model).collect();
             let local_size = page_models.len();
             models.extend(page_models);
             if models.len() > max_matches as usize {
@@ -1427,22 +2337,20 @@ impl ApiClient {
         Ok(ListOfModels::from(models))
     }
 
-    pub fn get_list_of_users(&self) -> Result<ListOfUsers, ClientError> {
+    async fn get_list_of_users(&self) -> Result<ListOfUsers, ClientError> {
         let url = format!("{}/v2/users", self.base_url,);
 
         let builder = self
             .client
             .get(url)
-            .timeout(Duration::from_secs(180))
+            .timeout(self.query_timeout(180))
             .header(reqwest::header::USER_AGENT, APP_USER_AGENT)
             .header("X-PHYSNA-TENANTID", self.tenant.to_owned());
 
-        let request = builder.bearer_auth(self.access_token.to_owned()).build()?;
-        log::trace!("GET {}", request.url());
-        let response = self.client.execute(request);
+        let response = self.execute(builder).await;
 
-        let users = self.handle_response::<ListOfUsers>(response)?;
-        Ok(ListOfUsers::from(users))
+        let users = self.handle_response::<ListOfUsers>(response).await?;
+        Ok(users)
     }
 }
 
@@ -1451,6 +2359,29 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_curl_command() {
+        let request = reqwest::Client::new()
+            .post("https://api.physna.com/v2/metadata-keys")
+            .bearer_auth("secret")
+            .header("X-PHYSNA-TENANTID", "tenant")
+            .timeout(Duration::from_secs(30))
+            .body(r#"{"name":"it's"}"#)
+            .build()
+            .unwrap();
+
+        let command = curl_command(&request);
+        assert!(!command.contains("secret"));
+        assert_eq!(
+            command,
+            "curl -X POST 'https://api.physna.com/v2/metadata-keys' \\\n  \
+             -H \"authorization: Bearer $PCLI_TOKEN\" \\\n  \
+             -H 'x-physna-tenantid: tenant' \\\n  \
+             --max-time 30 \\\n  \
+             --data-raw '{\"name\":\"it'\\''s\"}'"
+        );
+    }
+
     #[test]
     fn test_deserialization_of_model_with_metadata() {
         let json = r#"
@@ -1517,7 +2448,7 @@ mod tests {
 }
         "#;
 
-        let result = serde_json::from_str::<ModelListResponse>(&json);
+        let result = serde_json::from_str::<ModelListResponse>(json);
         match result {
             Ok(_models) => (),
             Err(e) => panic!("Parsing of JSON failed: {}", e),
@@ -3,7 +3,7 @@ use crate::{model, token};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -30,15 +30,33 @@ pub fn from_client_configuration(
     })
 }
 
+/// The name of the configuration file in the home directory
+const HOME_CONFIGURATION_FILE: &str = ".pcli.conf";
+
+/// Returns the location of the configuration file when none is specified. A file in the XDG configuration directory
+/// ($XDG_CONFIG_HOME/pcli/pcli.conf, or ~/.config/pcli/pcli.conf) is used if it exists, otherwise ~/.pcli.conf
+pub fn default_path() -> Option<PathBuf> {
+    let home = dirs::home_dir();
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| home.as_ref().map(|home| home.join(".config")));
+
+    match config_home.map(|directory| directory.join("pcli").join("pcli.conf")) {
+        Some(path) if path.is_file() => Some(path),
+        _ => home.map(|home| home.join(HOME_CONFIGURATION_FILE)),
+    }
+}
+
 /// Reads the client configuration from a file
-pub fn initialize(configuration: &String) -> Result<ClientConfiguration, ConfigurationError> {
-    let configuration = Path::new(configuration.as_str());
+pub fn initialize(configuration: &str) -> Result<ClientConfiguration, ConfigurationError> {
+    let configuration = Path::new(configuration);
     let configuration = read_to_string(configuration)?;
     Ok(serde_yaml::from_str(&configuration)?)
 }
 
 /// Represents a Physna tenant
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tenant {
     #[serde(default)]
     pub client_id: String,
@@ -46,12 +64,273 @@ pub struct Tenant {
     pub client_secret: Option<String>,
     #[serde(default)]
     pub page_size: Option<u32>,
+    /// The number of folders requested per page
+    #[serde(default)]
+    pub folders_page_size: Option<u32>,
+    /// The number of metadata properties requested for a model. The API returns only the first page
+    #[serde(default)]
+    pub metadata_page_size: Option<u32>,
+    #[serde(default)]
+    pub token_store: TokenStore,
+    #[serde(default)]
+    pub proxy: Option<ProxyConfiguration>,
+    /// Overrides the file extensions accepted for upload to this tenant
+    #[serde(default)]
+    pub upload_extensions: Option<Vec<String>>,
+    /// Overrides the base URL of the web application in links to models, e.g. for a private deployment
+    #[serde(default)]
+    pub web_url: Option<String>,
+    /// Overrides the default output settings for this tenant
+    #[serde(default)]
+    pub output: OutputConfiguration,
+}
+
+/// An HTTP, HTTPS or SOCKS5 proxy through which all requests for the tenant are sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfiguration {
+    /// The proxy URL, e.g. "http://proxy.mycompany.com:8080" or "socks5://127.0.0.1:1080"
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl ProxyConfiguration {
+    pub fn new(url: String) -> Self {
+        ProxyConfiguration {
+            url,
+            username: None,
+            password: None,
+        }
+    }
+
+    /// Creates the proxy definition used by the HTTP client
+    pub fn to_proxy(&self) -> Result<reqwest::Proxy, reqwest::Error> {
+        let proxy = reqwest::Proxy::all(self.url.as_str())?;
+        Ok(match &self.username {
+            Some(username) => {
+                proxy.basic_auth(username, self.password.as_deref().unwrap_or_default())
+            }
+            None => proxy,
+        })
+    }
+}
+
+/// Where the access token and the client secret are cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStore {
+    /// Plaintext file in the home directory
+    #[default]
+    File,
+    /// The operating system's keyring
+    Keyring,
+}
+
+impl std::fmt::Display for TokenStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenStore::File => write!(f, "file"),
+            TokenStore::Keyring => write!(f, "keyring"),
+        }
+    }
 }
 
 /// The client configuration contains the base path, URL to the identity provider and the currently selected tenant
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClientConfiguration {
     pub base_path: String,
     pub identity_provider_url: String,
     pub tenants: HashMap<String, Tenant>,
+    /// Cached tokens that expire within this many seconds are refreshed before use
+    #[serde(default = "default_token_leeway")]
+    pub token_leeway: i64,
+    /// How many times a request that failed with a transient error (e.g. 429 Too Many Requests) is retried
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub http_timeouts: HttpTimeouts,
+    /// How many requests may be in flight at the same time. Commands that fan out expensive requests (e.g. matching)
+    /// are capped lower. Defaults to DEFAULT_CONCURRENCY
+    #[serde(default)]
+    pub jobs: Option<usize>,
+    /// Size in MiB of the chunks in which files are uploaded when the storage supports resumable uploads
+    #[serde(default)]
+    pub upload_chunk_size: Option<u64>,
+    /// The on-disk cache is only used if this section is present
+    #[serde(default)]
+    pub cache: Option<CacheConfiguration>,
+    #[serde(default)]
+    pub csv: CsvConfiguration,
+    /// Rules for highlighting the table and tree output, e.g. "state=FAILED:red"
+    #[serde(default)]
+    pub highlight: Vec<String>,
+    /// YAML or JSON file with the metadata properties allowed by "upload-model-meta --validate"
+    #[serde(default)]
+    pub metadata_schema: Option<PathBuf>,
+    /// File extensions accepted for upload by the commands that read a directory (e.g. "upload-many"). Defaults to
+    /// DEFAULT_UPLOAD_EXTENSIONS
+    #[serde(default)]
+    pub upload_extensions: Option<Vec<String>>,
+    /// Defaults for the "--format", "--pretty" and "--color" options
+    #[serde(default)]
+    pub output: OutputConfiguration,
+    /// Names for argument lists, e.g. "dups: match-folder --threshold 0.95 --format csv", which are used like commands
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
+impl ClientConfiguration {
+    /// The arguments the alias stands for, split like a shell does: at whitespace, except in single or double quotes
+    pub fn alias(&self, name: &str) -> Option<Vec<String>> {
+        let alias = self.aliases.get(name)?;
+        let mut arguments = Vec::new();
+        let mut argument: Option<String> = None;
+        let mut quote: Option<char> = None;
+        for c in alias.chars() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), c) => argument.get_or_insert_with(String::new).push(c),
+                (None, '\'' | '"') => {
+                    quote = Some(c);
+                    argument.get_or_insert_with(String::new);
+                }
+                (None, c) if c.is_whitespace() => arguments.extend(argument.take()),
+                (None, c) => argument.get_or_insert_with(String::new).push(c),
+            }
+        }
+        arguments.extend(argument);
+        Some(arguments)
+    }
+
+    /// The default output settings for the tenant. Each setting of the tenant takes precedence over the global one
+    pub fn output(&self, tenant: &str) -> OutputConfiguration {
+        let global = &self.output;
+        match self.tenants.get(tenant) {
+            Some(tenant) => OutputConfiguration {
+                format: tenant.output.format.to_owned().or(global.format.to_owned()),
+                pretty: tenant.output.pretty.or(global.pretty),
+                color: tenant.output.color.to_owned().or(global.color.to_owned()),
+            },
+            None => global.to_owned(),
+        }
+    }
+
+    /// The file extensions accepted for upload to the tenant, in lower case and without the leading dot. The tenant's
+    /// setting takes precedence over the global one
+    pub fn upload_extensions(&self, tenant: &str) -> Vec<String> {
+        let configured = self
+            .tenants
+            .get(tenant)
+            .and_then(|tenant| tenant.upload_extensions.as_ref())
+            .or(self.upload_extensions.as_ref());
+        match configured {
+            Some(extensions) => extensions
+                .iter()
+                .map(|extension| normalize_extension(extension))
+                .filter(|extension| !extension.is_empty())
+                .collect(),
+            None => DEFAULT_UPLOAD_EXTENSIONS
+                .iter()
+                .map(|extension| extension.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Default values of the output options, which the options on the command line and their environment variables
+/// override
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct OutputConfiguration {
+    /// The output format, e.g. "csv" or "table"
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub pretty: Option<bool>,
+    /// The color of the output, e.g. "green"
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// The file formats accepted by Physna
+pub const DEFAULT_UPLOAD_EXTENSIONS: [&str; 18] = [
+    "3ds",
+    "catpart",
+    "catproduct",
+    "glb",
+    "igs",
+    "iges",
+    "prt",
+    "x_b",
+    "x_t",
+    "asm",
+    "par",
+    "sldasm",
+    "sldprt",
+    "step",
+    "stp",
+    "stl",
+    "obj",
+    "jt",
+];
+
+/// Converts e.g. ".STEP" into "step"
+pub fn normalize_extension(extension: &str) -> String {
+    extension.trim().trim_start_matches('.').to_lowercase()
+}
+
+/// Default dialect of the CSV output. The command line options take precedence over these settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CsvConfiguration {
+    /// Field delimiter (defaults to ',')
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Quotes every field, not only those that need it
+    #[serde(default)]
+    pub quote_all: bool,
+    /// Ends lines with CRLF (the default) or, if false, with LF only
+    #[serde(default)]
+    pub crlf: Option<bool>,
+}
+
+/// Settings of the on-disk cache of models, folders and metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheConfiguration {
+    /// Location of the cache database (defaults to ~/.pcli/cache.db)
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// How long (in seconds) cached records are used before they are read again from the API
+    #[serde(default = "default_cache_ttl")]
+    pub ttl: u64,
+    /// Folders change more often than models, so the list of folders expires sooner
+    #[serde(default = "default_folders_ttl")]
+    pub folders_ttl: u64,
+}
+
+fn default_cache_ttl() -> u64 {
+    3600
+}
+
+fn default_folders_ttl() -> u64 {
+    300
+}
+
+/// Request timeouts in seconds. Operations without a configured value use their built-in default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HttpTimeouts {
+    /// Timeout for API queries (e.g. listing folders and models, matching)
+    #[serde(default)]
+    pub query: Option<u64>,
+    /// Timeout for file transfers (uploading and downloading models and images)
+    #[serde(default)]
+    pub upload: Option<u64>,
+}
+
+fn default_token_leeway() -> i64 {
+    60
+}
+
+fn default_max_retries() -> u32 {
+    crate::client::DEFAULT_MAX_RETRIES
 }
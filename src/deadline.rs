@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+/// Why a file or a model was skipped by a run that stopped at its deadline
+pub const NOT_STARTED: &str = "Not started, because the maximum duration of the run was exceeded";
+
+/// When a run must end. The operations that work through many files or models stop starting new ones once the
+/// deadline has passed, so that they can still report what they have done. By default, there is no deadline
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Limits the duration of the run, starting now
+    pub fn after(max_duration: Duration) -> Deadline {
+        Deadline(Some(Instant::now() + max_duration))
+    }
+
+    /// Returns true if the duration of the run is limited and its deadline has passed
+    pub fn exceeded(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Shortens the timeout so that it ends at the deadline at the latest
+    pub fn limit(&self, timeout: Duration) -> Duration {
+        match self.0 {
+            Some(deadline) => timeout.min(deadline.saturating_duration_since(Instant::now())),
+            None => timeout,
+        }
+    }
+}
+
+/// Reads a duration such as "90s", "45m", "2h" or "1h 30m"
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    match humantime::parse_duration(text.trim()) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        Ok(_) => Err(String::from("The duration must be longer than zero")),
+        Err(e) => Err(format!(
+            "Invalid duration '{}': {}. Use e.g. '90s', '45m' or '2h'",
+            text, e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1h 30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration(" 90s "), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("two hours").is_err());
+    }
+
+    #[test]
+    fn test_deadline() {
+        assert!(!Deadline::default().exceeded());
+        assert_eq!(
+            Deadline::default().limit(Duration::from_secs(60)),
+            Duration::from_secs(60)
+        );
+        assert!(Deadline::after(Duration::ZERO).exceeded());
+        assert!(
+            Deadline::after(Duration::from_secs(3600)).limit(Duration::from_secs(7200))
+                <= Duration::from_secs(3600)
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::client::{ClientError, RequestFailure};
+use crate::format::FormatError;
+use crate::model::ParsingError;
+use crate::service::ApiError;
+use crate::token::TokenError;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::error::Error;
+use std::sync::OnceLock;
+
+/// How errors are reported on STDERR
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// The format selected with `set_format`
+static FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+/// Selects how errors are reported on STDERR
+pub fn set_format(format: ErrorFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// The kinds of failures. Each kind terminates the process with its own exit code, so that scripts can
+/// tell them apart
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    BadRequest,
+    RateLimited,
+    Unavailable,
+    Timeout,
+    Network,
+    Server,
+    Protocol,
+    Configuration,
+    InputOutput,
+    Data,
+}
+
+impl ErrorKind {
+    pub fn exit_code(&self) -> exitcode::ExitCode {
+        match self {
+            ErrorKind::Unauthorized | ErrorKind::Forbidden => exitcode::NOPERM,
+            ErrorKind::NotFound => exitcode::NOINPUT,
+            ErrorKind::Conflict => exitcode::CANTCREAT,
+            ErrorKind::RateLimited
+            | ErrorKind::Unavailable
+            | ErrorKind::Timeout
+            | ErrorKind::Network => exitcode::TEMPFAIL,
+            ErrorKind::Server => exitcode::UNAVAILABLE,
+            ErrorKind::Protocol => exitcode::PROTOCOL,
+            ErrorKind::Configuration => exitcode::CONFIG,
+            ErrorKind::InputOutput => exitcode::IOERR,
+            ErrorKind::BadRequest | ErrorKind::Data => exitcode::DATAERR,
+        }
+    }
+
+    /// Transient failures may succeed if the command is run again later
+    pub fn retryable(&self) -> bool {
+        self.exit_code() == exitcode::TEMPFAIL
+    }
+
+    fn from_status(status: StatusCode) -> ErrorKind {
+        match status {
+            StatusCode::UNAUTHORIZED => ErrorKind::Unauthorized,
+            StatusCode::FORBIDDEN => ErrorKind::Forbidden,
+            StatusCode::NOT_FOUND => ErrorKind::NotFound,
+            StatusCode::CONFLICT => ErrorKind::Conflict,
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ErrorKind::BadRequest,
+            StatusCode::TOO_MANY_REQUESTS => ErrorKind::RateLimited,
+            StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Unavailable,
+            StatusCode::REQUEST_TIMEOUT => ErrorKind::Timeout,
+            status if status.is_server_error() => ErrorKind::Server,
+            _ => ErrorKind::Protocol,
+        }
+    }
+
+    fn from_http_error(error: &reqwest::Error) -> ErrorKind {
+        match error.status() {
+            Some(status) => ErrorKind::from_status(status),
+            None if error.is_timeout() => ErrorKind::Timeout,
+            None if error.is_decode() => ErrorKind::Protocol,
+            None => ErrorKind::Network,
+        }
+    }
+}
+
+/// A failure as reported by `--error-format json`
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub status: Option<u16>,
+    pub endpoint: Option<String>,
+    pub retryable: bool,
+    pub exit_code: exitcode::ExitCode,
+}
+
+impl ErrorReport {
+    /// Describes the error. The failed HTTP request is only attributed to errors reported by the server
+    pub fn new(
+        message: String,
+        error: &(dyn Error + 'static),
+        failure: Option<RequestFailure>,
+    ) -> ErrorReport {
+        let (mut kind, http) = classify(error);
+
+        // the server may explain an error with any status in its message and transport errors are reported as
+        // server errors too, so the failed request tells more precisely what happened
+        if let (Some(failure), true, ErrorKind::Server) = (&failure, http, kind) {
+            kind = match failure.status {
+                Some(status) => ErrorKind::from_status(status),
+                None if failure.timeout => ErrorKind::Timeout,
+                None => ErrorKind::Network,
+            };
+        }
+
+        let failure = failure.filter(|failure| {
+            http && match failure.status {
+                Some(status) => ErrorKind::from_status(status) == kind,
+                None => kind == ErrorKind::Timeout || kind == ErrorKind::Network,
+            }
+        });
+
+        ErrorReport {
+            kind,
+            message,
+            status: failure
+                .as_ref()
+                .and_then(|failure| failure.status)
+                .map(|status| status.as_u16()),
+            endpoint: failure.map(|failure| failure.endpoint),
+            retryable: kind.retryable(),
+            exit_code: kind.exit_code(),
+        }
+    }
+
+    /// Prints the report on STDERR in the selected format and returns the exit code
+    pub fn print(&self) -> exitcode::ExitCode {
+        match FORMAT.get().copied().unwrap_or_default() {
+            ErrorFormat::Text => eprintln!("{}", self.message),
+            ErrorFormat::Json => match serde_json::to_string(self) {
+                Ok(json) => eprintln!("{}", json),
+                Err(_) => eprintln!("{}", self.message),
+            },
+        }
+        self.exit_code
+    }
+}
+
+/// Determines the kind of the error and whether it was reported by the server (or on the way to it)
+fn classify(error: &(dyn Error + 'static)) -> (ErrorKind, bool) {
+    if let Some(error) = error.downcast_ref::<ApiError>() {
+        return match error {
+            ApiError::ClientError(error) => classify(error),
+            ApiError::FolderNotFound(_) => (ErrorKind::NotFound, false),
+            ApiError::InputOutputError(_) => (ErrorKind::InputOutput, false),
+            ApiError::FormatError(error) => classify(error.as_ref()),
+            ApiError::ParsingError(error) => classify(error.as_ref()),
+            ApiError::TokenError(error) => classify(error),
+            ApiError::MissingSetting(_) => (ErrorKind::Configuration, false),
+            ApiError::DeadlineExceeded => (ErrorKind::Timeout, false),
+            _ => (ErrorKind::Data, false),
+        };
+    }
+
+    if let Some(error) = error.downcast_ref::<ClientError>() {
+        return match error {
+            ClientError::Unauthorized => (ErrorKind::Unauthorized, true),
+            ClientError::Forbidden => (ErrorKind::Forbidden, true),
+            ClientError::NotFound => (ErrorKind::NotFound, true),
+            ClientError::BadRequest => (ErrorKind::BadRequest, true),
+            ClientError::Conflict(_) => (ErrorKind::Conflict, true),
+            ClientError::ServerError(_)
+            | ClientError::Unsupported(_)
+            | ClientError::FailedToDeleteFolder(_) => (ErrorKind::Server, true),
+            ClientError::HttpError(error) => (ErrorKind::from_http_error(error), true),
+            ClientError::Parsing(_) | ClientError::JsonError(_) => (ErrorKind::Protocol, true),
+            ClientError::InputOutputError(_) => (ErrorKind::InputOutput, false),
+            ClientError::FailedToFindMatchesForImage => (ErrorKind::NotFound, false),
+            _ => (ErrorKind::Data, false),
+        };
+    }
+
+    if let Some(error) = error.downcast_ref::<TokenError>() {
+        return match error {
+            TokenError::HttpError(error) => (ErrorKind::from_http_error(error), true),
+            TokenError::InputOutputError(_) => (ErrorKind::InputOutput, false),
+            TokenError::UnknownTenant(_)
+            | TokenError::EmptyClientId
+            | TokenError::NoHomeDirectory => (ErrorKind::Configuration, false),
+            _ => (ErrorKind::Unauthorized, false),
+        };
+    }
+
+    if let Some(error) = error.downcast_ref::<FormatError>() {
+        return match error {
+            FormatError::InputOutputError(_) => (ErrorKind::InputOutput, false),
+            FormatError::ParsingError(error) => classify(error.as_ref()),
+            _ => (ErrorKind::Data, false),
+        };
+    }
+
+    if let Some(ParsingError::InputOutputError(_)) = error.downcast_ref::<ParsingError>() {
+        return (ErrorKind::InputOutput, false);
+    }
+
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        return (ErrorKind::from_http_error(error), true);
+    }
+
+    if error.is::<std::io::Error>() {
+        return (ErrorKind::InputOutput, false);
+    }
+
+    (ErrorKind::Data, false)
+}
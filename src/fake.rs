@@ -0,0 +1,375 @@
+use crate::client::{
+    AssemblyTree, ClientError, Folder, FolderListResponse, ImageUploadResponse,
+    ImageUploadSizeRequirements, ModelListResponse, ModelVisualMatchResponse, PageData,
+    PartToPartMatch, PartToPartMatchResponse, PhysnaClient, SingleModelResponse,
+};
+use crate::model::{
+    FolderCreateResponse, ListOfModels, ListOfUsers, Model, ModelMetadata, ModelMetadataItem,
+    Property, PropertyCollection,
+};
+use async_trait::async_trait;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use url::Url;
+use uuid::Uuid;
+
+/// An in-memory tenant for testing the `Api` without a server. Operations that are not simulated fail as unsupported
+#[derive(Debug, Default)]
+pub(crate) struct FakeClient {
+    folders: Vec<Folder>,
+    models: HashMap<Uuid, Model>,
+    /// Matches of each model, with their match percentages
+    matches: HashMap<Uuid, Vec<(Uuid, f64)>>,
+    properties: RefCell<Vec<Property>>,
+    metadata: RefCell<HashMap<Uuid, Vec<ModelMetadataItem>>>,
+    /// The direct children of each assembly
+    assemblies: HashMap<Uuid, Vec<Uuid>>,
+}
+
+impl FakeClient {
+    pub fn new() -> FakeClient {
+        FakeClient::default()
+    }
+
+    pub fn with_folder(mut self, id: u32, name: &str) -> Self {
+        self.folders.push(Folder {
+            id,
+            name: name.to_owned(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Adds a part in the given state (e.g. "finished")
+    pub fn with_model(mut self, uuid: Uuid, name: &str, folder_id: u32, state: &str) -> Self {
+        let short_id = self.models.len() as u64 + 1;
+        self.models.insert(
+            uuid,
+            Model {
+                uuid,
+                name: name.to_owned(),
+                folder_id,
+                state: state.to_owned(),
+                short_id: Some(short_id),
+                ..Default::default()
+            },
+        );
+        self
+    }
+
+    pub fn with_metadata(self, uuid: &Uuid, name: &str, value: &str) -> Self {
+        let id = self.property_id(name);
+        self.metadata
+            .borrow_mut()
+            .entry(*uuid)
+            .or_default()
+            .push(ModelMetadataItem::new(
+                id,
+                name.to_owned(),
+                value.to_owned(),
+            ));
+        self
+    }
+
+    pub fn with_match(mut self, uuid: &Uuid, matched: &Uuid, percentage: f64) -> Self {
+        self.matches
+            .entry(*uuid)
+            .or_default()
+            .push((*matched, percentage));
+        self
+    }
+
+    /// Adds a component to the assembly, which must have been added as a model first
+    pub fn with_child(mut self, assembly: &Uuid, child: &Uuid) -> Self {
+        if let Some(model) = self.models.get_mut(assembly) {
+            model.is_assembly = true;
+        }
+        self.assemblies.entry(*assembly).or_default().push(*child);
+        self
+    }
+
+    fn assembly_tree(&self, uuid: &Uuid) -> AssemblyTree {
+        AssemblyTree {
+            uuid: uuid.to_string(),
+            children: self.assemblies.get(uuid).map(|children| {
+                children
+                    .iter()
+                    .map(|child| self.assembly_tree(child))
+                    .collect()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the ID of the property, defining it first if needed
+    fn property_id(&self, name: &str) -> u64 {
+        let mut properties = self.properties.borrow_mut();
+        match properties.iter().find(|p| p.name == name) {
+            Some(property) => property.id,
+            None => {
+                let id = properties.iter().map(|p| p.id).max().unwrap_or_default() + 1;
+                properties.push(Property {
+                    id,
+                    name: name.to_owned(),
+                });
+                id
+            }
+        }
+    }
+
+    fn single_page() -> PageData {
+        PageData {
+            current_page: 1,
+            last_page: 1,
+            ..Default::default()
+        }
+    }
+
+    fn unsupported<T>(operation: &str) -> Result<T, ClientError> {
+        Err(ClientError::Unsupported(format!(
+            "The fake client does not support {}",
+            operation
+        )))
+    }
+}
+
+#[async_trait(?Send)]
+impl PhysnaClient for FakeClient {
+    fn tenant(&self) -> &str {
+        "fake"
+    }
+
+    async fn get_model_match_page(
+        &self,
+        uuid: &Uuid,
+        threshold: f64,
+        _per_page: u32,
+        _page: u32,
+    ) -> Result<PartToPartMatchResponse, ClientError> {
+        let matches: Vec<PartToPartMatch> = self
+            .matches
+            .get(uuid)
+            .into_iter()
+            .flatten()
+            .filter(|(_, percentage)| *percentage >= threshold)
+            .filter_map(|(matched, percentage)| {
+                self.models.get(matched).map(|model| PartToPartMatch {
+                    matched_model: model.clone(),
+                    match_percentage: *percentage,
+                })
+            })
+            .collect();
+
+        Ok(PartToPartMatchResponse {
+            page_data: PageData {
+                total: matches.len() as u32,
+                ..FakeClient::single_page()
+            },
+            matches,
+        })
+    }
+
+    async fn get_model_scan_match_page(
+        &self,
+        uuid: &Uuid,
+        threshold: f64,
+        per_page: u32,
+        page: u32,
+    ) -> Result<PartToPartMatchResponse, ClientError> {
+        self.get_model_match_page(uuid, threshold, per_page, page)
+            .await
+    }
+
+    async fn get_model_visual_match_page(
+        &self,
+        _uuid: &Uuid,
+        _per_page: u32,
+        _page: u32,
+    ) -> Result<ModelVisualMatchResponse, ClientError> {
+        FakeClient::unsupported("visual matching")
+    }
+
+    async fn get_list_of_folders(
+        &self,
+        desired_folders: Option<HashSet<String>>,
+    ) -> Result<FolderListResponse, ClientError> {
+        let folders = self
+            .folders
+            .iter()
+            .filter(|f| {
+                desired_folders
+                    .as_ref()
+                    .is_none_or(|desired| desired.contains(&f.name))
+            })
+            .cloned()
+            .collect();
+        Ok(FolderListResponse { folders })
+    }
+
+    async fn delete_folder(&self, _folders: &HashSet<u32>) -> Result<(), ClientError> {
+        FakeClient::unsupported("deleting folders")
+    }
+
+    async fn create_folder(&self, _name: &str) -> Result<FolderCreateResponse, ClientError> {
+        FakeClient::unsupported("creating folders")
+    }
+
+    async fn rename_folder(
+        &self,
+        _id: &u32,
+        _name: &str,
+    ) -> Result<FolderCreateResponse, ClientError> {
+        FakeClient::unsupported("renaming folders")
+    }
+
+    async fn get_model(&self, uuid: &Uuid) -> Result<SingleModelResponse, ClientError> {
+        match self.models.get(uuid) {
+            Some(model) => Ok(SingleModelResponse {
+                model: Box::new(model.clone()),
+            }),
+            None => Err(ClientError::NotFound),
+        }
+    }
+
+    async fn delete_model(&self, _uuid: &Uuid) -> Result<(), ClientError> {
+        FakeClient::unsupported("deleting models")
+    }
+
+    async fn reprocess_model(&self, _uuid: &Uuid) -> Result<(), ClientError> {
+        FakeClient::unsupported("reprocessing models")
+    }
+
+    async fn get_model_metadata(&self, uuid: &Uuid) -> Result<Option<ModelMetadata>, ClientError> {
+        Ok(self
+            .metadata
+            .borrow()
+            .get(uuid)
+            .map(|properties| ModelMetadata::new(properties.clone())))
+    }
+
+    async fn get_assembly_tree_for_model(&self, uuid: &Uuid) -> Result<AssemblyTree, ClientError> {
+        Ok(self.assembly_tree(uuid))
+    }
+
+    async fn get_list_of_models_page(
+        &self,
+        folders: Option<HashSet<u32>>,
+        search: Option<&String>,
+        _per_page: u32,
+        _page: u32,
+    ) -> Result<ModelListResponse, ClientError> {
+        let models: Vec<Model> = self
+            .models
+            .values()
+            .filter(|m| {
+                folders
+                    .as_ref()
+                    .is_none_or(|folders| folders.contains(&m.folder_id))
+            })
+            .filter(|m| {
+                search.is_none_or(|search| m.name.to_lowercase().contains(&search.to_lowercase()))
+            })
+            .cloned()
+            .collect();
+
+        Ok(ModelListResponse {
+            page_data: Box::new(PageData {
+                total: models.len() as u32,
+                ..FakeClient::single_page()
+            }),
+            models,
+        })
+    }
+
+    async fn upload_model(
+        &self,
+        _folder: &str,
+        _path: &Path,
+    ) -> Result<Option<Model>, ClientError> {
+        FakeClient::unsupported("uploading models")
+    }
+
+    async fn download_model(&self, _uuid: &Uuid) -> Result<(), ClientError> {
+        FakeClient::unsupported("downloading models")
+    }
+
+    async fn download_model_to(
+        &self,
+        _uuid: &Uuid,
+        _directory: &Path,
+    ) -> Result<PathBuf, ClientError> {
+        FakeClient::unsupported("downloading models")
+    }
+
+    async fn get_list_of_properties(&self) -> Result<PropertyCollection, ClientError> {
+        Ok(PropertyCollection {
+            properties: self.properties.borrow().clone(),
+        })
+    }
+
+    async fn post_property(&self, name: &str) -> Result<Property, ClientError> {
+        Ok(Property {
+            id: self.property_id(name),
+            name: name.to_owned(),
+        })
+    }
+
+    async fn put_model_property(
+        &self,
+        model_uuid: &Uuid,
+        _id: &u64,
+        item: &ModelMetadataItem,
+    ) -> Result<ModelMetadataItem, ClientError> {
+        let mut metadata = self.metadata.borrow_mut();
+        let properties = metadata.entry(*model_uuid).or_default();
+        properties.retain(|p| p.key_id != item.key_id);
+        properties.push(item.clone());
+        Ok(item.clone())
+    }
+
+    async fn delete_model_property(&self, model_uuid: &Uuid, id: &u64) -> Result<(), ClientError> {
+        if let Some(properties) = self.metadata.borrow_mut().get_mut(model_uuid) {
+            properties.retain(|p| p.key_id != *id);
+        }
+        Ok(())
+    }
+
+    async fn delete_property(&self, id: &u64) -> Result<(), ClientError> {
+        self.properties.borrow_mut().retain(|p| p.id != *id);
+        Ok(())
+    }
+
+    async fn get_image_upload_specs(
+        &self,
+        _path: &Path,
+    ) -> Result<ImageUploadResponse, ClientError> {
+        FakeClient::unsupported("image search")
+    }
+
+    async fn upload_image_file(
+        &self,
+        _url: Url,
+        _upload_size_requirements: ImageUploadSizeRequirements,
+        _path: &Path,
+        _mime: String,
+        _content_range: String,
+    ) -> Result<(), ClientError> {
+        FakeClient::unsupported("image search")
+    }
+
+    async fn get_image_search_maches(
+        &self,
+        _id: Vec<String>,
+        _search: Option<&String>,
+        _filter: Option<&String>,
+        _max_matches: u32,
+        _per_page: u32,
+    ) -> Result<ListOfModels, ClientError> {
+        FakeClient::unsupported("image search")
+    }
+
+    async fn get_list_of_users(&self) -> Result<ListOfUsers, ClientError> {
+        FakeClient::unsupported("listing users")
+    }
+}
@@ -1,11 +1,31 @@
 use crate::model::{
-    EnvironmentStatusReport, Folder, ListOfFolders, ListOfGeoClassifierPredictions,
-    ListOfMatchedMetadataItems, ListOfModelMatches, ListOfModels, ListOfUsers,
-    ListOfVisualModelMatches, Model, ModelAssemblyTree, ModelMetadata, PropertyCollection,
-    SimpleDuplicatesMatchReport, ToCsv, ToHtml, ToJson,
+    same_column, write_json_lines, BillOfMaterials, CsvDialect, CsvWriter, EnvironmentStatusReport,
+    Folder, ListOfAssemblyCoverage, ListOfAssemblyTreeRows, ListOfCachedTokens,
+    ListOfClassificationChanges, ListOfCleanupItems, ListOfFolderCounts, ListOfFolders,
+    ListOfFoundModels, ListOfGeoClassifierPredictions, ListOfLintIssues, ListOfManifestUploads,
+    ListOfMatchedMetadataItems, ListOfMetadataImportResults, ListOfMetadataViolations,
+    ListOfModelDownloads, ListOfModelMatches, ListOfModels, ListOfPropertyMerges,
+    ListOfReprocessOutcomes, ListOfReuseItems, ListOfStatusChanges, ListOfSyncChanges,
+    ListOfTenantCounts, ListOfTenantFolders, ListOfUsers, ListOfVisualModelMatches,
+    MetadataImportStatus, Model, ModelAssemblyTree, ModelMetadata, ParsingError,
+    PropertyCollection, Rollup, SimpleDuplicatesMatchReport, ToHtml, ToJson, ToYaml, WriteCsv,
+    WriteJson, WriteJsonLines,
 };
 use colored::*;
-use ptree::print_tree;
+use comfy_table::presets::{NOTHING, UTF8_FULL};
+use comfy_table::{Cell, ContentArrangement, Table};
+use csv::StringRecord;
+use ptree::{write_tree, Style, TreeItem};
+use rust_xlsxwriter::{Format as CellFormat, Workbook, XlsxError};
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::mem;
+use std::path::Path;
 use std::str::FromStr;
 use thiserror::Error;
 use uuid::Uuid;
@@ -16,46 +36,198 @@ pub enum FormatError {
     UnsupportedFormat(String),
     #[error("JSON parsing error")]
     JsonParsingError(#[from] serde_json::Error),
+    #[error("YAML error")]
+    YamlError(#[from] serde_yaml::Error),
+    #[error("Spreadsheet error: {0}")]
+    XlsxError(#[from] XlsxError),
     #[error("CSV parsing error")]
     CsvError(#[from] csv::Error),
     #[error("Parsing error")]
-    ParsingError(#[from] crate::model::ParsingError),
+    ParsingError(Box<crate::model::ParsingError>),
     #[error("I/O error")]
     InputOutputError(#[from] std::io::Error),
+    #[error("Invalid highlighting rule '{0}'. Expected e.g. 'percentage>=0.95:green'")]
+    InvalidHighlightRule(String),
+}
+
+impl From<crate::model::ParsingError> for FormatError {
+    fn from(error: crate::model::ParsingError) -> Self {
+        FormatError::ParsingError(Box::new(error))
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Format {
     Json,
-    Csv,
+    Csv(CsvDialect),
     Tree,
     Html,
+    Table,
+    Yaml,
+    Xlsx,
+    Jsonl,
 }
 
 impl FromStr for Format {
     type Err = FormatError;
     fn from_str(input: &str) -> Result<Format, Self::Err> {
         match input {
-            "JSON" => return Ok(Format::Json),
-            "CSV" => return Ok(Format::Csv),
-            "TREE" => return Ok(Format::Tree),
-            "HTML" => return Ok(Format::Html),
+            "JSON" => Ok(Format::Json),
+            "CSV" => Ok(Format::Csv(CsvDialect::default())),
+            "TREE" => Ok(Format::Tree),
+            "HTML" => Ok(Format::Html),
+            "TABLE" => Ok(Format::Table),
+            "YAML" => Ok(Format::Yaml),
+            "XLSX" => Ok(Format::Xlsx),
+            "JSONL" => Ok(Format::Jsonl),
             _ => Err(FormatError::UnsupportedFormat(input.to_string())),
         }
     }
 }
 
-impl ToString for Format {
-    fn to_string(&self) -> String {
-        match self {
-            Format::Json => "JSON".to_string(),
-            Format::Csv => "CSV".to_string(),
-            Format::Tree => "TREE".to_string(),
-            Format::Html => "HTML".to_string(),
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::Json => "JSON",
+            Format::Csv(_) => "CSV",
+            Format::Tree => "TREE",
+            Format::Html => "HTML",
+            Format::Table => "TABLE",
+            Format::Yaml => "YAML",
+            Format::Xlsx => "XLSX",
+            Format::Jsonl => "JSONL",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How a highlighting rule compares the value of a column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+/// Colors the rows of a table (or the nodes of a tree) in which the value of the column satisfies the condition,
+/// e.g. "percentage>=0.95:green" or "state=FAILED:red"
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighlightRule {
+    column: String,
+    comparison: Comparison,
+    value: String,
+    color: Color,
+}
+
+impl FromStr for HighlightRule {
+    type Err = FormatError;
+    fn from_str(input: &str) -> Result<HighlightRule, Self::Err> {
+        let invalid = || FormatError::InvalidHighlightRule(input.to_string());
+
+        let (condition, color) = input.rsplit_once(':').ok_or_else(invalid)?;
+        let color = Color::from_str(color.trim()).map_err(|_| invalid())?;
+
+        let start = condition.find(['=', '!', '>', '<']).ok_or_else(invalid)?;
+        let (column, condition) = condition.split_at(start);
+        let (comparison, value) = [
+            ("!=", Comparison::NotEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<=", Comparison::LessOrEqual),
+            ("=", Comparison::Equal),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+        ]
+        .iter()
+        .find_map(|(operator, comparison)| {
+            condition
+                .strip_prefix(operator)
+                .map(|value| (*comparison, value))
+        })
+        .ok_or_else(invalid)?;
+
+        if column.trim().is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(HighlightRule {
+            column: column.trim().to_string(),
+            comparison,
+            value: value.trim().to_string(),
+            color,
+        })
+    }
+}
+
+impl HighlightRule {
+    /// Values are compared as numbers if both are numbers and otherwise as text, regardless of case
+    fn matches(&self, value: &str) -> bool {
+        let ordering = match (value.trim().parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(value), Ok(expected)) => value.partial_cmp(&expected),
+            _ => Some(value.to_lowercase().cmp(&self.value.to_lowercase())),
+        };
+
+        match (self.comparison, ordering) {
+            (_, None) => false,
+            (Comparison::Equal, Some(ordering)) => ordering == Ordering::Equal,
+            (Comparison::NotEqual, Some(ordering)) => ordering != Ordering::Equal,
+            (Comparison::Greater, Some(ordering)) => ordering == Ordering::Greater,
+            (Comparison::GreaterOrEqual, Some(ordering)) => ordering != Ordering::Less,
+            (Comparison::Less, Some(ordering)) => ordering == Ordering::Less,
+            (Comparison::LessOrEqual, Some(ordering)) => ordering != Ordering::Greater,
         }
     }
 }
 
+/// Options that apply to the output of every command
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormatOptions {
+    /// Restricts the CSV, table, spreadsheet, JSON and YAML output to these columns, in this order
+    pub columns: Option<Vec<String>>,
+    /// Highlights the rows of tables and the nodes of trees that match the rules. The first matching rule wins
+    pub highlight: Vec<HighlightRule>,
+}
+
+/// Returns the color of the first rule satisfied by the record, if the output is colored at all
+fn highlight_color<F>(rules: &[HighlightRule], field: F) -> Option<Color>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if !control::SHOULD_COLORIZE.should_colorize() {
+        return None;
+    }
+
+    rules.iter().find_map(|rule| {
+        field(&rule.column)
+            .filter(|value| rule.matches(value))
+            .map(|_| rule.color)
+    })
+}
+
+fn table_color(color: Color) -> comfy_table::Color {
+    match color {
+        Color::Black => comfy_table::Color::Black,
+        Color::Red => comfy_table::Color::DarkRed,
+        Color::Green => comfy_table::Color::DarkGreen,
+        Color::Yellow => comfy_table::Color::DarkYellow,
+        Color::Blue => comfy_table::Color::DarkBlue,
+        Color::Magenta => comfy_table::Color::DarkMagenta,
+        Color::Cyan => comfy_table::Color::DarkCyan,
+        Color::White => comfy_table::Color::Grey,
+        Color::BrightBlack => comfy_table::Color::DarkGrey,
+        Color::BrightRed => comfy_table::Color::Red,
+        Color::BrightGreen => comfy_table::Color::Green,
+        Color::BrightYellow => comfy_table::Color::Yellow,
+        Color::BrightBlue => comfy_table::Color::Blue,
+        Color::BrightMagenta => comfy_table::Color::Magenta,
+        Color::BrightCyan => comfy_table::Color::Cyan,
+        Color::BrightWhite => comfy_table::Color::White,
+        Color::TrueColor { r, g, b } => comfy_table::Color::Rgb { r, g, b },
+    }
+}
+
 fn color_string(message: &str, color: Option<Color>) -> colored::ColoredString {
     match color {
         Some(color) => colored::ColoredString::from(message).color(color),
@@ -63,16 +235,423 @@ fn color_string(message: &str, color: Option<Color>) -> colored::ColoredString {
     }
 }
 
+/// Streams the records into the writer as a CSV document in the requested dialect, restricted to the selected columns
+fn write_csv<T, W>(
+    value: &T,
+    writer: W,
+    dialect: &CsvDialect,
+    options: &FormatOptions,
+) -> Result<(), FormatError>
+where
+    T: WriteCsv,
+    W: Write,
+{
+    let mut writer = CsvWriter::new(writer, dialect, options.columns.as_deref());
+    value.write_csv(&mut writer)?;
+    Ok(writer.flush()?)
+}
+
+/// Renders the records as a CSV document in the requested dialect, restricted to the selected columns
+fn csv<T: WriteCsv>(
+    value: &T,
+    dialect: &CsvDialect,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    let mut bytes = Vec::new();
+    write_csv(value, &mut bytes, dialect, options)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Returns the records, starting with the header row, restricted to the selected columns
+fn records<T: WriteCsv>(
+    value: &T,
+    options: &FormatOptions,
+) -> Result<Vec<StringRecord>, FormatError> {
+    let mut writer = CsvWriter::records(options.columns.as_deref());
+    value.write_csv(&mut writer)?;
+    Ok(writer.into_records())
+}
+
+/// Renders the records as a table with aligned columns. Pretty tables also get borders
+fn table<T: WriteCsv>(
+    value: &T,
+    pretty: bool,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    let mut records = records(value, options)?.into_iter();
+
+    let mut table = Table::new();
+    table
+        .load_preset(if pretty { UTF8_FULL } else { NOTHING })
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    let header = records.next().unwrap_or_default();
+    table.set_header(header.iter());
+
+    for record in records {
+        let color = highlight_color(&options.highlight, |column| {
+            let index = header.iter().position(|name| same_column(name, column))?;
+            record.get(index).map(|value| value.to_string())
+        });
+        match color {
+            Some(color) => {
+                table.enforce_styling();
+                table.add_row(
+                    record
+                        .iter()
+                        .map(|value| Cell::new(value).fg(table_color(color))),
+                );
+            }
+            None => {
+                table.add_row(record.iter());
+            }
+        }
+    }
+
+    Ok(table.to_string())
+}
+
+/// Finds a metadata property (e.g. of a model) by name
+fn property(fields: &Map<String, Value>, name: &str) -> Option<Value> {
+    fields
+        .get("metadata")?
+        .as_array()?
+        .iter()
+        .find(|property| {
+            property
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        })
+        .and_then(|property| property.get("value").cloned())
+}
+
+/// The metadata of a model as CSV records that start with the UUID of the model
+struct EnhancedMetadata<'a>(&'a Uuid, &'a ModelMetadata);
+
+impl WriteCsv for EnhancedMetadata<'_> {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        self.1.write_enhanced_csv(self.0, writer)
+    }
+}
+
+/// Prints the tree, highlighting the models that match the rules
+#[derive(Clone)]
+struct HighlightedTree<'a>(&'a ModelAssemblyTree, &'a [HighlightRule]);
+
+impl TreeItem for HighlightedTree<'_> {
+    type Child = Self;
+
+    fn write_self<W: io::Write>(&self, f: &mut W, style: &Style) -> io::Result<()> {
+        let model = serde_json::to_value(&self.0.model).unwrap_or_default();
+        let fields = model.as_object().cloned().unwrap_or_default();
+        let color = highlight_color(self.1, |column| {
+            let value = fields
+                .iter()
+                .find(|(name, _)| same_column(name, column))
+                .map(|(_, value)| value.clone())
+                .or_else(|| property(&fields, column))?;
+            match value {
+                Value::String(value) => Some(value),
+                Value::Null => None,
+                value => Some(value.to_string()),
+            }
+        });
+
+        match color {
+            Some(color) => write!(
+                f,
+                "{}",
+                format!("{}:[{}]", self.0.model.name, self.0.model.uuid).color(color)
+            ),
+            None => self.0.write_self(f, style),
+        }
+    }
+
+    fn children(&self) -> Cow<'_, [Self::Child]> {
+        Cow::from(
+            self.0
+                .children
+                .iter()
+                .flatten()
+                .map(|child| HighlightedTree(child, self.1))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Restricts a JSON record to the selected columns. Columns that are not fields of the record are looked up
+/// among its metadata properties and are null if not found there either
+fn project_record(record: Value, columns: &[String]) -> Value {
+    let fields = match record {
+        Value::Object(fields) => fields,
+        other => return other,
+    };
+
+    let mut projected = Map::new();
+    for column in columns {
+        match fields.iter().find(|(name, _)| same_column(name, column)) {
+            Some((name, value)) => projected.insert(name.to_owned(), value.clone()),
+            None => projected.insert(
+                column.to_owned(),
+                property(&fields, column).unwrap_or(Value::Null),
+            ),
+        };
+    }
+    Value::Object(projected)
+}
+
+/// Serializes a record restricted to the selected columns, so that a list can be streamed one record at a time
+struct Projected<'a, T>(&'a T, &'a [String]);
+
+impl<T: Serialize> Serialize for Projected<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let record = serde_json::to_value(self.0).map_err(serde::ser::Error::custom)?;
+        project_record(record, self.1).serialize(serializer)
+    }
+}
+
+fn projected<'a, T, I>(records: I, columns: &'a [String]) -> Vec<Projected<'a, T>>
+where
+    I: IntoIterator<Item = &'a T>,
+{
+    records
+        .into_iter()
+        .map(|record| Projected(record, columns))
+        .collect()
+}
+
+fn write_json<W: Write, T: Serialize>(
+    writer: W,
+    value: &T,
+    pretty: bool,
+) -> Result<(), FormatError> {
+    if pretty {
+        serde_json::to_writer_pretty(writer, value)?;
+    } else {
+        serde_json::to_writer(writer, value)?;
+    }
+    Ok(())
+}
+
+/// Where the records that the selected columns apply to are in a JSON document
+#[derive(Clone, Copy, Debug)]
+enum Records {
+    /// The document is a list of records or a single record
+    Document,
+    /// The document is a map of records, e.g. keyed by the UUIDs of the models
+    Values,
+    /// The records are listed in this field of the document, and in the same field of its children, if any
+    Field(&'static str),
+}
+
+/// Restricts the records of a JSON document to the selected columns, leaving the rest of the document as is
+fn project_document(document: Value, records: Records, columns: &[String]) -> Value {
+    match (records, document) {
+        (Records::Document, Value::Array(list)) => Value::Array(
+            list.into_iter()
+                .map(|record| project_record(record, columns))
+                .collect(),
+        ),
+        (Records::Document, record) => project_record(record, columns),
+        (Records::Values, Value::Object(map)) => Value::Object(
+            map.into_iter()
+                .map(|(key, record)| (key, project_record(record, columns)))
+                .collect(),
+        ),
+        (Records::Field(name), Value::Object(mut fields)) => {
+            if let Some(list) = fields.get_mut(name) {
+                *list = project_document(mem::take(list), Records::Document, columns);
+            }
+            if let Some(Value::Object(children)) = fields.get_mut("children") {
+                for child in children.values_mut() {
+                    *child = project_document(mem::take(child), records, columns);
+                }
+            }
+            Value::Object(fields)
+        }
+        (_, other) => other,
+    }
+}
+
+/// Restricts the records of a JSON document to the selected columns
+fn json(
+    document: String,
+    records: Records,
+    pretty: bool,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    let columns = match &options.columns {
+        Some(columns) => columns,
+        None => return Ok(document),
+    };
+
+    let projected = project_document(serde_json::from_str(&document)?, records, columns);
+
+    if pretty {
+        Ok(serde_json::to_string_pretty(&projected)?)
+    } else {
+        Ok(serde_json::to_string(&projected)?)
+    }
+}
+
+/// Renders the value as YAML, with its records restricted to the selected columns
+fn yaml<T: ToJson + ToYaml>(
+    value: &T,
+    records: Records,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    let columns = match &options.columns {
+        Some(columns) => columns,
+        None => return yaml(value, Records::Document, options),
+    };
+
+    let document = serde_json::from_str(&value.to_json(false)?)?;
+    Ok(serde_yaml::to_string(&project_document(
+        document, records, columns,
+    ))?)
+}
+
+/// Restricts each record of a JSON Lines document to the selected columns
+fn json_lines(document: String, options: &FormatOptions) -> Result<String, FormatError> {
+    let columns = match &options.columns {
+        Some(columns) => columns,
+        None => return Ok(document),
+    };
+
+    let mut lines = Vec::new();
+    for line in document.lines() {
+        let record = project_record(serde_json::from_str(line)?, columns);
+        lines.push(serde_json::to_string(&record)?);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Numbers are stored as numbers in spreadsheets, unless they have leading zeros (e.g. part numbers)
+fn is_number(value: &str) -> bool {
+    let digits = value.trim_start_matches('-');
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && !(digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0."))
+        && value.parse::<f64>().is_ok()
+}
+
+/// Saves records (starting with header rows) as a spreadsheet with one sheet per section and frozen header rows
+fn save_xlsx(sections: &[(&str, Vec<StringRecord>)], path: &Path) -> Result<(), FormatError> {
+    let mut workbook = Workbook::new();
+    let header_format = CellFormat::new().set_bold();
+
+    for (name, records) in sections {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(*name)?;
+        worksheet.set_freeze_panes(1, 0)?;
+
+        for (row, record) in records.iter().enumerate() {
+            let row = row as u32;
+            for (column, value) in record.iter().enumerate() {
+                let column = column as u16;
+                if row == 0 {
+                    worksheet.write_string_with_format(row, column, value, &header_format)?;
+                } else if is_number(value) {
+                    worksheet.write_number(
+                        row,
+                        column,
+                        value.parse::<f64>().unwrap_or_default(),
+                    )?;
+                } else {
+                    worksheet.write_string(row, column, value)?;
+                }
+            }
+        }
+
+        worksheet.autofit();
+    }
+
+    log::trace!("Saving spreadsheet {}...", path.to_string_lossy());
+    workbook.save(path)?;
+    Ok(())
+}
+
+/// Streams the document produced by `write` into the writer, wrapped in the color codes if requested.
+/// The output ends with a new line, the same way as printing a formatted string would
+fn write_colored<W, F>(writer: &mut W, color: Option<Color>, write: F) -> Result<(), FormatError>
+where
+    W: Write,
+    F: FnOnce(&mut W) -> Result<(), FormatError>,
+{
+    let color = color.filter(|_| control::SHOULD_COLORIZE.should_colorize());
+    if let Some(color) = color {
+        write!(writer, "\x1b[{}m", color.to_fg_str())?;
+    }
+    write(writer)?;
+    if color.is_some() {
+        write!(writer, "\x1b[0m")?;
+    }
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
 pub fn format_list_of_folders(
     folders: ListOfFolders,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(folders.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(folders.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(&folders, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(&folders, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(&folders, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_folder_counts(
+    folders: &ListOfFolderCounts,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
-    let folders = ListOfFolders::from(folders);
     match format {
-        Format::Json => Ok(color_string(folders.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(folders.to_csv(pretty)?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(folders.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(folders.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(folders, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(folders, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(folders, pretty, options)?.as_str(),
+            color,
+        )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
@@ -82,11 +661,27 @@ pub fn format_list_of_users(
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     log::trace!("Formatting list of users...");
     match format {
-        Format::Json => Ok(color_string(users.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(users.to_csv(pretty)?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(users.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(users.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(&users, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(&users, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(&users, pretty, options)?.as_str(),
+            color,
+        )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
@@ -96,11 +691,25 @@ pub fn format_folder(
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
-    let folder = Folder::from(folder);
     match format {
-        Format::Json => Ok(color_string(folder.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(folder.to_csv(pretty)?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(folder.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(&folder, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(&folder, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(&folder, pretty, options)?.as_str(),
+            color,
+        )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
@@ -110,10 +719,19 @@ pub fn format_model(
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
-        Format::Json => Ok(color_string(model.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(model.to_csv(pretty)?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(model.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(model, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(model, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(model, pretty, options)?.as_str(), color)),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
@@ -124,11 +742,29 @@ pub fn format_model_metadata(
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
-        Format::Json => Ok(color_string(meta.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(
-            meta.to_enhanced_csv(uuid, pretty)?.as_str(),
+        Format::Json => Ok(color_string(
+            json(
+                meta.to_json(pretty)?,
+                Records::Field("metadata"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(meta, Records::Field("metadata"), options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(&EnhancedMetadata(uuid, meta), dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(&EnhancedMetadata(uuid, meta), pretty, options)?.as_str(),
             color,
         )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
@@ -140,139 +776,1251 @@ pub fn format_list_of_models(
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
-        Format::Json => Ok(color_string(models.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(models.to_csv(pretty)?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(models.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(models.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(models, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(models, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(models, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn write_list_of_models<W: io::Write>(
+    models: &ListOfModels,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+    writer: &mut W,
+) -> Result<(), FormatError> {
+    match format {
+        Format::Json => write_colored(writer, color, |w| match &options.columns {
+            Some(columns) => write_json(w, &projected(&models.models, columns), pretty),
+            None => Ok(models.write_json(w, pretty)?),
+        }),
+        Format::Jsonl => write_colored(writer, color, |w| match &options.columns {
+            Some(columns) => Ok(write_json_lines(w, &projected(&models.models, columns))?),
+            None => Ok(models.write_json_lines(w)?),
+        }),
+        Format::Yaml => write_colored(writer, color, |w| {
+            Ok(w.write_all(
+                yaml(models, Records::Document, options)?
+                    .trim_end()
+                    .as_bytes(),
+            )?)
+        }),
+        Format::Csv(dialect) => {
+            write_colored(writer, color, |w| write_csv(models, w, dialect, options))
+        }
+        Format::Table => write_colored(writer, color, |w| {
+            Ok(w.write_all(table(models, pretty, options)?.as_bytes())?)
+        }),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
+pub fn save_list_of_models(
+    models: &ListOfModels,
+    path: &Path,
+    options: &FormatOptions,
+) -> Result<(), FormatError> {
+    save_xlsx(&[("Models", records(models, options)?)], path)
+}
+
 pub fn format_enhanced_assembly_tree(
     enhanced_assembly_tree: &ModelAssemblyTree,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
         Format::Json => Ok(color_string(
-            enhanced_assembly_tree.to_json(pretty)?.as_str(),
+            json(
+                enhanced_assembly_tree.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(enhanced_assembly_tree, Records::Document, options)?.trim_end(),
             color,
         )),
         Format::Tree => {
-            print_tree(enhanced_assembly_tree)?;
-            Ok(colored::ColoredString::from(""))
+            let mut tree = Vec::new();
+            write_tree(
+                &HighlightedTree(enhanced_assembly_tree, &options.highlight),
+                &mut tree,
+            )?;
+            Ok(color_string(
+                String::from_utf8_lossy(&tree).trim_end(),
+                color,
+            ))
         }
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_list_of_model_matches(
-    list_of_model_matches: &ListOfModelMatches,
+pub fn format_list_of_assembly_tree_rows(
+    rows: &ListOfAssemblyTreeRows,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
         Format::Json => Ok(color_string(
-            list_of_model_matches.to_json(pretty)?.as_str(),
+            json(
+                rows.to_json(pretty)?,
+                Records::Field("rows"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(rows.to_json_lines()?, options)?.as_str(),
             color,
         )),
-        Format::Csv => Ok(color_string(
-            list_of_model_matches.to_csv(pretty)?.as_str(),
+        Format::Yaml => Ok(color_string(
+            yaml(rows, Records::Field("rows"), options)?.trim_end(),
             color,
         )),
+        Format::Csv(dialect) => Ok(color_string(csv(rows, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(rows, pretty, options)?.as_str(), color)),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_list_of_visual_model_matches(
-    list_of_visual_model_matches: &ListOfVisualModelMatches,
+pub fn format_bill_of_materials(
+    bom: &BillOfMaterials,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
         Format::Json => Ok(color_string(
-            list_of_visual_model_matches.to_json(pretty)?.as_str(),
+            json(bom.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(bom.to_json_lines()?, options)?.as_str(),
             color,
         )),
-        Format::Csv => Ok(color_string(
-            list_of_visual_model_matches.to_csv(pretty)?.as_str(),
+        Format::Yaml => Ok(color_string(
+            yaml(bom, Records::Document, options)?.trim_end(),
             color,
         )),
+        Format::Csv(dialect) => Ok(color_string(csv(bom, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(bom, pretty, options)?.as_str(), color)),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_list_of_geo_matches(
-    list_of_model_matches: &ListOfGeoClassifierPredictions,
+pub fn format_rollup(
+    rollup: &Rollup,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
         Format::Json => Ok(color_string(
-            list_of_model_matches.to_json(pretty)?.as_str(),
+            json(rollup.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(rollup.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(rollup, Records::Document, options)?.trim_end(),
             color,
         )),
-        Format::Csv => Ok(color_string(
-            list_of_model_matches.to_csv(pretty)?.as_str(),
+        Format::Csv(dialect) => Ok(color_string(csv(rollup, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(rollup, pretty, options)?.as_str(),
             color,
         )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_list_of_properties(
-    properties: &PropertyCollection,
+pub fn format_list_of_assembly_coverage(
+    coverage: &ListOfAssemblyCoverage,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
-        Format::Json => Ok(color_string(properties.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(properties.to_csv(pretty)?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(
+                coverage.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(coverage.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(coverage, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(coverage, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(coverage, pretty, options)?.as_str(),
+            color,
+        )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_simple_duplicates_match_report(
-    bom: &SimpleDuplicatesMatchReport,
+pub fn format_list_of_model_matches(
+    list_of_model_matches: &ListOfModelMatches,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
-        Format::Json => Ok(color_string(bom.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(bom.to_csv(pretty)?.as_str(), color)),
-        Format::Html => Ok(color_string(bom.to_html()?.as_str(), color)),
+        Format::Json => Ok(color_string(
+            json(
+                list_of_model_matches.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(list_of_model_matches.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(list_of_model_matches, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(list_of_model_matches, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(list_of_model_matches, pretty, options)?.as_str(),
+            color,
+        )),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_environment_status_report(
-    stats: &EnvironmentStatusReport,
+pub fn write_list_of_model_matches<W: io::Write>(
+    list_of_model_matches: &ListOfModelMatches,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
-) -> Result<colored::ColoredString, FormatError> {
+    options: &FormatOptions,
+    writer: &mut W,
+) -> Result<(), FormatError> {
     match format {
-        Format::Json => Ok(color_string(stats.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(stats.to_csv(pretty)?.as_str(), color)),
+        Format::Json => write_colored(writer, color, |w| match &options.columns {
+            Some(columns) => write_json(
+                w,
+                &projected(list_of_model_matches.inner.iter(), columns),
+                pretty,
+            ),
+            None => Ok(list_of_model_matches.write_json(w, pretty)?),
+        }),
+        Format::Jsonl => write_colored(writer, color, |w| match &options.columns {
+            Some(columns) => Ok(write_json_lines(
+                w,
+                &projected(list_of_model_matches.inner.iter(), columns),
+            )?),
+            None => Ok(list_of_model_matches.write_json_lines(w)?),
+        }),
+        Format::Yaml => write_colored(writer, color, |w| {
+            Ok(w.write_all(
+                yaml(list_of_model_matches, Records::Document, options)?
+                    .trim_end()
+                    .as_bytes(),
+            )?)
+        }),
+        Format::Csv(dialect) => write_colored(writer, color, |w| {
+            write_csv(list_of_model_matches, w, dialect, options)
+        }),
+        Format::Table => write_colored(writer, color, |w| {
+            Ok(w.write_all(table(list_of_model_matches, pretty, options)?.as_bytes())?)
+        }),
         _ => Err(FormatError::UnsupportedFormat(format.to_string())),
     }
 }
 
-pub fn format_list_of_matched_properties(
-    props: &ListOfMatchedMetadataItems,
+pub fn save_list_of_model_matches(
+    list_of_model_matches: &ListOfModelMatches,
+    path: &Path,
+    options: &FormatOptions,
+) -> Result<(), FormatError> {
+    save_xlsx(
+        &[("Matches", records(list_of_model_matches, options)?)],
+        path,
+    )
+}
+
+pub fn format_list_of_visual_model_matches(
+    list_of_visual_model_matches: &ListOfVisualModelMatches,
     format: &Format,
     pretty: bool,
     color: Option<Color>,
+    options: &FormatOptions,
 ) -> Result<colored::ColoredString, FormatError> {
     match format {
-        Format::Json => Ok(color_string(props.to_json(pretty)?.as_str(), color)),
-        Format::Csv => Ok(color_string(props.to_csv(pretty)?.as_str(), color)),
-        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+        Format::Json => Ok(color_string(
+            json(
+                list_of_visual_model_matches.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(list_of_visual_model_matches.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(list_of_visual_model_matches, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(list_of_visual_model_matches, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(list_of_visual_model_matches, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_geo_matches(
+    list_of_model_matches: &ListOfGeoClassifierPredictions,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                list_of_model_matches.to_json(pretty)?,
+                Records::Field("matches"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(list_of_model_matches.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(list_of_model_matches, Records::Field("matches"), options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(list_of_model_matches, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(list_of_model_matches, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_properties(
+    properties: &PropertyCollection,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                properties.to_json(pretty)?,
+                Records::Field("metadataKeys"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(properties.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(properties, Records::Field("metadataKeys"), options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(properties, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(properties, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_simple_duplicates_match_report(
+    bom: &SimpleDuplicatesMatchReport,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(bom.to_json(pretty)?, Records::Values, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(bom.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(bom, Records::Values, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(bom, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(bom, pretty, options)?.as_str(), color)),
+        Format::Html => Ok(color_string(bom.to_html()?.as_str(), color)),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn write_simple_duplicates_match_report<W: io::Write>(
+    bom: &SimpleDuplicatesMatchReport,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+    writer: &mut W,
+) -> Result<(), FormatError> {
+    match format {
+        // the report is a map of records, keyed by the UUIDs of the models
+        Format::Json => write_colored(writer, color, |w| match &options.columns {
+            Some(columns) => write_json(
+                w,
+                &bom.inner
+                    .iter()
+                    .map(|(uuid, item)| (uuid, Projected(item, columns)))
+                    .collect::<HashMap<_, _>>(),
+                pretty,
+            ),
+            None => Ok(bom.write_json(w, pretty)?),
+        }),
+        Format::Jsonl => write_colored(writer, color, |w| match &options.columns {
+            Some(columns) => Ok(write_json_lines(
+                w,
+                &projected(bom.inner.values(), columns),
+            )?),
+            None => Ok(bom.write_json_lines(w)?),
+        }),
+        Format::Yaml => write_colored(writer, color, |w| {
+            Ok(w.write_all(yaml(bom, Records::Values, options)?.trim_end().as_bytes())?)
+        }),
+        Format::Csv(dialect) => {
+            write_colored(writer, color, |w| write_csv(bom, w, dialect, options))
+        }
+        Format::Table => write_colored(writer, color, |w| {
+            Ok(w.write_all(table(bom, pretty, options)?.as_bytes())?)
+        }),
+        Format::Html => write_colored(writer, color, |w| {
+            Ok(w.write_all(bom.to_html()?.as_bytes())?)
+        }),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn save_simple_duplicates_match_report(
+    bom: &SimpleDuplicatesMatchReport,
+    path: &Path,
+    options: &FormatOptions,
+) -> Result<(), FormatError> {
+    save_xlsx(&[("Matches", records(bom, options)?)], path)
+}
+
+pub fn format_environment_status_report(
+    stats: &EnvironmentStatusReport,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(stats.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(stats.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(stats, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(stats, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(stats, pretty, options)?.as_str(), color)),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn save_environment_status_report(
+    stats: &EnvironmentStatusReport,
+    path: &Path,
+    options: &FormatOptions,
+) -> Result<(), FormatError> {
+    let mut repaired = vec![StringRecord::from(vec!["UUID"])];
+    for uuid in &stats.repaired {
+        repaired.push(StringRecord::from(vec![uuid.to_string()]));
+    }
+
+    save_xlsx(
+        &[("Status", records(stats, options)?), ("Repaired", repaired)],
+        path,
+    )
+}
+
+pub fn format_list_of_status_changes(
+    changes: &ListOfStatusChanges,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(changes.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(changes.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(changes, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(changes, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(changes, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_metadata_violations(
+    violations: &ListOfMetadataViolations,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                violations.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(violations.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(violations, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(violations, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(violations, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_found_models(
+    models: &ListOfFoundModels,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(models.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(models.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(models, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(models, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(models, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_lint_issues(
+    issues: &ListOfLintIssues,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(issues.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(issues.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(issues, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(issues, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(issues, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn save_list_of_status_changes(
+    changes: &ListOfStatusChanges,
+    path: &Path,
+    options: &FormatOptions,
+) -> Result<(), FormatError> {
+    save_xlsx(&[("Changes", records(changes, options)?)], path)
+}
+
+pub fn format_list_of_matched_properties(
+    props: &ListOfMatchedMetadataItems,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                props.to_json(pretty)?,
+                Records::Field("items"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(props, Records::Field("items"), options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(props, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(props, pretty, options)?.as_str(), color)),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_classification_changes(
+    changes: &ListOfClassificationChanges,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                changes.to_json(pretty)?,
+                Records::Field("changes"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(changes.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(changes, Records::Field("changes"), options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(changes, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(changes, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_sync_changes(
+    changes: &ListOfSyncChanges,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                changes.to_json(pretty)?,
+                Records::Field("changes"),
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(changes.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(changes, Records::Field("changes"), options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(changes, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(changes, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+/// Formats the outcome of a metadata upload. The "tree" format shows the changes as a diff per model: "+" for a new
+/// property, "~" for a changed value, "-" for a deleted property and "!" for an error. Skipped rows are left out
+pub fn format_list_of_metadata_import_results(
+    results: &ListOfMetadataImportResults,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(results.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(results.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(results, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(results, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(results, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Tree => Ok(color_string(metadata_diff(results).as_str(), color)),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+fn metadata_diff(results: &ListOfMetadataImportResults) -> String {
+    let mut models: Vec<Option<Uuid>> = Vec::new();
+    for result in results.results.iter() {
+        if !models.contains(&result.uuid) {
+            models.push(result.uuid);
+        }
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    for uuid in models {
+        let changes: Vec<String> = results
+            .results
+            .iter()
+            .filter(|result| result.uuid == uuid)
+            .filter_map(|result| {
+                let previous = result.previous_value.to_owned().unwrap_or_default();
+                match result.status {
+                    MetadataImportStatus::Created => {
+                        Some(format!("+ {}: {}", result.name, result.value))
+                    }
+                    MetadataImportStatus::Updated => Some(format!(
+                        "~ {}: {} -> {}",
+                        result.name, previous, result.value
+                    )),
+                    MetadataImportStatus::Deleted => {
+                        Some(format!("- {}: {}", result.name, previous))
+                    }
+                    MetadataImportStatus::Error => Some(format!(
+                        "! row {}: {}",
+                        result.row.map(|row| row.to_string()).unwrap_or_default(),
+                        result.reason.to_owned().unwrap_or_default()
+                    )),
+                    MetadataImportStatus::Skipped => None,
+                }
+            })
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+
+        match uuid {
+            Some(uuid) => lines.push(uuid.to_string()),
+            None => lines.push(String::from("(unreadable rows)")),
+        }
+        lines.extend(changes.into_iter().map(|change| format!("  {}", change)));
+    }
+
+    if lines.is_empty() {
+        String::from("No changes")
+    } else {
+        lines.join("\n")
+    }
+}
+
+pub fn format_list_of_property_merges(
+    merges: &ListOfPropertyMerges,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(merges.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(merges.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(merges, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(merges, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(merges, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_cleanup_items(
+    items: &ListOfCleanupItems,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(items.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(items.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(items, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(items, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(items, pretty, options)?.as_str(), color)),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_model_downloads(
+    downloads: &ListOfModelDownloads,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                downloads.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(downloads.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(downloads, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(downloads, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(downloads, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_manifest_uploads(
+    uploads: &ListOfManifestUploads,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(uploads.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(uploads.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(uploads, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(uploads, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(uploads, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_reprocess_outcomes(
+    outcomes: &ListOfReprocessOutcomes,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(
+                outcomes.to_json(pretty)?,
+                Records::Document,
+                pretty,
+                options,
+            )?
+            .as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(outcomes.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(outcomes, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(outcomes, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(outcomes, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_reuse_items(
+    items: &ListOfReuseItems,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(items.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(items.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(items, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(items, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(table(items, pretty, options)?.as_str(), color)),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_cached_tokens(
+    tokens: &ListOfCachedTokens,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(tokens.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(tokens.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(tokens, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(tokens, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(tokens, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_tenant_folders(
+    folders: &ListOfTenantFolders,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(folders.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(folders.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(folders, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(
+            csv(folders, dialect, options)?.as_str(),
+            color,
+        )),
+        Format::Table => Ok(color_string(
+            table(folders, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+pub fn format_list_of_tenant_counts(
+    counts: &ListOfTenantCounts,
+    format: &Format,
+    pretty: bool,
+    color: Option<Color>,
+    options: &FormatOptions,
+) -> Result<colored::ColoredString, FormatError> {
+    match format {
+        Format::Json => Ok(color_string(
+            json(counts.to_json(pretty)?, Records::Document, pretty, options)?.as_str(),
+            color,
+        )),
+        Format::Jsonl => Ok(color_string(
+            json_lines(counts.to_json_lines()?, options)?.as_str(),
+            color,
+        )),
+        Format::Yaml => Ok(color_string(
+            yaml(counts, Records::Document, options)?.trim_end(),
+            color,
+        )),
+        Format::Csv(dialect) => Ok(color_string(csv(counts, dialect, options)?.as_str(), color)),
+        Format::Table => Ok(color_string(
+            table(counts, pretty, options)?.as_str(),
+            color,
+        )),
+        _ => Err(FormatError::UnsupportedFormat(format.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::model::{ModelMatchReportItem, ModelMetadataItem};
+
+    fn columns(columns: &[&str]) -> FormatOptions {
+        FormatOptions {
+            columns: Some(columns.iter().map(|c| c.to_string()).collect()),
+            ..FormatOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_json_columns_of_model_metadata() {
+        let meta = ModelMetadata::new(vec![ModelMetadataItem::new(
+            1,
+            "material".to_string(),
+            "steel".to_string(),
+        )]);
+        let output = format_model_metadata(
+            &Uuid::nil(),
+            &meta,
+            &Format::Json,
+            false,
+            None,
+            &columns(&["NAME", "VALUE"]),
+        )
+        .unwrap();
+        assert_eq!(
+            output.to_string(),
+            r#"{"metadata":[{"name":"material","value":"steel"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_json_columns_of_duplicates_match_report() {
+        let uuid = Uuid::nil().to_string();
+        let mut report = SimpleDuplicatesMatchReport::new();
+        report.inner.insert(
+            uuid.to_owned(),
+            ModelMatchReportItem {
+                uuid: uuid.to_owned(),
+                name: "bolt".to_string(),
+                ..ModelMatchReportItem::default()
+            },
+        );
+        let options = columns(&["NAME"]);
+        let expected = format!(r#"{{"{uuid}":{{"name":"bolt"}}}}"#);
+
+        let output =
+            format_simple_duplicates_match_report(&report, &Format::Json, false, None, &options)
+                .unwrap();
+        assert_eq!(output.to_string(), expected);
+
+        let mut written = Vec::new();
+        write_simple_duplicates_match_report(
+            &report,
+            &Format::Json,
+            false,
+            None,
+            &options,
+            &mut written,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), format!("{expected}\n"));
+    }
+
+    #[test]
+    fn test_yaml_columns_of_model_metadata() {
+        let meta = ModelMetadata::new(vec![ModelMetadataItem::new(
+            1,
+            "material".to_string(),
+            "steel".to_string(),
+        )]);
+        let output = format_model_metadata(
+            &Uuid::nil(),
+            &meta,
+            &Format::Yaml,
+            false,
+            None,
+            &columns(&["VALUE"]),
+        )
+        .unwrap();
+        assert_eq!(output.to_string(), "metadata:\n- value: steel");
     }
 }
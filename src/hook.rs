@@ -0,0 +1,135 @@
+use crate::error::ErrorReport;
+use serde::Serialize;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// How long to wait for the notification URL to accept the summary
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Reports the outcome of a long-running command to a URL and/or a command, so that pipelines can react to it
+#[derive(Debug)]
+pub struct CompletionHook {
+    command: String,
+    tenant: String,
+    started: SystemTime,
+    notify_url: Option<Url>,
+    on_complete: Option<String>,
+}
+
+impl CompletionHook {
+    /// Starts timing the command
+    pub fn new(
+        command: &str,
+        tenant: &str,
+        notify_url: Option<Url>,
+        on_complete: Option<String>,
+    ) -> CompletionHook {
+        CompletionHook {
+            command: command.to_owned(),
+            tenant: tenant.to_owned(),
+            started: SystemTime::now(),
+            notify_url,
+            on_complete,
+        }
+    }
+}
+
+/// The JSON summary sent to the notification URL and passed to the command
+#[derive(Debug, Serialize)]
+pub struct CompletionSummary<'a> {
+    pub command: &'a str,
+    pub tenant: &'a str,
+    /// "succeeded" or "failed"
+    pub status: &'static str,
+    pub exit_code: exitcode::ExitCode,
+    pub error: Option<&'a ErrorReport>,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub duration_seconds: f64,
+}
+
+/// The hook selected with `set_hook`
+static HOOK: OnceLock<CompletionHook> = OnceLock::new();
+
+/// Selects what to do when the command completes
+pub fn set_hook(hook: CompletionHook) {
+    let _ = HOOK.set(hook);
+}
+
+/// Runs the selected hook, if any, with the outcome of the command. Failing to notify is reported on STDERR, but
+/// does not change the outcome
+pub fn complete(exit_code: exitcode::ExitCode, error: Option<&ErrorReport>) {
+    let hook = match HOOK.get() {
+        Some(hook) => hook,
+        None => return,
+    };
+
+    let summary = CompletionSummary {
+        command: &hook.command,
+        tenant: &hook.tenant,
+        status: if exit_code == exitcode::OK {
+            "succeeded"
+        } else {
+            "failed"
+        },
+        exit_code,
+        error,
+        started_at: hook
+            .started
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        duration_seconds: hook.started.elapsed().unwrap_or_default().as_secs_f64(),
+    };
+    let json = match serde_json::to_string(&summary) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("Failed to report the completion: {}", e);
+            return;
+        }
+    };
+
+    if let Some(url) = &hook.notify_url {
+        log::trace!("Sending the completion summary to {}...", url);
+        let result = reqwest::blocking::Client::builder()
+            .timeout(NOTIFY_TIMEOUT)
+            .build()
+            .and_then(|client| {
+                client
+                    .post(url.to_owned())
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(json.to_owned())
+                    .send()
+            })
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            eprintln!("Failed to send the completion summary to {}: {}", url, e);
+        }
+    }
+
+    if let Some(on_complete) = &hook.on_complete {
+        log::trace!("Running the completion command: {}", on_complete);
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C").arg(on_complete);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(on_complete);
+            command
+        };
+        let result = command
+            .env("PCLI_COMMAND", &hook.command)
+            .env("PCLI_STATUS", summary.status)
+            .env("PCLI_EXIT_CODE", exit_code.to_string())
+            .env("PCLI_SUMMARY", &json)
+            .status();
+        match result {
+            Ok(status) if status.success() => (),
+            Ok(status) => eprintln!("The completion command failed with {}", status),
+            Err(e) => eprintln!("Failed to run the completion command: {}", e),
+        }
+    }
+}
@@ -1,6 +1,55 @@
-pub mod client;
+//! Access to the Physna API for Rust programs, as used by the PCLI command line tool.
+//!
+//! [`Api`] is the entry point. It is created for one tenant with [`Api::builder`], either from the PCLI configuration
+//! or from a base URL, an access token and the HTTP settings. The access token for a tenant
+//! of the PCLI configuration is obtained with [`configuration::from_client_configuration`]. The results are the types of
+//! the [`model`] module, which the [`format`](mod@format) module renders as JSON, CSV, YAML and other formats. All failures are
+//! returned as [`ApiError`]; [`Api`] never prints and never terminates the process.
+//!
+//! ```no_run
+//! use pcli::{configuration, Api};
+//!
+//! let configuration = configuration::initialize(&String::from("/home/me/.pcli.conf"))?;
+//! let tenant = String::from("mytenant");
+//! let access = configuration::from_client_configuration(&configuration, &tenant)?;
+//!
+//! let api = Api::builder()
+//!     .configuration(&configuration, &tenant)
+//!     .access_token(&access.access_token)
+//!     .build()?;
+//! for folder in api.get_list_of_folders(None)?.folders {
+//!     println!("{}", folder.name);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+pub(crate) mod cache;
+pub(crate) mod cli;
+pub(crate) mod client;
 pub mod configuration;
+pub(crate) mod deadline;
+pub(crate) mod error;
+#[cfg(test)]
+mod fake;
 pub mod format;
+pub(crate) mod hook;
+pub(crate) mod lint;
+pub(crate) mod manifest;
+pub(crate) mod metadata_file;
 pub mod model;
-pub mod service;
-pub mod token;
+pub(crate) mod recording;
+pub(crate) mod schema;
+pub(crate) mod secret;
+pub(crate) mod service;
+pub(crate) mod storage;
+pub(crate) mod sync;
+pub(crate) mod token;
+pub(crate) mod watch;
+
+pub use client::ClientError;
+pub use service::{Api, ApiBuilder, ApiError};
+
+/// Runs the PCLI command line tool. This is the entry point of the `pcli` binary and not part of the library API.
+#[doc(hidden)]
+pub fn run() {
+    cli::main()
+}
@@ -0,0 +1,324 @@
+use crate::manifest::{FILE_COLUMN, FOLDER_COLUMN};
+use crate::metadata_file::MetadataColumns;
+use crate::model::{LintIssue, LintSeverity, ListOfLintIssues};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use uuid::Uuid;
+
+/// What an input file is read by
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputKind {
+    /// The metadata read by "upload-model-meta"
+    Meta(MetadataColumns),
+    /// The manifest read by "upload-manifest"
+    Manifest,
+}
+
+/// Checks a CSV input file before it is imported: its encoding, its columns, the number of fields in every row, and
+/// the values of every row. Only a file that cannot be read at all is an error; everything else is reported as an
+/// issue with the line it was found on
+pub fn lint_file(path: &Path, kind: &InputKind) -> Result<ListOfLintIssues, io::Error> {
+    let bytes = fs::read(path)?;
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(lint(&bytes, kind, base))
+}
+
+fn lint(bytes: &[u8], kind: &InputKind, base: &Path) -> ListOfLintIssues {
+    let mut linter = Linter::default();
+    if bytes.starts_with(&[0xFF, 0xFE]) || bytes.starts_with(&[0xFE, 0xFF]) {
+        linter.error(
+            1,
+            None,
+            "the file is encoded as UTF-16. Save it as UTF-8".to_string(),
+        );
+        return linter.finish();
+    }
+    for (index, line) in bytes.split(|b| *b == b'\n').enumerate() {
+        if let Err(e) = std::str::from_utf8(line) {
+            linter.error(
+                index as u64 + 1,
+                None,
+                format!(
+                    "invalid UTF-8 at character {}. Save the file as UTF-8",
+                    e.valid_up_to() + 1
+                ),
+            );
+        }
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut records = Vec::new();
+    for record in reader.records() {
+        match record {
+            Ok(record) => {
+                let line = record.position().map(|p| p.line()).unwrap_or_default();
+                records.push((line, record.iter().map(String::from).collect::<Vec<_>>()));
+            }
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or_default();
+                linter.error(line, None, e.to_string());
+            }
+        }
+    }
+    let mut records = records.into_iter();
+    let (header_line, headers) = match records.next() {
+        Some(header) => header,
+        None => {
+            linter.error(1, None, "the file is empty".to_string());
+            return linter.finish();
+        }
+    };
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (index, header) in headers.iter().enumerate() {
+        let header = header.trim();
+        if header.is_empty() {
+            linter.warning(
+                header_line,
+                None,
+                format!("column {} has no name", index + 1),
+            );
+        } else if seen.insert(header.to_lowercase(), index).is_some() {
+            linter.error(
+                header_line,
+                Some(header),
+                "the column appears more than once".to_string(),
+            );
+        }
+    }
+    let required = match kind {
+        InputKind::Meta(columns) if columns.wide => vec![columns.uuid.as_str()],
+        InputKind::Meta(columns) => vec![
+            columns.uuid.as_str(),
+            columns.name.as_str(),
+            columns.value.as_str(),
+        ],
+        InputKind::Manifest => vec![FILE_COLUMN, FOLDER_COLUMN],
+    };
+    let mut missing = false;
+    for column in required {
+        if !seen.contains_key(&column.to_lowercase()) {
+            linter.error(
+                header_line,
+                Some(column),
+                "the column is missing".to_string(),
+            );
+            missing = true;
+        }
+    }
+    // without the columns, every row would be reported
+    if missing {
+        return linter.finish();
+    }
+    let column = |name: &str| seen[&name.to_lowercase()];
+
+    // the first row and the value of everything that is set, to find duplicates
+    let mut set: HashMap<(String, String), (u64, String)> = HashMap::new();
+    for (line, values) in records {
+        if values.len() != headers.len() {
+            linter.error(
+                line,
+                None,
+                format!(
+                    "the row has {} field(s), but the header has {}",
+                    values.len(),
+                    headers.len()
+                ),
+            );
+        }
+        if values.iter().all(|value| value.trim().is_empty()) {
+            linter.warning(line, None, "the row is empty".to_string());
+            continue;
+        }
+        let value = |index: usize| values.get(index).map(|v| v.as_str()).unwrap_or_default();
+
+        match kind {
+            InputKind::Meta(columns) => {
+                let uuid = value(column(&columns.uuid)).trim();
+                if Uuid::parse_str(uuid).is_err() {
+                    linter.error(
+                        line,
+                        Some(&columns.uuid),
+                        format!("'{}' is not a valid UUID", uuid),
+                    );
+                    continue;
+                }
+                let properties: Vec<(Option<&str>, &str, &str)> = if columns.wide {
+                    headers
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, header)| {
+                            *index != column(&columns.uuid)
+                                && !header.trim().is_empty()
+                                && !value(*index).is_empty()
+                        })
+                        .map(|(index, header)| (Some(header.trim()), header.trim(), value(index)))
+                        .collect()
+                } else {
+                    vec![(
+                        None,
+                        value(column(&columns.name)).trim(),
+                        value(column(&columns.value)),
+                    )]
+                };
+                for (header, name, value) in properties {
+                    if name.is_empty() {
+                        linter.error(
+                            line,
+                            Some(&columns.name),
+                            "the property has no name".to_string(),
+                        );
+                        continue;
+                    }
+                    if value.is_empty() {
+                        linter.warning(
+                            line,
+                            Some(&columns.value),
+                            format!("the value is empty, so '{}' will be deleted", name),
+                        );
+                    }
+                    let key = (uuid.to_lowercase(), name.to_lowercase());
+                    match set.get(&key) {
+                        Some((first, first_value)) if first_value == value => {
+                            linter.warning(line, header, format!("duplicate of row {}", first))
+                        }
+                        Some((first, first_value)) => linter.error(
+                            line,
+                            header,
+                            format!(
+                                "sets '{}' to '{}', but row {} sets it to '{}'",
+                                name, value, first, first_value
+                            ),
+                        ),
+                        None => {
+                            set.insert(key, (line, value.to_owned()));
+                        }
+                    }
+                }
+            }
+            InputKind::Manifest => {
+                let file = value(column(FILE_COLUMN)).trim();
+                let folder = value(column(FOLDER_COLUMN)).trim();
+                if file.is_empty() {
+                    linter.warning(
+                        line,
+                        Some(FILE_COLUMN),
+                        "the row has no file, so it is skipped".to_string(),
+                    );
+                    continue;
+                }
+                if !base.join(file).is_file() {
+                    linter.error(
+                        line,
+                        Some(FILE_COLUMN),
+                        format!("the file '{}' does not exist", file),
+                    );
+                }
+                if folder.is_empty() {
+                    linter.error(
+                        line,
+                        Some(FOLDER_COLUMN),
+                        "the target folder is missing".to_string(),
+                    );
+                }
+                let key = (file.to_owned(), folder.to_lowercase());
+                match set.get(&key) {
+                    Some((first, _)) => linter.warning(
+                        line,
+                        None,
+                        format!(
+                            "duplicate of row {}, so the file would be uploaded twice",
+                            first
+                        ),
+                    ),
+                    None => {
+                        set.insert(key, (line, String::default()));
+                    }
+                }
+            }
+        }
+    }
+    linter.finish()
+}
+
+#[derive(Default)]
+struct Linter {
+    issues: Vec<LintIssue>,
+}
+
+impl Linter {
+    fn error(&mut self, row: u64, column: Option<&str>, message: String) {
+        self.push(row, LintSeverity::Error, column, message);
+    }
+
+    fn warning(&mut self, row: u64, column: Option<&str>, message: String) {
+        self.push(row, LintSeverity::Warning, column, message);
+    }
+
+    fn push(&mut self, row: u64, severity: LintSeverity, column: Option<&str>, message: String) {
+        self.issues.push(LintIssue {
+            row,
+            severity,
+            column: column.map(String::from),
+            message,
+        });
+    }
+
+    /// Lists the issues in the order of the file
+    fn finish(mut self) -> ListOfLintIssues {
+        self.issues.sort_by_key(|issue| issue.row);
+        ListOfLintIssues::new(self.issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_lint() {
+        let uuid = Uuid::new_v4();
+        let input = format!(
+            "modelId,name,value\n{uuid},Material,steel\nbolt,Material,steel\n{uuid},Material,ABS\n{uuid},Finish\n{uuid},Finish,\n\"{uuid}\",material,steel\n"
+        );
+        let issues = lint(
+            input.as_bytes(),
+            &InputKind::Meta(MetadataColumns::default()),
+            Path::new(""),
+        );
+        let issues: Vec<(u64, LintSeverity)> = issues
+            .issues
+            .into_iter()
+            .map(|issue| (issue.row, issue.severity))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (3, LintSeverity::Error),
+                (4, LintSeverity::Error),
+                (5, LintSeverity::Error),
+                (5, LintSeverity::Warning),
+                (6, LintSeverity::Warning),
+                (6, LintSeverity::Warning),
+                (7, LintSeverity::Warning),
+            ]
+        );
+
+        let issues = lint(b"FILE,Material\n", &InputKind::Manifest, Path::new(""));
+        assert_eq!(issues.issues[0].column.as_deref(), Some(FOLDER_COLUMN));
+
+        let issues = lint(
+            b"FILE,FOLDER\nbr\xfcckenteil.stl,Parts\n",
+            &InputKind::Manifest,
+            Path::new(""),
+        );
+        assert_eq!(issues.count(LintSeverity::Error), 2);
+    }
+}
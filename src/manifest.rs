@@ -0,0 +1,251 @@
+use crate::deadline::NOT_STARTED;
+use crate::model::{
+    ListOfManifestUploads, ManifestStatus, ManifestUpload, ModelMetadata, ModelMetadataItem,
+};
+use crate::service::{Api, ApiError};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// The column with the path of the file to upload
+pub(crate) const FILE_COLUMN: &str = "FILE";
+/// The column with the name of the target folder
+pub(crate) const FOLDER_COLUMN: &str = "FOLDER";
+
+/// The column with the reason why a file failed to upload
+const ERROR_COLUMN: &str = "ERROR";
+
+/// One row of the manifest
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManifestEntry {
+    pub file: PathBuf,
+    pub folder: String,
+    pub metadata: ModelMetadata,
+}
+
+/// Reads a CSV manifest with a FILE and a FOLDER column. Every other column is a metadata property set for the
+/// uploaded model, unless its value is empty. Relative paths are resolved against the directory of the manifest
+pub fn read_manifest(path: &Path) -> Result<Vec<ManifestEntry>, ApiError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.to_owned();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ApiError::FailedToRead(format!("the manifest has no {} column", name)))
+    };
+    let file_column = column(FILE_COLUMN)?;
+    let folder_column = column(FOLDER_COLUMN)?;
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let file = record.get(file_column).unwrap_or_default().trim();
+        let folder = record.get(folder_column).unwrap_or_default().trim();
+        if file.is_empty() {
+            continue;
+        }
+
+        let properties = headers
+            .iter()
+            .zip(record.iter())
+            .enumerate()
+            .filter(|(index, (_, value))| {
+                *index != file_column && *index != folder_column && !value.is_empty()
+            })
+            .map(|(_, (name, value))| {
+                ModelMetadataItem::new(0, name.trim().to_owned(), value.to_owned())
+            })
+            .collect();
+        entries.push(ManifestEntry {
+            file: base.join(file),
+            folder: folder.to_owned(),
+            metadata: ModelMetadata::new(properties),
+        });
+    }
+    Ok(entries)
+}
+
+/// Writes the files that failed to upload, with the reasons, as CSV with a FILE and an ERROR column. The paths are
+/// absolute, so that the files can be retried from any directory with [`read_failures`]
+pub fn write_failures(path: &Path, failures: &[(PathBuf, String)]) -> Result<(), ApiError> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record([FILE_COLUMN, ERROR_COLUMN])?;
+    for (file, error) in failures {
+        let file = std::fs::canonicalize(file).unwrap_or_else(|_| file.to_owned());
+        writer.write_record([file.to_string_lossy().as_ref(), error.as_str()])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the files from a CSV file written by [`write_failures`]
+pub fn read_failures(path: &Path) -> Result<Vec<PathBuf>, ApiError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let file_column = reader
+        .headers()?
+        .iter()
+        .position(|header| header.trim().eq_ignore_ascii_case(FILE_COLUMN))
+        .ok_or_else(|| ApiError::FailedToRead(format!("the file has no {} column", FILE_COLUMN)))?;
+
+    let mut files = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let file = record.get(file_column).unwrap_or_default().trim();
+        if !file.is_empty() {
+            files.push(PathBuf::from(file));
+        }
+    }
+    Ok(files)
+}
+
+/// Writes the entries as a manifest, e.g. those that are left to upload. The paths are absolute, so that the manifest
+/// can be uploaded from any directory
+pub fn write_manifest(path: &Path, entries: &[&ManifestEntry]) -> Result<(), ApiError> {
+    let mut properties: Vec<&str> = Vec::new();
+    for entry in entries {
+        for property in entry.metadata.properties.iter() {
+            if !properties.contains(&property.name.as_str()) {
+                properties.push(property.name.as_str());
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_path(path)?;
+    let mut header = vec![FILE_COLUMN, FOLDER_COLUMN];
+    header.extend(properties.iter());
+    writer.write_record(&header)?;
+    for entry in entries {
+        let file = std::fs::canonicalize(&entry.file).unwrap_or_else(|_| entry.file.to_owned());
+        let mut record = vec![file.to_string_lossy().to_string(), entry.folder.to_owned()];
+        record.extend(properties.iter().map(|name| {
+            entry
+                .metadata
+                .properties
+                .iter()
+                .find(|property| property.name == *name)
+                .map(|property| property.value.to_owned())
+                .unwrap_or_default()
+        }));
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Uploads the file of every entry into its folder and applies its metadata. Failing to upload one file does not
+/// stop the others, but the entries left once the run is out of time are not started. The report lists the outcome
+/// for each entry, in the order of the manifest
+pub fn upload_manifest(api: &Api, entries: &[ManifestEntry]) -> ListOfManifestUploads {
+    let uploads = entries
+        .iter()
+        .map(|entry| {
+            if api.deadline().exceeded() {
+                return ManifestUpload {
+                    file: entry.file.to_string_lossy().to_string(),
+                    folder: entry.folder.to_owned(),
+                    uuid: None,
+                    status: ManifestStatus::NotStarted,
+                    error: Some(String::from(NOT_STARTED)),
+                };
+            }
+            log::trace!("Uploading file {}...", entry.file.to_string_lossy());
+            let (uuid, error) = match upload(api, entry) {
+                Ok(uuid) => (Some(uuid), None),
+                Err((uuid, e)) => {
+                    log::warn!("Failed to upload {}: {}", entry.file.to_string_lossy(), e);
+                    (uuid, Some(e.to_string()))
+                }
+            };
+            ManifestUpload {
+                file: entry.file.to_string_lossy().to_string(),
+                folder: entry.folder.to_owned(),
+                uuid,
+                status: match error {
+                    Some(_) => ManifestStatus::Failed,
+                    None => ManifestStatus::Uploaded,
+                },
+                error,
+            }
+        })
+        .collect();
+    ListOfManifestUploads::new(uploads)
+}
+
+/// Returns the UUID of the new model. If only the metadata could not be set, the error comes with the UUID
+fn upload(api: &Api, entry: &ManifestEntry) -> Result<Uuid, (Option<Uuid>, ApiError)> {
+    if entry.folder.is_empty() {
+        return Err((
+            None,
+            ApiError::FailedToRead("the target folder is missing".to_string()),
+        ));
+    }
+    if !entry.file.is_file() {
+        return Err((
+            None,
+            ApiError::FailedToRead(format!("{} is not a file", entry.file.to_string_lossy())),
+        ));
+    }
+
+    let model = match api.upload_model(&entry.folder, &entry.file) {
+        Ok(Some(model)) => model,
+        Ok(None) => {
+            return Err((
+                None,
+                ApiError::FailedToRead(format!(
+                    "no model was created when uploading {}",
+                    entry.file.to_string_lossy()
+                )),
+            ))
+        }
+        Err(e) => return Err((None, e)),
+    };
+
+    if !entry.metadata.properties.is_empty() {
+        api.set_model_metadata(&model.uuid, &entry.metadata)
+            .map_err(|e| (Some(model.uuid), e))?;
+    }
+    Ok(model.uuid)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_read_manifest() {
+        let directory = tempfile::tempdir().unwrap();
+        let manifest = directory.path().join("manifest.csv");
+        fs::write(
+            &manifest,
+            "file,folder,Material,Revision\nparts/bracket.stl,Parts,steel,\n,Parts,,\n/data/housing.step,Housings,ABS,B\n",
+        )
+        .unwrap();
+
+        let entries = read_manifest(&manifest).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ManifestEntry {
+                    file: directory.path().join("parts/bracket.stl"),
+                    folder: "Parts".to_string(),
+                    metadata: ModelMetadata::new(vec![ModelMetadataItem::new(
+                        0,
+                        "Material".to_string(),
+                        "steel".to_string()
+                    )]),
+                },
+                ManifestEntry {
+                    file: PathBuf::from("/data/housing.step"),
+                    folder: "Housings".to_string(),
+                    metadata: ModelMetadata::new(vec![
+                        ModelMetadataItem::new(0, "Material".to_string(), "ABS".to_string()),
+                        ModelMetadataItem::new(0, "Revision".to_string(), "B".to_string()),
+                    ]),
+                },
+            ]
+        );
+    }
+}
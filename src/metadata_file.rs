@@ -0,0 +1,461 @@
+use crate::model::ModelMetadataItemShort;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+#[allow(clippy::enum_variant_names)]
+pub enum MetadataFileError {
+    #[error("I/O error: {0}")]
+    InputOutputError(#[from] io::Error),
+    #[error("Invalid JSON: {0}")]
+    JsonParsingError(#[from] serde_json::Error),
+    #[error("Invalid spreadsheet: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("Invalid spreadsheet: {0}")]
+    XmlError(#[from] quick_xml::Error),
+    #[error("Invalid spreadsheet: {0}")]
+    SpreadsheetError(String),
+}
+
+/// One record of a metadata file: its row (or its position in a JSON array, starting at 1) and the item, or why
+/// it cannot be read
+pub type MetadataRow = (u64, Result<ModelMetadataItemShort, String>);
+
+/// The columns (or the properties of the JSON objects) a metadata file is read from. The names are not case-sensitive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataColumns {
+    pub uuid: String,
+    pub name: String,
+    pub value: String,
+    /// Every column other than the UUID is a property, named by its header. Empty values are skipped
+    pub wide: bool,
+}
+
+impl Default for MetadataColumns {
+    fn default() -> Self {
+        MetadataColumns {
+            uuid: "modelId".to_string(),
+            name: "name".to_string(),
+            value: "value".to_string(),
+            wide: false,
+        }
+    }
+}
+
+impl MetadataColumns {
+    /// Reads the items of one record, given as pairs of a column and its value
+    fn read(&self, row: u64, fields: &[(String, String)]) -> Vec<MetadataRow> {
+        let field = |column: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case(column))
+                .map(|(_, value)| value.to_owned())
+                .ok_or_else(|| format!("the column '{}' is missing", column))
+        };
+        let uuid = match field(&self.uuid).and_then(|uuid| {
+            Uuid::parse_str(uuid.trim()).map_err(|_| format!("'{}' is not a valid UUID", uuid))
+        }) {
+            Ok(uuid) => uuid,
+            Err(reason) => return vec![(row, Err(reason))],
+        };
+
+        if self.wide {
+            return fields
+                .iter()
+                .filter(|(name, value)| {
+                    !name.trim().is_empty()
+                        && !name.trim().eq_ignore_ascii_case(&self.uuid)
+                        && !value.is_empty()
+                })
+                .map(|(name, value)| {
+                    let item = ModelMetadataItemShort {
+                        model_uuid: uuid,
+                        name: name.trim().to_owned(),
+                        value: value.to_owned(),
+                    };
+                    (row, Ok(item))
+                })
+                .collect();
+        }
+
+        let item = field(&self.name).and_then(|name| {
+            Ok(ModelMetadataItemShort {
+                model_uuid: uuid,
+                name,
+                value: field(&self.value)?,
+            })
+        });
+        vec![(row, item)]
+    }
+}
+
+/// Reads a metadata file with a column for the model UUID, and either a column for the name and the value of the
+/// property or a column for every property. The format is selected by the extension: ".json" for an array of
+/// objects, ".xlsx" for the first worksheet of a spreadsheet with a header row, and CSV for any other extension. A
+/// record that cannot be read is returned with the reason, so that the others can still be used
+pub fn read_metadata_file(
+    path: &Path,
+    columns: &MetadataColumns,
+) -> Result<Vec<MetadataRow>, MetadataFileError> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("json") => read_json(&fs::read_to_string(path)?, columns),
+        Some("xlsx") => Ok(records_to_rows(read_xlsx(path)?, columns)),
+        _ => Ok(read_csv(File::open(path)?, columns)),
+    }
+}
+
+pub fn read_csv<R: Read>(reader: R, columns: &MetadataColumns) -> Vec<MetadataRow> {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    let reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+    for (index, record) in reader.into_records().enumerate() {
+        let row = index as u64 + 1;
+        match record {
+            Ok(record) => records.push((row, record.iter().map(String::from).collect())),
+            Err(e) => errors.push((row, Err(e.to_string()))),
+        }
+    }
+    let mut rows = records_to_rows(records, columns);
+    if !errors.is_empty() {
+        rows.extend(errors);
+        rows.sort_by_key(|(row, _)| *row);
+    }
+    rows
+}
+
+fn read_json(json: &str, columns: &MetadataColumns) -> Result<Vec<MetadataRow>, MetadataFileError> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(json)?;
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, value)| {
+            let row = index as u64 + 1;
+            let object = match value {
+                serde_json::Value::Object(object) => object,
+                _ => return vec![(row, Err("not an object".to_string()))],
+            };
+            let fields: Vec<(String, String)> = object
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value,
+                        serde_json::Value::Null => String::default(),
+                        value => value.to_string(),
+                    };
+                    (name, value)
+                })
+                .collect();
+            columns.read(row, &fields)
+        })
+        .collect())
+}
+
+/// Reads the records below the header row. Empty rows are skipped
+fn records_to_rows(
+    records: Vec<(u64, Vec<String>)>,
+    columns: &MetadataColumns,
+) -> Vec<MetadataRow> {
+    let mut records = records.into_iter();
+    let headers = match records.next() {
+        Some((_, headers)) => headers,
+        None => return Vec::new(),
+    };
+    records
+        .filter(|(_, values)| values.iter().any(|value| !value.is_empty()))
+        .flat_map(|(row, values)| {
+            // trailing empty cells are not stored
+            let fields: Vec<(String, String)> = headers
+                .iter()
+                .enumerate()
+                .map(|(index, header)| {
+                    let value = values.get(index).cloned().unwrap_or_default();
+                    (header.to_owned(), value)
+                })
+                .collect();
+            columns.read(row, &fields)
+        })
+        .collect()
+}
+
+/// Reads the cells of the first worksheet as text, with the number of each row. Missing cells are empty
+pub fn read_xlsx(path: &Path) -> Result<Vec<(u64, Vec<String>)>, MetadataFileError> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+
+    let shared_strings = match archive.by_name("xl/sharedStrings.xml") {
+        Ok(file) => read_shared_strings(file)?,
+        Err(zip::result::ZipError::FileNotFound) => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let sheet = first_sheet(&mut archive)?;
+    let file = archive.by_name(&sheet)?;
+    read_sheet(file, &shared_strings)
+}
+
+/// Returns the path of the first worksheet in the archive, as listed by the workbook
+fn first_sheet<R: Read + io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<String, MetadataFileError> {
+    let mut relation = None;
+    for_each_element(archive.by_name("xl/workbook.xml")?, |name, attributes| {
+        if name == b"sheet" && relation.is_none() {
+            relation = attributes.get("r:id").cloned();
+        }
+    })?;
+    let relation = relation
+        .ok_or_else(|| MetadataFileError::SpreadsheetError("there is no worksheet".to_string()))?;
+
+    let mut target = None;
+    for_each_element(
+        archive.by_name("xl/_rels/workbook.xml.rels")?,
+        |name, attributes| {
+            if name == b"Relationship" && attributes.get("Id") == Some(&relation) {
+                target = attributes.get("Target").cloned();
+            }
+        },
+    )?;
+    match target {
+        // the target is relative to the "xl" directory, unless it is absolute
+        Some(target) => match target.strip_prefix('/') {
+            Some(target) => Ok(target.to_owned()),
+            None => Ok(format!("xl/{}", target)),
+        },
+        None => Err(MetadataFileError::SpreadsheetError(format!(
+            "the worksheet {} is missing",
+            relation
+        ))),
+    }
+}
+
+/// Calls the function with the name and the attributes of every element
+fn for_each_element<R: Read, F>(file: R, mut f: F) -> Result<(), MetadataFileError>
+where
+    F: FnMut(&[u8], &HashMap<String, String>),
+{
+    let mut reader = quick_xml::Reader::from_reader(BufReader::new(file));
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => {
+                let mut attributes = HashMap::new();
+                for attribute in e.attributes() {
+                    let attribute = attribute.map_err(quick_xml::Error::InvalidAttr)?;
+                    attributes.insert(
+                        String::from_utf8_lossy(attribute.key).to_string(),
+                        attribute.unescape_and_decode_value(&reader)?,
+                    );
+                }
+                f(e.local_name(), &attributes);
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+fn read_shared_strings<R: Read>(file: R) -> Result<Vec<String>, MetadataFileError> {
+    let mut reader = quick_xml::Reader::from_reader(BufReader::new(file));
+    let mut buf = Vec::new();
+    let mut strings = Vec::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(e) if e.local_name() == b"si" => strings.push(String::new()),
+            Event::Start(e) if e.local_name() == b"t" => in_text = true,
+            Event::End(e) if e.local_name() == b"t" => in_text = false,
+            Event::Text(text) if in_text => {
+                if let Some(string) = strings.last_mut() {
+                    string.push_str(&text.unescape_and_decode(&reader)?);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(strings)
+}
+
+fn read_sheet<R: Read>(
+    file: R,
+    shared_strings: &[String],
+) -> Result<Vec<(u64, Vec<String>)>, MetadataFileError> {
+    let mut reader = quick_xml::Reader::from_reader(BufReader::new(file));
+    let mut buf = Vec::new();
+    let mut rows: Vec<(u64, Vec<String>)> = Vec::new();
+    // the column and the type of the current cell
+    let mut cell: Option<(usize, String)> = None;
+    let mut in_value = false;
+    let mut value = String::new();
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(e) if e.local_name() == b"row" => {
+                let mut number = rows.last().map(|(row, _)| row + 1).unwrap_or(1);
+                for attribute in e.attributes() {
+                    let attribute = attribute.map_err(quick_xml::Error::InvalidAttr)?;
+                    if attribute.key == b"r" {
+                        if let Ok(n) = attribute.unescape_and_decode_value(&reader)?.parse() {
+                            number = n;
+                        }
+                    }
+                }
+                rows.push((number, Vec::new()));
+            }
+            Event::Start(e) if e.local_name() == b"c" => {
+                let mut column = rows.last().map(|(_, values)| values.len()).unwrap_or(0);
+                let mut cell_type = String::from("n");
+                for attribute in e.attributes() {
+                    let attribute = attribute.map_err(quick_xml::Error::InvalidAttr)?;
+                    match attribute.key {
+                        b"r" => {
+                            if let Some(index) =
+                                column_index(&attribute.unescape_and_decode_value(&reader)?)
+                            {
+                                column = index;
+                            }
+                        }
+                        b"t" => cell_type = attribute.unescape_and_decode_value(&reader)?,
+                        _ => (),
+                    }
+                }
+                cell = Some((column, cell_type));
+                value.clear();
+            }
+            Event::Start(e) if e.local_name() == b"v" || e.local_name() == b"t" => in_value = true,
+            Event::End(e) if e.local_name() == b"v" || e.local_name() == b"t" => in_value = false,
+            Event::Text(text) if in_value => value.push_str(&text.unescape_and_decode(&reader)?),
+            Event::End(e) if e.local_name() == b"c" => {
+                if let (Some((column, cell_type)), Some((_, values))) =
+                    (cell.take(), rows.last_mut())
+                {
+                    let text = match cell_type.as_str() {
+                        "s" => value
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| shared_strings.get(index))
+                            .cloned()
+                            .unwrap_or_default(),
+                        "b" => (value.trim() == "1").to_string(),
+                        _ => value.to_owned(),
+                    };
+                    if values.len() <= column {
+                        values.resize(column + 1, String::new());
+                    }
+                    values[column] = text;
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(rows)
+}
+
+/// The zero-based column of a cell reference such as "B7"
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: Vec<u8> = reference
+        .bytes()
+        .take_while(|b| b.is_ascii_alphabetic())
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let number = letters.iter().fold(0usize, |number, letter| {
+        number * 26 + (letter - b'A' + 1) as usize
+    });
+    Some(number - 1)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rust_xlsxwriter::Workbook;
+
+    #[test]
+    fn test_read_metadata_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let uuid = Uuid::new_v4();
+
+        let json = directory.path().join("metadata.json");
+        fs::write(
+            &json,
+            format!(
+                r#"[{{"modelId": "{}", "name": "Material", "value": "steel"}}, {{"name": "Weight"}}]"#,
+                uuid
+            ),
+        )
+        .unwrap();
+        let rows = read_metadata_file(&json, &MetadataColumns::default()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].1.as_ref().unwrap().value, "steel");
+        assert!(rows[1].1.is_err());
+
+        let csv = format!("Part,Material,Finish\n{},steel,\nbolt,ABS,zinc\n", uuid);
+        let columns = MetadataColumns {
+            uuid: "part".to_string(),
+            wide: true,
+            ..Default::default()
+        };
+        let rows = read_csv(csv.as_bytes(), &columns);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].1.as_ref().unwrap().name, "Material");
+        assert_eq!(rows[1], (3, Err("'bolt' is not a valid UUID".to_string())));
+
+        let xlsx = directory.path().join("metadata.xlsx");
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        for (column, header) in ["modelId", "name", "value"].iter().enumerate() {
+            sheet.write_string(0, column as u16, *header).unwrap();
+        }
+        sheet.write_string(1, 0, uuid.to_string()).unwrap();
+        sheet.write_string(1, 1, "Weight").unwrap();
+        sheet.write_number(1, 2, 2.5).unwrap();
+        sheet.write_string(3, 0, uuid.to_string()).unwrap();
+        sheet.write_string(3, 1, "Finish").unwrap();
+        workbook.save(&xlsx).unwrap();
+
+        let rows: Vec<(u64, ModelMetadataItemShort)> =
+            read_metadata_file(&xlsx, &MetadataColumns::default())
+                .unwrap()
+                .into_iter()
+                .map(|(row, item)| (row, item.unwrap()))
+                .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    2,
+                    ModelMetadataItemShort {
+                        model_uuid: uuid,
+                        name: "Weight".to_string(),
+                        value: "2.5".to_string()
+                    }
+                ),
+                (
+                    4,
+                    ModelMetadataItemShort {
+                        model_uuid: uuid,
+                        name: "Finish".to_string(),
+                        value: String::default()
+                    }
+                ),
+            ]
+        );
+    }
+}
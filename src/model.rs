@@ -1,5 +1,7 @@
 use crate::client;
-use csv::{Terminator, Writer, WriterBuilder};
+use crate::schema::{self, PropertyType};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use csv::{QuoteStyle, StringRecord, Terminator, Writer, WriterBuilder};
 use log::trace;
 use petgraph::matrix_graph::MatrixGraph;
 use ptree::style::Style;
@@ -7,12 +9,15 @@ use ptree::TreeItem;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io;
 use std::io::BufWriter;
 use std::iter::Extend;
 use std::iter::IntoIterator;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::vec::IntoIter;
 use thiserror::Error;
 use uuid::Uuid;
@@ -28,9 +33,18 @@ pub enum ParsingError {
     #[error("Failed to extract value from byte buffer")]
     FailedToExtractValueFromByteBuffer(#[from] std::io::IntoInnerError<BufWriter<Vec<u8>>>),
     #[error("Failed to extract value from CSV buffer")]
-    FailedToExtractValueFromCsvBuffer(#[from] csv::IntoInnerError<Writer<BufWriter<Vec<u8>>>>),
+    FailedToExtractValueFromCsvBuffer(Box<csv::IntoInnerError<Writer<BufWriter<Vec<u8>>>>>),
     #[error("Conversion error")]
     ConversionError(#[from] std::string::FromUtf8Error),
+    #[error("JSON error")]
+    JsonError(#[from] serde_json::Error),
+}
+
+// the CSV writer is boxed, so that it does not make every parsing result large
+impl From<csv::IntoInnerError<Writer<BufWriter<Vec<u8>>>>> for ParsingError {
+    fn from(error: csv::IntoInnerError<Writer<BufWriter<Vec<u8>>>>) -> Self {
+        ParsingError::FailedToExtractValueFromCsvBuffer(Box::new(error))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -52,9 +66,266 @@ pub trait ToJson {
     fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error>;
 }
 
-/// Marshals the state into CSV
+/// Marshals the state into YAML
+pub trait ToYaml {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error>;
+}
+
+/// Marshals the state into CSV in the default dialect, starting with a header row if requested
 pub trait ToCsv {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError>;
+    fn to_csv(&self, header: bool) -> Result<String, ParsingError>;
+}
+
+impl<T: WriteCsv> ToCsv for T {
+    fn to_csv(&self, header: bool) -> Result<String, ParsingError> {
+        let dialect = CsvDialect {
+            header,
+            ..CsvDialect::default()
+        };
+        write_to_string(|bytes| {
+            let mut writer = CsvWriter::new(bytes, &dialect, None);
+            self.write_csv(&mut writer)?;
+            Ok(writer.flush()?)
+        })
+    }
+}
+
+/// Writes the state as JSON directly into a writer, without building the document in memory
+pub trait WriteJson {
+    fn write_json<W: io::Write>(&self, writer: W, pretty: bool) -> Result<(), serde_json::Error>;
+}
+
+/// The dialect of the CSV output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvDialect {
+    pub delimiter: u8,
+    /// Quotes every field, not only those that contain delimiters, quotes or line breaks
+    pub quote_all: bool,
+    /// Ends the lines with CRLF instead of LF
+    pub crlf: bool,
+    /// Starts with a header row containing the column names
+    pub header: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect {
+            delimiter: b',',
+            quote_all: false,
+            crlf: true,
+            header: true,
+        }
+    }
+}
+
+impl CsvDialect {
+    pub fn writer_builder(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote_style(if self.quote_all {
+                QuoteStyle::Always
+            } else {
+                QuoteStyle::Necessary
+            })
+            .terminator(if self.crlf {
+                Terminator::CRLF
+            } else {
+                Terminator::Any(b'\n')
+            });
+        builder
+    }
+}
+
+/// Writes the state as CSV directly into a writer, one record at a time, starting with the header row
+pub trait WriteCsv {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError>;
+}
+
+/// Column names match regardless of case and underscores, so that e.g. FOLDER_ID (CSV) is also folderId (JSON)
+pub fn same_column(name: &str, column: &str) -> bool {
+    let normalize = |name: &str| name.replace('_', "").to_lowercase();
+    normalize(name) == normalize(column)
+}
+
+/// Where the records written by a `CsvWriter` go
+enum CsvOutput<W: io::Write> {
+    Document(Box<Writer<W>>),
+    /// Kept in memory with the header row, e.g. to be shown as a table
+    Records(Vec<StringRecord>),
+}
+
+/// Writes CSV records in a dialect, restricted to the selected columns in their order. The columns are selected by
+/// the names in the header row, which is therefore always passed to `write_header`, even if the dialect leaves it
+/// out. Columns that are not in the header (e.g. a property that none of the models has) are left empty
+pub struct CsvWriter<W: io::Write> {
+    output: CsvOutput<W>,
+    header: bool,
+    columns: Option<Vec<String>>,
+    indexes: Option<Vec<Option<usize>>>,
+}
+
+impl<W: io::Write> CsvWriter<W> {
+    pub fn new(writer: W, dialect: &CsvDialect, columns: Option<&[String]>) -> Self {
+        CsvWriter {
+            output: CsvOutput::Document(Box::new(dialect.writer_builder().from_writer(writer))),
+            header: dialect.header,
+            columns: columns.map(|columns| columns.to_vec()),
+            indexes: None,
+        }
+    }
+
+    pub fn write_header<I, T>(&mut self, record: I) -> Result<(), csv::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let names: Vec<String> = record
+            .into_iter()
+            .map(|name| String::from_utf8_lossy(name.as_ref()).into_owned())
+            .collect();
+
+        let names = match &self.columns {
+            Some(columns) => {
+                let indexes: Vec<Option<usize>> = columns
+                    .iter()
+                    .map(|column| {
+                        let index = names.iter().position(|name| same_column(name, column));
+                        if index.is_none() {
+                            log::warn!("Column '{}' is not present in the output", column);
+                        }
+                        index
+                    })
+                    .collect();
+                let projected = columns
+                    .iter()
+                    .zip(&indexes)
+                    .map(|(column, index)| index.map_or(column, |i| &names[i]).to_owned())
+                    .collect();
+                self.indexes = Some(indexes);
+                projected
+            }
+            None => names,
+        };
+
+        match &mut self.output {
+            CsvOutput::Document(writer) if self.header => writer.write_record(&names),
+            CsvOutput::Document(_) => Ok(()),
+            CsvOutput::Records(records) => {
+                records.push(StringRecord::from(names));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn write_record<I, T>(&mut self, record: I) -> Result<(), csv::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        match &self.indexes {
+            Some(indexes) => {
+                let fields: Vec<T> = record.into_iter().collect();
+                let projected: Vec<&[u8]> = indexes
+                    .iter()
+                    .map(|index| {
+                        index
+                            .and_then(|i| fields.get(i))
+                            .map(|field| field.as_ref())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                self.output.write(projected)
+            }
+            None => self.output.write(record),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.output {
+            CsvOutput::Document(writer) => writer.flush(),
+            CsvOutput::Records(_) => Ok(()),
+        }
+    }
+}
+
+impl CsvWriter<io::Sink> {
+    /// Keeps the records in memory instead of writing a document. The header row is always kept
+    pub fn records(columns: Option<&[String]>) -> Self {
+        CsvWriter {
+            output: CsvOutput::Records(Vec::new()),
+            header: true,
+            columns: columns.map(|columns| columns.to_vec()),
+            indexes: None,
+        }
+    }
+
+    /// The records written so far, starting with the header row
+    pub fn into_records(self) -> Vec<StringRecord> {
+        match self.output {
+            CsvOutput::Records(records) => records,
+            CsvOutput::Document(_) => Vec::new(),
+        }
+    }
+}
+
+impl<W: io::Write> CsvOutput<W> {
+    fn write<I, T>(&mut self, record: I) -> Result<(), csv::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        match self {
+            CsvOutput::Document(writer) => writer.write_record(record),
+            CsvOutput::Records(records) => {
+                records.push(
+                    record
+                        .into_iter()
+                        .map(|field| String::from_utf8_lossy(field.as_ref()).into_owned())
+                        .collect(),
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes the records as JSON Lines: one JSON object per line, so that they can be consumed as a stream
+pub trait WriteJsonLines {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error>;
+
+    fn to_json_lines(&self) -> Result<String, ParsingError> {
+        write_to_string(|bytes| Ok(self.write_json_lines(bytes)?))
+    }
+}
+
+/// Writes each record on its own line. There is no new line after the last record
+pub(crate) fn write_json_lines<'a, T, I, W>(
+    mut writer: W,
+    records: I,
+) -> Result<(), serde_json::Error>
+where
+    T: Serialize + 'a,
+    I: IntoIterator<Item = &'a T>,
+    W: io::Write,
+{
+    for (i, record) in records.into_iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+        }
+        serde_json::to_writer(&mut writer, record)?;
+    }
+    Ok(())
+}
+
+/// Renders a streamed document into a string, for callers that still need the whole text
+fn write_to_string<F>(write: F) -> Result<String, ParsingError>
+where
+    F: FnOnce(&mut Vec<u8>) -> Result<(), ParsingError>,
+{
+    let mut bytes: Vec<u8> = Vec::new();
+    write(&mut bytes)?;
+    Ok(String::from_utf8(bytes)?)
 }
 
 /// Marshals the state into HTML
@@ -82,6 +353,18 @@ impl Folder {
     pub fn new(id: u32, name: String) -> Self {
         Folder { id, name }
     }
+
+    /// Returns true if the name of the folder is the given name, or matches it as a glob pattern (e.g. 'PROJ-*')
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name
+            || (is_folder_pattern(name)
+                && glob::Pattern::new(name).is_ok_and(|pattern| pattern.matches(&self.name)))
+    }
+}
+
+/// Returns true if the folder name contains glob wildcards and is to be matched against the existing folders
+pub fn is_folder_pattern(name: &str) -> bool {
+    name.contains(['*', '?', '['])
 }
 
 impl Ord for Folder {
@@ -92,7 +375,7 @@ impl Ord for Folder {
 
 impl PartialOrd for Folder {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.name.cmp(&other.name))
+        Some(self.cmp(other))
     }
 }
 
@@ -118,28 +401,21 @@ impl ToJson for Folder {
     }
 }
 
-impl ToCsv for Folder {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
-
-        if pretty {
-            let columns = vec!["ID", "NAME"];
-            writer.write_record(&columns)?;
-        }
+impl ToYaml for Folder {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
 
-        let mut values: Vec<String> = Vec::new();
+impl WriteCsv for Folder {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["ID", "NAME"];
+        writer.write_header(&columns)?;
 
-        values.push(self.id.to_string());
-        values.push(self.name.to_owned());
+        let values: Vec<String> = vec![self.id.to_string(), self.name.to_owned()];
         writer.write_record(&values)?;
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -171,9 +447,7 @@ impl<'a> IntoIterator for &'a ListOfFolders {
 
 impl ListOfFolders {
     pub fn get_folder_by_id(&self, id: &u32) -> Option<&Folder> {
-        self.folders
-            .iter()
-            .find(|&folder| folder.id == id.to_owned())
+        self.folders.iter().find(|&folder| folder.id == *id)
     }
 
     pub fn get_folder_by_name(&self, name: &str) -> Option<&Folder> {
@@ -192,37 +466,245 @@ impl ToJson for ListOfFolders {
     }
 }
 
-impl ToCsv for ListOfFolders {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
+impl ToYaml for ListOfFolders {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.folders)
+    }
+}
+
+impl WriteJsonLines for ListOfFolders {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.folders)
+    }
+}
+
+impl WriteCsv for ListOfFolders {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
         let folders = self.folders.clone();
 
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+        let columns = vec!["ID", "NAME"];
+        writer.write_header(&columns)?;
+
+        for folder in folders {
+            let values: Vec<String> = vec![folder.id.to_string(), folder.name];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A folder with the number of models in it and optionally their breakdown by state
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct FolderCounts {
+    #[serde(rename = "id")]
+    pub id: u32,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "models")]
+    pub models: u32,
+    #[serde(rename = "states", skip_serializing_if = "BTreeMap::is_empty")]
+    pub states: BTreeMap<String, u32>,
+}
+
+impl FolderCounts {
+    pub fn new(folder: Folder, models: u32) -> Self {
+        Self {
+            id: folder.id,
+            name: folder.name,
+            models,
+            states: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfFolderCounts {
+    #[serde(rename = "folders")]
+    pub folders: Vec<FolderCounts>,
+}
+
+impl From<Vec<FolderCounts>> for ListOfFolderCounts {
+    fn from(folders: Vec<FolderCounts>) -> Self {
+        ListOfFolderCounts { folders }
+    }
+}
 
+impl ToJson for ListOfFolderCounts {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
-            let columns = vec!["ID", "NAME"];
-            writer.write_record(&columns)?;
+            serde_json::to_string_pretty(&self.folders)
+        } else {
+            serde_json::to_string(&self.folders)
         }
+    }
+}
 
-        for folder in folders {
-            let mut values: Vec<String> = Vec::new();
+impl ToYaml for ListOfFolderCounts {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.folders)
+    }
+}
+
+impl WriteJsonLines for ListOfFolderCounts {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.folders)
+    }
+}
+
+impl WriteCsv for ListOfFolderCounts {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        // one column for each state found in any of the folders
+        let mut states: Vec<&String> = self
+            .folders
+            .iter()
+            .flat_map(|f| f.states.keys())
+            .collect::<HashSet<&String>>()
+            .into_iter()
+            .collect();
+        states.sort();
 
-            values.push(folder.id.to_string());
-            values.push(folder.name);
+        let mut columns: Vec<&str> = vec!["ID", "NAME", "MODELS"];
+        columns.extend(states.iter().map(|s| s.as_str()));
+        writer.write_header(&columns)?;
+
+        for folder in &self.folders {
+            let mut values: Vec<String> = vec![
+                folder.id.to_string(),
+                folder.name.to_owned(),
+                folder.models.to_string(),
+            ];
+            for state in states.iter() {
+                values.push(folder.states.get(*state).unwrap_or(&0).to_string());
+            }
             writer.write_record(&values)?;
         }
 
-        writer.flush()?;
-
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
 // Implementing FromIterator for references to Folder
+/// A folder in one of several tenants
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantFolder {
+    pub tenant: String,
+    pub id: u32,
+    pub name: String,
+}
+
+/// The folders of several tenants, in one report
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfTenantFolders {
+    pub folders: Vec<TenantFolder>,
+}
+
+impl ListOfTenantFolders {
+    /// Adds the folders of the tenant to the report
+    pub fn extend(&mut self, tenant: &str, folders: ListOfFolders) {
+        self.folders
+            .extend(folders.folders.into_iter().map(|folder| TenantFolder {
+                tenant: tenant.to_owned(),
+                id: folder.id,
+                name: folder.name,
+            }));
+    }
+}
+
+impl ToJson for ListOfTenantFolders {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.folders)
+        } else {
+            serde_json::to_string(&self.folders)
+        }
+    }
+}
+
+impl ToYaml for ListOfTenantFolders {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.folders)
+    }
+}
+
+impl WriteJsonLines for ListOfTenantFolders {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.folders)
+    }
+}
+
+impl WriteCsv for ListOfTenantFolders {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        writer.write_header(["TENANT", "ID", "NAME"])?;
+
+        for folder in &self.folders {
+            writer.write_record([
+                folder.tenant.to_owned(),
+                folder.id.to_string(),
+                folder.name.to_owned(),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of folders or models in one of several tenants
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantCount {
+    pub tenant: String,
+    pub count: u64,
+}
+
+/// The counts of several tenants, in one report
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfTenantCounts {
+    pub counts: Vec<TenantCount>,
+}
+
+impl ListOfTenantCounts {
+    pub fn push(&mut self, tenant: &str, count: u64) {
+        self.counts.push(TenantCount {
+            tenant: tenant.to_owned(),
+            count,
+        });
+    }
+}
+
+impl ToJson for ListOfTenantCounts {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.counts)
+        } else {
+            serde_json::to_string(&self.counts)
+        }
+    }
+}
+
+impl ToYaml for ListOfTenantCounts {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.counts)
+    }
+}
+
+impl WriteJsonLines for ListOfTenantCounts {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.counts)
+    }
+}
+
+impl WriteCsv for ListOfTenantCounts {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        writer.write_header(["TENANT", "COUNT"])?;
+
+        for count in &self.counts {
+            writer.write_record([count.tenant.to_owned(), count.count.to_string()])?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<'a> FromIterator<&'a Folder> for ListOfFolders {
     fn from_iter<I: IntoIterator<Item = &'a Folder>>(iter: I) -> Self {
         let folders = iter.into_iter().cloned().collect();
@@ -232,7 +714,7 @@ impl<'a> FromIterator<&'a Folder> for ListOfFolders {
 
 impl From<Vec<Folder>> for ListOfFolders {
     fn from(folders: Vec<Folder>) -> Self {
-        let folders = folders.into_iter().map(|f| Folder::from(f)).collect();
+        let folders = folders.into_iter().collect();
         ListOfFolders { folders }
     }
 }
@@ -253,6 +735,9 @@ pub struct Model {
     #[serde(rename = "ownerId")]
     #[serde(default)]
     pub owner_id: String,
+    /// The name or email of the owner, if resolved from the list of users
+    #[serde(rename = "ownerName", default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "fileType")]
@@ -274,6 +759,46 @@ pub struct Model {
 }
 
 impl Model {
+    /// Returns true if the model is in a state that does not require repair
+    pub fn is_healthy(&self) -> bool {
+        self.state.eq_ignore_ascii_case("FINISHED") || self.state.eq_ignore_ascii_case("NO 3D DATA")
+    }
+
+    /// Returns true if the model could not be processed
+    pub fn is_failed(&self) -> bool {
+        self.state.eq_ignore_ascii_case("FAILED")
+    }
+
+    /// Returns true if the processing of the model has ended, successfully or not
+    pub fn is_processed(&self) -> bool {
+        self.is_healthy() || self.is_failed()
+    }
+
+    /// The time the model was created, unless the API reported an invalid one
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        parse_timestamp(&self.created_at)
+    }
+
+    /// Sets the owner from the map of user IDs to names. Unknown users are identified by their ID
+    pub fn resolve_owner(&mut self, owners: &HashMap<String, String>) {
+        self.owner = Some(
+            owners
+                .get(&self.owner_id)
+                .cloned()
+                .unwrap_or(self.owner_id.to_owned()),
+        );
+    }
+
+    /// Returns the value of the metadata property, ignoring the case of its name
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.metadata.as_ref().and_then(|metadata| {
+            metadata
+                .iter()
+                .find(|item| item.name.trim().eq_ignore_ascii_case(name.trim()))
+                .map(|item| item.value.as_str())
+        })
+    }
+
     pub fn get_metadata_as_properties(&self) -> Option<HashMap<String, String>> {
         match &self.metadata {
             Some(metadata) => {
@@ -288,6 +813,41 @@ impl Model {
     }
 }
 
+/// Reads a point in time given as an RFC 3339 timestamp (e.g. "2024-05-01T08:00:00Z") or as a date (e.g.
+/// "2024-05-01"), which stands for midnight UTC
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    match DateTime::parse_from_rfc3339(value) {
+        Ok(timestamp) => Some(timestamp.with_timezone(&Utc)),
+        Err(_) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|midnight| Utc.from_utc_datetime(&midnight)),
+    }
+}
+
+/// Remembers when an incremental run started, so that the next run only processes the models created since
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunState {
+    pub last_run: DateTime<Utc>,
+}
+
+impl RunState {
+    /// Returns None if there is no state file yet
+    pub fn read(path: &Path) -> Result<Option<Self>, ParsingError> {
+        if path.exists() {
+            Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ParsingError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
 use serde::de::Deserializer;
 fn deserialize_with_nullable_name<'de, D>(d: D) -> Result<String, D::Error>
 where
@@ -328,28 +888,29 @@ impl ToJson for PropertyCollection {
     }
 }
 
-impl ToCsv for PropertyCollection {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for PropertyCollection {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
 
-        if pretty {
-            let columns = vec!["ID", "NAME"];
-            writer.write_record(&columns)?;
-        }
+impl WriteJsonLines for PropertyCollection {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.properties)
+    }
+}
+
+impl WriteCsv for PropertyCollection {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["ID", "NAME"];
+        writer.write_header(&columns)?;
 
         for property in &self.properties {
-            let mut values: Vec<String> = Vec::new();
-            values.push(property.id.to_string());
-            values.push(property.name.to_owned());
+            let values: Vec<String> = vec![property.id.to_string(), property.name.to_owned()];
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        Ok(String::from_utf8(bytes)?)
+        Ok(())
     }
 }
 
@@ -446,29 +1007,25 @@ impl ModelMetadata {
         self.properties.push(new_item.to_owned());
     }
 
-    pub fn to_enhanced_csv(&self, uuid: &Uuid, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
-
-        if pretty {
-            let columns = vec!["UUID", "NAME", "VALUE"];
-            writer.write_record(&columns)?;
-        }
+    /// Writes the properties as CSV records that start with the UUID of the model
+    pub fn write_enhanced_csv<W: io::Write>(
+        &self,
+        uuid: &Uuid,
+        writer: &mut CsvWriter<W>,
+    ) -> Result<(), ParsingError> {
+        let columns = vec!["UUID", "NAME", "VALUE"];
+        writer.write_header(&columns)?;
 
         for property in &self.properties {
-            let mut values: Vec<String> = Vec::new();
-            values.push(uuid.to_string());
-            values.push(property.name.to_owned());
-            values.push(property.value.to_owned());
+            let values: Vec<String> = vec![
+                uuid.to_string(),
+                property.name.to_owned(),
+                property.value.to_owned(),
+            ];
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -482,29 +1039,23 @@ impl ToJson for ModelMetadata {
     }
 }
 
-impl ToCsv for ModelMetadata {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for ModelMetadata {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
 
-        if pretty {
-            let columns = vec!["NAME", "VALUE"];
-            writer.write_record(&columns)?;
-        }
+impl WriteCsv for ModelMetadata {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["NAME", "VALUE"];
+        writer.write_header(&columns)?;
 
         for property in &self.properties {
-            let mut values: Vec<String> = Vec::new();
-            values.push(property.name.to_owned());
-            values.push(property.value.to_owned());
+            let values: Vec<String> = vec![property.name.to_owned(), property.value.to_owned()];
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -518,14 +1069,15 @@ impl ToJson for Model {
     }
 }
 
-impl ToCsv for Model {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        log::trace!("Preparing CSV output for a model...");
+impl ToYaml for Model {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
 
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl WriteCsv for Model {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        log::trace!("Preparing CSV output for a model...");
 
         let standard_columns = vec![
             "ID",
@@ -541,63 +1093,52 @@ impl ToCsv for Model {
         let mut columns: HashSet<String> = HashSet::new();
 
         let meta = self.metadata.clone();
-        match meta {
-            Some(meta) => {
-                for property in &meta {
-                    let name = property.name.to_owned();
-                    columns.insert(name);
-                }
+        if let Some(meta) = meta {
+            for property in &meta {
+                let name = property.name.to_owned();
+                columns.insert(name);
             }
-            None => (),
         }
 
         let mut all_columns: Vec<&str> = standard_columns.clone();
         // using a HashSet first to guard against the backend returning duplicate property names
         let all_property_columns: HashSet<&str> = columns.iter().map(|n| n.as_str()).collect();
-        let mut all_property_columns: Vec<&str> =
-            all_property_columns.iter().map(|name| *name).collect();
+        let mut all_property_columns: Vec<&str> = all_property_columns.iter().copied().collect();
         all_property_columns.sort();
         all_columns.append(&mut all_property_columns);
 
         trace!("Columns: {:?}", all_columns);
 
-        if pretty {
-            writer.write_record(&all_columns)?;
-        }
-
-        let mut values: Vec<String> = Vec::new();
-
-        values.push(self.uuid.to_string());
-        values.push(self.name.to_owned());
-        values.push(self.folder_id.to_string());
-        values.push(self.folder_name.to_owned().unwrap_or_default());
-        values.push(self.is_assembly.to_string());
-        values.push(self.file_type.to_string());
-        values.push(self.units.to_owned());
-        values.push(self.state.to_owned());
-        values.push(self.owner_id.to_owned());
+        writer.write_header(&all_columns)?;
+
+        let mut values: Vec<String> = vec![
+            self.uuid.to_string(),
+            self.name.to_owned(),
+            self.folder_id.to_string(),
+            self.folder_name.to_owned().unwrap_or_default(),
+            self.is_assembly.to_string(),
+            self.file_type.to_string(),
+            self.units.to_owned(),
+            self.state.to_owned(),
+            self.owner_id.to_owned(),
+        ];
 
         let mut properties: HashMap<String, String> = HashMap::new();
         let meta = self.metadata.clone();
 
         trace!("Preparing the name/value pairs for metadata properties...");
-        match meta {
-            Some(meta) => {
-                for property in meta {
-                    let name = property.name;
-                    let value = property.value;
+        if let Some(meta) = meta {
+            for property in meta {
+                let name = property.name;
+                let value = property.value;
 
-                    trace!("{}={}", &name, &value);
+                trace!("{}={}", &name, &value);
 
-                    properties.insert(name, value);
-                }
+                properties.insert(name, value);
             }
-            None => (),
         }
 
-        let number_of_columns = all_columns.len();
-        for i in 9..number_of_columns {
-            let column_name = all_columns[i];
+        for &column_name in all_columns.iter().skip(9) {
             let value = match properties.get(column_name) {
                 Some(value) => value.to_owned(),
                 None => String::from(""),
@@ -608,10 +1149,8 @@ impl ToCsv for Model {
         }
 
         writer.write_record(&values)?;
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        Ok(String::from_utf8(bytes)?)
+        Ok(())
     }
 }
 
@@ -621,119 +1160,234 @@ pub struct ListOfModels {
     pub models: Vec<Model>,
 }
 
-impl ToCsv for ListOfModels {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let models = self.models.clone();
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+/// The order in which models are listed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModelSortKey {
+    Name,
+    Created,
+    State,
+    Folder,
+    /// The value of a metadata property, compared as a value of the type if known. Models without the property come
+    /// last
+    Property(String, Option<PropertyType>),
+}
 
-        let mut columns: HashSet<String> = HashSet::new();
-        let standard_columns = vec![
-            "ID",
-            "NAME",
-            "FOLDER_ID",
-            "FOLDER_NAME",
-            "IS_ASSEMBLY",
-            "FILE_TYPE",
+impl ListOfModels {
+    /// Sets the owners of the models from the map of user IDs to names
+    pub fn resolve_owners(&mut self, owners: &HashMap<String, String>) {
+        for model in self.models.iter_mut() {
+            model.resolve_owner(owners);
+        }
+    }
+
+    /// Sorts the models by the key. Models with the same value are ordered by name and UUID, so that the order is
+    /// always the same
+    pub fn sort(&mut self, key: ModelSortKey, descending: bool) {
+        self.models.sort_by(|a, b| {
+            if let ModelSortKey::Property(name, _) = &key {
+                let (a, b) = (a.property(name), b.property(name));
+                if a.is_none() != b.is_none() {
+                    return a.is_none().cmp(&b.is_none());
+                }
+            }
+            let ordering = match &key {
+                ModelSortKey::Name => Ordering::Equal,
+                ModelSortKey::Created => a.created().cmp(&b.created()),
+                ModelSortKey::State => a.state.to_lowercase().cmp(&b.state.to_lowercase()),
+                ModelSortKey::Folder => a
+                    .folder_name
+                    .cmp(&b.folder_name)
+                    .then(a.folder_id.cmp(&b.folder_id)),
+                ModelSortKey::Property(name, property_type) => {
+                    match (a.property(name), b.property(name)) {
+                        (Some(a), Some(b)) => schema::compare_values(a, b, *property_type),
+                        _ => Ordering::Equal,
+                    }
+                }
+            };
+            let ordering = ordering
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then(a.uuid.cmp(&b.uuid));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    /// Keeps the models created at or after the first point in time and before the second one. Without a valid
+    /// creation time, a model is left out
+    pub fn retain_created(&mut self, after: Option<DateTime<Utc>>, before: Option<DateTime<Utc>>) {
+        self.models.retain(|model| match model.created() {
+            Some(created) => {
+                after.is_none_or(|after| created >= after)
+                    && before.is_none_or(|before| created < before)
+            }
+            None => false,
+        });
+    }
+
+    /// Keeps the models in one of the states and of one of the file types, ignoring the case and the leading dot of
+    /// the file type. An empty list does not filter. With Some(true), only assemblies are kept, and with Some(false)
+    /// only parts
+    pub fn retain_selected(
+        &mut self,
+        states: &[String],
+        file_types: &[String],
+        is_assembly: Option<bool>,
+    ) {
+        let file_types: Vec<String> = file_types
+            .iter()
+            .map(|file_type| crate::configuration::normalize_extension(file_type))
+            .collect();
+        self.models.retain(|model| {
+            let file_type = crate::configuration::normalize_extension(&model.file_type);
+            (states.is_empty()
+                || states
+                    .iter()
+                    .any(|state| state.eq_ignore_ascii_case(&model.state)))
+                && (file_types.is_empty() || file_types.contains(&file_type))
+                && is_assembly.is_none_or(|is_assembly| model.is_assembly == is_assembly)
+        });
+    }
+}
+
+impl WriteCsv for ListOfModels {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let mut columns: HashSet<&str> = HashSet::new();
+        let standard_columns = [
+            "ID",
+            "NAME",
+            "FOLDER_ID",
+            "FOLDER_NAME",
+            "IS_ASSEMBLY",
+            "FILE_TYPE",
             "UNITS",
             "STATE",
             "OWNER_ID",
         ];
+        // the owner is only known if it was resolved
+        let with_owner = self.models.iter().any(|model| model.owner.is_some());
+        let owner_column = if with_owner { vec!["OWNER"] } else { vec![] };
 
         // populate the column names with the names of all properties found in models
-        for model in &models {
-            let meta = model.metadata.clone();
-
-            match meta {
-                Some(meta) => {
-                    for property in &meta {
-                        let name = property.name.to_owned();
-                        columns.insert(name);
-                    }
+        for model in &self.models {
+            if let Some(meta) = &model.metadata {
+                for property in meta {
+                    columns.insert(property.name.as_str());
                 }
-                None => (),
             }
         }
 
-        let mut all_columns: Vec<&str> = standard_columns.clone();
-        let mut all_property_columns: Vec<&str> = columns.iter().map(|n| n.as_str()).collect();
+        let mut all_property_columns: Vec<&str> = columns.into_iter().collect();
         all_property_columns.sort();
-        all_columns.append(&mut all_property_columns);
 
-        if pretty {
-            writer.write_record(&all_columns)?;
-        }
-
-        for model in models {
-            let mut values: Vec<String> = Vec::new();
-
-            values.push(model.uuid.to_string());
-            values.push(model.name);
-            values.push(model.folder_id.to_string());
-            values.push(model.folder_name.to_owned().unwrap_or_default());
-            values.push(model.is_assembly.to_string());
-            values.push(model.file_type.to_string());
-            values.push(model.units);
-            values.push(model.state);
-            values.push(model.owner_id.to_string());
-
-            let meta = model.metadata.clone();
-            let mut properties: HashMap<String, String> = HashMap::new();
-            match meta {
-                Some(meta) => {
-                    for property in meta {
-                        let name = property.name;
-                        let value = property.value;
-                        properties.insert(name, value);
-                    }
+        writer.write_header(
+            standard_columns
+                .iter()
+                .chain(owner_column.iter())
+                .chain(all_property_columns.iter()),
+        )?;
+
+        for model in &self.models {
+            let mut values: Vec<String> = vec![
+                model.uuid.to_string(),
+                model.name.to_owned(),
+                model.folder_id.to_string(),
+                model.folder_name.to_owned().unwrap_or_default(),
+                model.is_assembly.to_string(),
+                model.file_type.to_string(),
+                model.units.to_owned(),
+                model.state.to_owned(),
+                model.owner_id.to_string(),
+            ];
+            if with_owner {
+                values.push(model.owner.to_owned().unwrap_or_default());
+            }
+
+            let mut properties: HashMap<&str, &str> = HashMap::new();
+            if let Some(meta) = &model.metadata {
+                for property in meta {
+                    properties.insert(property.name.as_str(), property.value.as_str());
                 }
-                None => (),
             }
 
-            let number_of_columns = all_columns.len();
-            for i in 9..number_of_columns {
-                let column_name = all_columns[i];
-                let value = match properties.get(column_name) {
-                    Some(value) => value.to_owned(),
-                    None => String::from(""),
-                };
-                values.push(value);
+            for column_name in &all_property_columns {
+                let value = properties.get(column_name).copied().unwrap_or_default();
+                values.push(value.to_owned());
             }
 
             writer.write_record(&values)?;
         }
 
-        writer.flush()?;
+        Ok(())
+    }
+}
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+impl WriteJson for ListOfModels {
+    fn write_json<W: io::Write>(&self, writer: W, pretty: bool) -> Result<(), serde_json::Error> {
+        if pretty {
+            serde_json::to_writer_pretty(writer, &self.models)
+        } else {
+            serde_json::to_writer(writer, &self.models)
+        }
     }
 }
 
 impl ToJson for ListOfModels {
     fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
-        let models = self.models.clone();
         if pretty {
-            serde_json::to_string_pretty(&models)
+            serde_json::to_string_pretty(&self.models)
         } else {
-            serde_json::to_string(&models)
+            serde_json::to_string(&self.models)
         }
     }
 }
 
+impl ToYaml for ListOfModels {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.models)
+    }
+}
+
+impl WriteJsonLines for ListOfModels {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.models)
+    }
+}
+
 impl From<Vec<Model>> for ListOfModels {
     fn from(physna_list_of_models_response: Vec<Model>) -> Self {
-        let models = physna_list_of_models_response
-            .into_iter()
-            .map(|m| Model::from(m))
-            .collect();
+        let models = physna_list_of_models_response.into_iter().collect();
         ListOfModels { models }
     }
 }
 
+/// The outcome of a bulk transfer of models (e.g. export or import)
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TransferReport {
+    pub succeeded: Vec<Model>,
+    pub failed: Vec<(Model, String)>,
+}
+
+impl TransferReport {
+    pub fn new() -> Self {
+        TransferReport {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    /// Returns the number of models that were not transferred, because the maximum duration of the run was exceeded
+    pub fn not_started(&self) -> usize {
+        self.failed
+            .iter()
+            .filter(|(_, reason)| reason == crate::deadline::NOT_STARTED)
+            .count()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct ModelAssemblyTree {
     #[serde(rename = "model")]
@@ -758,6 +1412,12 @@ impl ToJson for ModelAssemblyTree {
     }
 }
 
+impl ToYaml for ModelAssemblyTree {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
 impl TreeItem for ModelAssemblyTree {
     type Child = Self;
 
@@ -766,11 +1426,11 @@ impl TreeItem for ModelAssemblyTree {
             f,
             "{}:[{}]",
             style.paint(self.model.name.clone()),
-            style.paint(self.model.uuid.clone())
+            style.paint(self.model.uuid)
         )
     }
 
-    fn children(&self) -> Cow<[Self::Child]> {
+    fn children(&self) -> Cow<'_, [Self::Child]> {
         if self.children.is_none() {
             Cow::from(vec![])
         } else {
@@ -779,6 +1439,94 @@ impl TreeItem for ModelAssemblyTree {
     }
 }
 
+/// One parent-child relationship of an assembly tree. The root assembly has no parent and is at level 0
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssemblyTreeRow {
+    #[serde(rename = "PARENT_UUID")]
+    pub parent_uuid: Option<Uuid>,
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "LEVEL")]
+    pub level: u32,
+}
+
+/// The assembly tree as flat rows, in depth-first order
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfAssemblyTreeRows {
+    pub rows: Vec<AssemblyTreeRow>,
+}
+
+impl ListOfAssemblyTreeRows {
+    pub fn new(rows: Vec<AssemblyTreeRow>) -> Self {
+        Self { rows }
+    }
+
+    fn push_tree(&mut self, tree: &ModelAssemblyTree, parent_uuid: Option<Uuid>, level: u32) {
+        self.rows.push(AssemblyTreeRow {
+            parent_uuid,
+            uuid: tree.model.uuid,
+            name: tree.model.name.to_owned(),
+            level,
+        });
+        for child in tree.children.iter().flatten() {
+            self.push_tree(child, Some(tree.model.uuid), level + 1);
+        }
+    }
+}
+
+impl From<&ModelAssemblyTree> for ListOfAssemblyTreeRows {
+    fn from(tree: &ModelAssemblyTree) -> Self {
+        let mut rows = ListOfAssemblyTreeRows::default();
+        rows.push_tree(tree, None, 0);
+        rows
+    }
+}
+
+impl ToJson for ListOfAssemblyTreeRows {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ToYaml for ListOfAssemblyTreeRows {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteJsonLines for ListOfAssemblyTreeRows {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.rows)
+    }
+}
+
+impl WriteCsv for ListOfAssemblyTreeRows {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["PARENT_UUID", "UUID", "NAME", "LEVEL"];
+        writer.write_header(&columns)?;
+
+        for row in &self.rows {
+            let values = vec![
+                row.parent_uuid
+                    .map(|uuid| uuid.to_string())
+                    .unwrap_or_default(),
+                row.uuid.to_string(),
+                row.name.to_owned(),
+                row.level.to_string(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
 // #[derive(Clone, Debug, Default)]
 // pub struct AssemblyGraph {
 //     pub flat_bom: IndexMap<Uuid, NodeIndex>,
@@ -890,32 +1638,302 @@ impl ToJson for FlatBom {
     }
 }
 
-impl ToCsv for FlatBom {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
+impl ToYaml for FlatBom {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteCsv for FlatBom {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
         let models = self.inner.clone();
 
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+        let columns = vec!["UUID", "NAME"];
+        writer.write_header(&columns)?;
+
+        for (uuid, model) in models {
+            let values: Vec<String> = vec![uuid, model.name.to_owned()];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// One line of a bill of materials: a component of the assembly and the number of times it occurs in the whole tree
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BomItem {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "IS_ASSEMBLY")]
+    pub is_assembly: bool,
+    #[serde(rename = "QUANTITY")]
+    pub quantity: u32,
+    /// The values of the selected metadata properties
+    #[serde(
+        rename = "PROPERTIES",
+        skip_serializing_if = "BTreeMap::is_empty",
+        default
+    )]
+    pub properties: BTreeMap<String, String>,
+}
+
+/// The components of an assembly with their quantities, in the order of their first occurrence in the tree
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct BillOfMaterials {
+    /// The names of the metadata properties that are listed for each component
+    #[serde(skip)]
+    pub properties: Vec<String>,
+    pub items: Vec<BomItem>,
+}
+
+impl BillOfMaterials {
+    fn add_children(&mut self, tree: &ModelAssemblyTree, index: &mut HashMap<Uuid, usize>) {
+        for child in tree.children.iter().flatten() {
+            match index.get(&child.model.uuid) {
+                Some(i) => self.items[*i].quantity += 1,
+                None => {
+                    index.insert(child.model.uuid, self.items.len());
+                    self.items.push(BomItem {
+                        uuid: child.model.uuid,
+                        name: child.model.name.to_owned(),
+                        is_assembly: child.model.is_assembly
+                            || child.children.as_ref().is_some_and(|c| !c.is_empty()),
+                        quantity: 1,
+                        properties: BTreeMap::new(),
+                    });
+                }
+            }
+            self.add_children(child, index);
+        }
+    }
+}
+
+/// Counts every sub-assembly and part below the root. A component used in several sub-assemblies is counted once for
+/// each occurrence
+impl From<&ModelAssemblyTree> for BillOfMaterials {
+    fn from(tree: &ModelAssemblyTree) -> Self {
+        let mut bom = BillOfMaterials::default();
+        bom.add_children(tree, &mut HashMap::new());
+        bom
+    }
+}
 
+impl ToJson for BillOfMaterials {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
-            let columns = vec!["UUID", "NAME"];
-            writer.write_record(&columns)?;
+            serde_json::to_string_pretty(&self.items)
+        } else {
+            serde_json::to_string(&self.items)
         }
+    }
+}
 
-        for (uuid, model) in models {
-            let mut values: Vec<String> = Vec::new();
-            values.push(uuid);
-            values.push(model.name.to_owned());
+impl ToYaml for BillOfMaterials {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.items)
+    }
+}
+
+impl WriteJsonLines for BillOfMaterials {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.items)
+    }
+}
+
+impl WriteCsv for BillOfMaterials {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = ["UUID", "NAME", "IS_ASSEMBLY", "QUANTITY"];
+        writer.write_header(
+            columns
+                .iter()
+                .map(|column| column.to_string())
+                .chain(self.properties.iter().cloned()),
+        )?;
+
+        for item in &self.items {
+            let mut values = vec![
+                item.uuid.to_string(),
+                item.name.to_owned(),
+                item.is_assembly.to_string(),
+                item.quantity.to_string(),
+            ];
+            for property in &self.properties {
+                values.push(item.properties.get(property).cloned().unwrap_or_default());
+            }
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The totals of the numeric metadata properties of all parts in one unit of an assembly
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RollupItem {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    /// The number of occurrences in the top-level assembly, which itself has a quantity of 1
+    #[serde(rename = "QUANTITY")]
+    pub quantity: u32,
+    #[serde(rename = "TOTALS")]
+    pub totals: BTreeMap<String, f64>,
+    /// The number of parts in one unit that have no numeric value for at least one of the properties
+    #[serde(rename = "MISSING")]
+    pub missing: u32,
+}
+
+/// The subtotals of the top-level assembly, which are the grand totals, followed by those of its sub-assemblies
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct Rollup {
+    #[serde(skip)]
+    pub properties: Vec<String>,
+    pub items: Vec<RollupItem>,
+}
+
+impl ToJson for Rollup {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.items)
+        } else {
+            serde_json::to_string(&self.items)
+        }
+    }
+}
+
+impl ToYaml for Rollup {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.items)
+    }
+}
+
+impl WriteJsonLines for Rollup {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.items)
+    }
+}
+
+impl WriteCsv for Rollup {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let mut columns: Vec<String> = vec!["UUID", "NAME", "QUANTITY"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        columns.extend(self.properties.iter().cloned());
+        columns.push("MISSING".to_string());
+        writer.write_header(&columns)?;
+
+        for item in &self.items {
+            let mut values = vec![
+                item.uuid.to_string(),
+                item.name.to_owned(),
+                item.quantity.to_string(),
+            ];
+            for property in &self.properties {
+                values.push(
+                    item.totals
+                        .get(property)
+                        .map(|total| total.to_string())
+                        .unwrap_or_default(),
+                );
+            }
+            values.push(item.missing.to_string());
             writer.write_record(&values)?;
         }
 
-        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// How often a model is used in assemblies and how many near-duplicates it has
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReuseItem {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "FOLDER")]
+    pub folder: String,
+    #[serde(rename = "IS_ASSEMBLY")]
+    pub is_assembly: bool,
+    /// The number of assemblies that have the model as a direct component
+    #[serde(rename = "ASSEMBLIES")]
+    pub assemblies: u32,
+    /// The number of other models that match it above the threshold
+    #[serde(rename = "DUPLICATES")]
+    pub duplicates: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfReuseItems {
+    pub items: Vec<ReuseItem>,
+}
+
+impl ListOfReuseItems {
+    /// Ranks the models as candidates for standardization: the models with the most duplicates come first, then
+    /// the ones used in the most assemblies
+    pub fn new(mut items: Vec<ReuseItem>) -> Self {
+        items.sort_by(|a, b| {
+            b.duplicates
+                .cmp(&a.duplicates)
+                .then(b.assemblies.cmp(&a.assemblies))
+                .then(a.name.cmp(&b.name))
+        });
+        Self { items }
+    }
+}
+
+impl ToJson for ListOfReuseItems {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.items)
+        } else {
+            serde_json::to_string(&self.items)
+        }
+    }
+}
+
+impl ToYaml for ListOfReuseItems {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.items)
+    }
+}
+
+impl WriteJsonLines for ListOfReuseItems {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.items)
+    }
+}
+
+impl WriteCsv for ListOfReuseItems {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec![
+            "UUID",
+            "NAME",
+            "FOLDER",
+            "IS_ASSEMBLY",
+            "ASSEMBLIES",
+            "DUPLICATES",
+        ];
+        writer.write_header(&columns)?;
+
+        for item in &self.items {
+            let values = vec![
+                item.uuid.to_string(),
+                item.name.to_owned(),
+                item.folder.to_owned(),
+                item.is_assembly.to_string(),
+                item.assemblies.to_string(),
+                item.duplicates.to_string(),
+            ];
+            writer.write_record(&values)?;
+        }
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -955,6 +1973,23 @@ impl ListOfModelMatches {
     pub fn new(matches: Box<Vec<ModelMatch>>) -> ListOfModelMatches {
         ListOfModelMatches { inner: matches }
     }
+
+    /// Sets the owners of the matching models from the map of user IDs to names
+    pub fn resolve_owners(&mut self, owners: &HashMap<String, String>) {
+        for m in self.inner.iter_mut() {
+            m.model.resolve_owner(owners);
+        }
+    }
+}
+
+impl WriteJson for ListOfModelMatches {
+    fn write_json<W: io::Write>(&self, writer: W, pretty: bool) -> Result<(), serde_json::Error> {
+        if pretty {
+            serde_json::to_writer_pretty(writer, &self.inner)
+        } else {
+            serde_json::to_writer(writer, &self.inner)
+        }
+    }
 }
 
 impl ToJson for ListOfModelMatches {
@@ -967,16 +2002,22 @@ impl ToJson for ListOfModelMatches {
     }
 }
 
-impl ToCsv for ListOfModelMatches {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let matches = *self.inner.clone();
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for ListOfModelMatches {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.inner)
+    }
+}
 
-        let mut columns: HashSet<String> = HashSet::new();
-        let standard_columns = vec![
+impl WriteJsonLines for ListOfModelMatches {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, self.inner.iter())
+    }
+}
+
+impl WriteCsv for ListOfModelMatches {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let mut columns: HashSet<&str> = HashSet::new();
+        let standard_columns = [
             "MATCH_PERCENTAGE",
             "ID",
             "NAME",
@@ -986,75 +2027,61 @@ impl ToCsv for ListOfModelMatches {
             "UNITS",
             "STATE",
         ];
+        // the owner is only known if it was resolved
+        let with_owner = self.inner.iter().any(|m| m.model.owner.is_some());
+        let owner_column = if with_owner { vec!["OWNER"] } else { vec![] };
 
         // populate the column names with the names of all properties found in the result
-        for model_match in &matches {
-            let model = model_match.model.clone();
-            let meta = model.metadata;
-
-            match meta {
-                Some(meta) => {
-                    for property in &meta {
-                        let name = property.name.to_owned();
-                        columns.insert(name);
-                    }
+        for model_match in self.inner.iter() {
+            if let Some(meta) = &model_match.model.metadata {
+                for property in meta {
+                    columns.insert(property.name.as_str());
                 }
-                None => (),
             }
         }
 
-        let mut all_columns: Vec<&str> = standard_columns.clone();
-        let mut all_property_columns: Vec<&str> = columns.iter().map(|n| n.as_str()).collect();
+        let mut all_property_columns: Vec<&str> = columns.into_iter().collect();
         all_property_columns.sort();
-        all_columns.append(&mut all_property_columns);
 
-        if pretty {
-            writer.write_record(&all_columns)?;
-        }
+        writer.write_header(
+            standard_columns
+                .iter()
+                .chain(owner_column.iter())
+                .chain(all_property_columns.iter()),
+        )?;
+
+        for m in self.inner.iter() {
+            let model = &m.model;
+            let mut values: Vec<String> = vec![
+                format!("{:.4}", m.percentage),
+                model.uuid.to_string(),
+                model.name.to_owned(),
+                model.folder_id.to_string(),
+                model.is_assembly.to_string(),
+                model.file_type.to_string(),
+                model.units.to_owned(),
+                model.state.to_owned(),
+            ];
+            if with_owner {
+                values.push(model.owner.to_owned().unwrap_or_default());
+            }
 
-        for m in matches {
-            let model = m.model;
-            let percentage = m.percentage;
-            let mut values: Vec<String> = Vec::new();
-
-            values.push(format!("{:.4}", percentage));
-            values.push(model.uuid.to_string());
-            values.push(model.name);
-            values.push(model.folder_id.to_string());
-            values.push(model.is_assembly.to_string());
-            values.push(model.file_type.to_string());
-            values.push(model.units);
-            values.push(model.state);
-
-            let meta = model.metadata.clone();
-            let mut properties: HashMap<String, String> = HashMap::new();
-            match meta {
-                Some(meta) => {
-                    for property in meta {
-                        let name = property.name;
-                        let value = property.value;
-                        properties.insert(name, value);
-                    }
+            let mut properties: HashMap<&str, &str> = HashMap::new();
+            if let Some(meta) = &model.metadata {
+                for property in meta {
+                    properties.insert(property.name.as_str(), property.value.as_str());
                 }
-                None => (),
             }
 
-            for column_name in &columns {
-                let value = match properties.get(column_name) {
-                    Some(value) => value.to_owned(),
-                    None => String::from(""),
-                };
-                values.push(value);
+            for column_name in &all_property_columns {
+                let value = properties.get(column_name).copied().unwrap_or_default();
+                values.push(value.to_owned());
             }
 
             writer.write_record(&values)?;
         }
 
-        writer.flush()?;
-
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -1099,14 +2126,21 @@ impl ToJson for ListOfVisualModelMatches {
     }
 }
 
-impl ToCsv for ListOfVisualModelMatches {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let matches = *self.models.clone();
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for ListOfVisualModelMatches {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.models)
+    }
+}
 
+impl WriteJsonLines for ListOfVisualModelMatches {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, self.models.iter())
+    }
+}
+
+impl WriteCsv for ListOfVisualModelMatches {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let matches = *self.models.clone();
         let standard_columns = vec![
             "ID",
             "NAME",
@@ -1117,30 +2151,24 @@ impl ToCsv for ListOfVisualModelMatches {
             "STATE",
         ];
 
-        if pretty {
-            writer.write_record(&standard_columns)?;
-        }
+        writer.write_header(&standard_columns)?;
 
         for m in matches {
             let model = m.clone();
-            let mut values: Vec<String> = Vec::new();
-
-            values.push(model.uuid.to_string());
-            values.push(model.name);
-            values.push(model.folder_id.to_string());
-            values.push(model.is_assembly.to_string());
-            values.push(model.file_type.to_string());
-            values.push(model.units);
-            values.push(model.state);
+            let values: Vec<String> = vec![
+                model.uuid.to_string(),
+                model.name,
+                model.folder_id.to_string(),
+                model.is_assembly.to_string(),
+                model.file_type.to_string(),
+                model.units,
+                model.state,
+            ];
 
             writer.write_record(&values)?;
         }
 
-        writer.flush()?;
-
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -1168,6 +2196,11 @@ pub struct ModelMatchReportItem {
     pub name: String,
     #[serde(rename = "fodler_name")]
     pub folder_name: String,
+    #[serde(skip)]
+    pub owner_id: String,
+    /// The name or email of the owner, if resolved from the list of users
+    #[serde(rename = "owner", default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
     #[serde(rename = "matches")]
     pub matches: Vec<ModelMatch>,
 }
@@ -1176,12 +2209,87 @@ pub struct ModelMatchReportItem {
 pub struct SimpleDuplicatesMatchReport {
     #[serde(rename = "matches")]
     pub inner: HashMap<String, ModelMatchReportItem>,
+    /// The number of models that were not matched, because the maximum duration of the run was exceeded
+    #[serde(skip)]
+    pub not_started: usize,
 }
 
 impl SimpleDuplicatesMatchReport {
     pub fn new() -> Self {
         SimpleDuplicatesMatchReport {
             inner: HashMap::new(),
+            not_started: 0,
+        }
+    }
+
+    /// Sets the owners of the models and of their matches from the map of user IDs to names
+    pub fn resolve_owners(&mut self, owners: &HashMap<String, String>) {
+        for item in self.inner.values_mut() {
+            item.owner = Some(
+                owners
+                    .get(&item.owner_id)
+                    .cloned()
+                    .unwrap_or(item.owner_id.to_owned()),
+            );
+            for m in item.matches.iter_mut() {
+                m.model.resolve_owner(owners);
+            }
+        }
+    }
+
+    /// Reports each pair of matching models once. When both models matched each other, the match is kept under the
+    /// model with the lower UUID, with the higher of the two scores
+    pub fn unique_pairs(&mut self) {
+        let mut scores: HashMap<(String, String), f64> = HashMap::new();
+        for item in self.inner.values() {
+            for m in &item.matches {
+                let score = scores
+                    .entry(Self::pair(&item.uuid, &m.model.uuid.to_string()))
+                    .or_insert(m.percentage);
+                *score = score.max(m.percentage);
+            }
+        }
+
+        let directed: HashSet<(String, String)> = self
+            .inner
+            .values()
+            .flat_map(|item| {
+                item.matches
+                    .iter()
+                    .map(|m| (item.uuid.to_owned(), m.model.uuid.to_string()))
+            })
+            .collect();
+
+        for item in self.inner.values_mut() {
+            let source = item.uuid.to_owned();
+            item.matches.retain(|m| {
+                let target = m.model.uuid.to_string();
+                source < target || !directed.contains(&(target, source.to_owned()))
+            });
+            for m in item.matches.iter_mut() {
+                if let Some(score) = scores.get(&Self::pair(&source, &m.model.uuid.to_string())) {
+                    m.percentage = *score;
+                }
+            }
+        }
+        self.inner.retain(|_, item| !item.matches.is_empty());
+    }
+
+    fn pair(first: &str, second: &str) -> (String, String) {
+        if first <= second {
+            (first.to_owned(), second.to_owned())
+        } else {
+            (second.to_owned(), first.to_owned())
+        }
+    }
+}
+
+impl WriteJson for SimpleDuplicatesMatchReport {
+    fn write_json<W: io::Write>(&self, writer: W, pretty: bool) -> Result<(), serde_json::Error> {
+        if pretty {
+            serde_json::to_writer_pretty(writer, &self.inner)
+        } else {
+            serde_json::to_writer(writer, &self.inner)
         }
     }
 }
@@ -1196,15 +2304,22 @@ impl ToJson for SimpleDuplicatesMatchReport {
     }
 }
 
-impl ToCsv for SimpleDuplicatesMatchReport {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for SimpleDuplicatesMatchReport {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.inner)
+    }
+}
+
+impl WriteJsonLines for SimpleDuplicatesMatchReport {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, self.inner.values())
+    }
+}
 
-        let mut columns: HashSet<String> = HashSet::new();
-        let standard_columns = vec![
+impl WriteCsv for SimpleDuplicatesMatchReport {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let mut columns: HashSet<&str> = HashSet::new();
+        let standard_columns = [
             "MODEL_NAME",
             "MATCHING_MODEL_NAME",
             "MATCH",
@@ -1214,83 +2329,70 @@ impl ToCsv for SimpleDuplicatesMatchReport {
             "MATCHING_FOLDER_NAME",
             "COMPARISON_URL",
         ];
+        // the owners are only known if they were resolved
+        let with_owner = self.inner.values().any(|item| item.owner.is_some());
+        let owner_columns = if with_owner {
+            vec!["SOURCE_OWNER", "MATCHING_OWNER"]
+        } else {
+            vec![]
+        };
 
         // populate the column names with the names of all properties found in the result
-        for (_uuid, item) in &self.inner {
+        for item in self.inner.values() {
             for model_match in &item.matches {
-                let model = model_match.model.clone();
-                let meta = model.metadata;
-
-                match meta {
-                    Some(meta) => {
-                        for property in &meta {
-                            let name = property.name.to_owned();
-                            columns.insert(name);
-                        }
+                if let Some(meta) = &model_match.model.metadata {
+                    for property in meta {
+                        columns.insert(property.name.as_str());
                     }
-                    None => (),
                 }
             }
         }
 
-        let mut all_columns: Vec<&str> = standard_columns.clone();
-        let mut all_property_columns: Vec<&str> = columns.iter().map(|n| n.as_str()).collect();
-        all_columns.append(&mut all_property_columns);
+        let all_property_columns: Vec<&str> = columns.into_iter().collect();
 
-        if pretty {
-            writer.write_record(&all_columns)?;
-        }
+        writer.write_header(
+            standard_columns
+                .iter()
+                .chain(owner_columns.iter())
+                .chain(all_property_columns.iter()),
+        )?;
 
-        for (_uuid, item) in &self.inner {
-            let model_name = item.name.to_owned();
+        for item in self.inner.values() {
             let source_uuid = item.uuid.to_string();
-            let source_folder_name = item.folder_name.to_owned();
 
             for m in &item.matches {
-                let mut values: Vec<String> = Vec::new();
-
-                values.push(model_name.to_owned());
-                values.push(m.model.name.to_owned());
-                values.push(m.percentage.to_string());
-                values.push(source_uuid.to_owned());
-                values.push(m.model.uuid.to_string());
-                values.push(source_folder_name.to_owned());
-                values.push(m.model.folder_name.to_owned().unwrap_or_default());
-
-                match &m.comparison_url {
-                    Some(url) => values.push(url.to_owned()),
-                    None => values.push("".to_string()),
+                let mut values: Vec<String> = vec![
+                    item.name.to_owned(),
+                    m.model.name.to_owned(),
+                    m.percentage.to_string(),
+                    source_uuid.to_owned(),
+                    m.model.uuid.to_string(),
+                    item.folder_name.to_owned(),
+                    m.model.folder_name.to_owned().unwrap_or_default(),
+                    m.comparison_url.to_owned().unwrap_or_default(),
+                ];
+                if with_owner {
+                    values.push(item.owner.to_owned().unwrap_or_default());
+                    values.push(m.model.owner.to_owned().unwrap_or_default());
                 }
 
-                let meta = m.model.metadata.clone();
-                let mut properties: HashMap<String, String> = HashMap::new();
-                match meta {
-                    Some(meta) => {
-                        for property in meta {
-                            let name = property.name;
-                            let value = property.value;
-                            properties.insert(name, value);
-                        }
+                let mut properties: HashMap<&str, &str> = HashMap::new();
+                if let Some(meta) = &m.model.metadata {
+                    for property in meta {
+                        properties.insert(property.name.as_str(), property.value.as_str());
                     }
-                    None => (),
                 }
 
-                for column_name in &columns {
-                    let value = match properties.get(column_name) {
-                        Some(value) => value.to_owned(),
-                        None => String::from(""),
-                    };
-                    values.push(value);
+                for column_name in &all_property_columns {
+                    let value = properties.get(column_name).copied().unwrap_or_default();
+                    values.push(value.to_owned());
                 }
 
                 writer.write_record(&values)?;
             }
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -1300,15 +2402,37 @@ impl ToHtml for SimpleDuplicatesMatchReport {
     }
 }
 
+/// What the models in the status report are counted by, besides their state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusGrouping {
+    Folder,
+    FileType,
+    Owner,
+}
+
+/// The number of models in one state. Depending on the grouping, the folder, the file type or the owner are empty
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct ModelStatusRecord {
+    /// Only set in reports that combine several tenants
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tenant: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
     pub folder_id: u32,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub folder_name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub file_type: String,
+    /// The name of the user who uploaded the models, or their ID if the name is not known
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub owner: String,
     pub state: String,
     pub count: u64,
 }
 
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
 impl ModelStatusRecord {
     pub fn new(
         folder_id: u32,
@@ -1318,9 +2442,11 @@ impl ModelStatusRecord {
         count: u64,
     ) -> Self {
         ModelStatusRecord {
+            tenant: String::default(),
             folder_id,
             folder_name,
             file_type,
+            owner: String::default(),
             state,
             count,
         }
@@ -1331,17 +2457,52 @@ impl Hash for ModelStatusRecord {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.folder_id.hash(state);
         self.file_type.hash(state);
+        self.owner.hash(state);
         self.state.hash(state);
     }
 }
 
 pub struct EnvironmentStatusReport {
     pub stats: Vec<ModelStatusRecord>,
+    pub repaired: Vec<Uuid>,
+    /// How the models are counted. If none, they are counted by folder and file type
+    pub group_by: Option<StatusGrouping>,
+    /// Whether the report combines several tenants, which are then listed in the first column
+    pub by_tenant: bool,
+}
+
+impl Default for EnvironmentStatusReport {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl EnvironmentStatusReport {
     pub fn new() -> Self {
-        EnvironmentStatusReport { stats: Vec::new() }
+        EnvironmentStatusReport {
+            stats: Vec::new(),
+            repaired: Vec::new(),
+            group_by: None,
+            by_tenant: false,
+        }
+    }
+
+    /// Adds the counts of the tenant to a report that combines several tenants
+    pub fn extend(&mut self, tenant: &str, report: EnvironmentStatusReport) {
+        self.by_tenant = true;
+        self.group_by = report.group_by;
+        self.stats
+            .extend(report.stats.into_iter().map(|stat| ModelStatusRecord {
+                tenant: tenant.to_owned(),
+                ..stat
+            }));
+    }
+
+    /// Returns true if all models in the report are in state FINISHED
+    pub fn is_finished(&self) -> bool {
+        self.stats
+            .iter()
+            .all(|stat| stat.state.eq_ignore_ascii_case("FINISHED"))
     }
 }
 
@@ -1355,329 +2516,1567 @@ impl ToJson for EnvironmentStatusReport {
     }
 }
 
-impl ToCsv for EnvironmentStatusReport {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for EnvironmentStatusReport {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.stats)
+    }
+}
 
-        if pretty {
-            let columns = vec!["FOLDER_ID", "FOLDER_NAME", "FILE_TYPE", "STATE", "COUNT"];
-            writer.write_record(&columns)?;
+impl WriteJsonLines for EnvironmentStatusReport {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.stats)
+    }
+}
+
+impl WriteCsv for EnvironmentStatusReport {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let mut columns = match self.group_by {
+            None => vec!["FOLDER_ID", "FOLDER_NAME", "FILE_TYPE", "STATE", "COUNT"],
+            Some(StatusGrouping::Folder) => vec!["FOLDER_ID", "FOLDER_NAME", "STATE", "COUNT"],
+            Some(StatusGrouping::FileType) => vec!["FILE_TYPE", "STATE", "COUNT"],
+            Some(StatusGrouping::Owner) => vec!["OWNER", "STATE", "COUNT"],
+        };
+        if self.by_tenant {
+            columns.insert(0, "TENANT");
         }
+        writer.write_header(&columns)?;
 
         for stat in &self.stats {
-            let folder_id = stat.folder_id.to_string().to_owned();
-            let folder_name = stat.folder_name.to_owned();
-            let file_type = stat.file_type.to_owned();
-            let state = stat.state.to_owned();
-            let count = stat.count.to_string().to_owned();
-
-            let mut values: Vec<String> = Vec::new();
-            values.push(folder_id);
-            values.push(folder_name);
-            values.push(file_type);
-            values.push(state);
-            values.push(count);
+            let folder = [stat.folder_id.to_string(), stat.folder_name.to_owned()];
+            let mut values: Vec<String> = match self.group_by {
+                None => [folder.to_vec(), vec![stat.file_type.to_owned()]].concat(),
+                Some(StatusGrouping::Folder) => folder.to_vec(),
+                Some(StatusGrouping::FileType) => vec![stat.file_type.to_owned()],
+                Some(StatusGrouping::Owner) => vec![stat.owner.to_owned()],
+            };
+            values.push(stat.state.to_owned());
+            values.push(stat.count.to_string());
+            if self.by_tenant {
+                values.insert(0, stat.tenant.to_owned());
+            }
 
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
-impl From<client::Folder> for Folder {
-    fn from(folder: client::Folder) -> Self {
-        Folder::new(folder.id, folder.name)
-    }
+/// The status report of a tenant at one point in time, saved to compare later reports against
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub tenant: String,
+    pub stats: Vec<ModelStatusRecord>,
 }
 
-impl From<client::FolderListResponse> for ListOfFolders {
-    fn from(response: client::FolderListResponse) -> Self {
-        let folders = response
-            .folders
-            .into_iter()
-            .map(|f| Folder::from(f))
-            .collect();
-        ListOfFolders { folders }
+impl StatusSnapshot {
+    pub fn new(tenant: &str, report: &EnvironmentStatusReport) -> Self {
+        StatusSnapshot {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            tenant: tenant.to_owned(),
+            stats: report.stats.to_owned(),
+        }
     }
-}
 
-impl From<client::SingleModelResponse> for Model {
-    fn from(response: client::SingleModelResponse) -> Self {
-        Model {
-            uuid: response.model.uuid,
-            is_assembly: response.model.is_assembly,
-            name: response.model.name,
-            folder_id: response.model.folder_id,
-            folder_name: None,
-            file_type: response.model.file_type,
-            thumbnail: response.model.thumbnail,
-            owner_id: response.model.owner_id,
-            created_at: response.model.created_at,
-            units: response.model.units,
-            state: response.model.state,
-            short_id: response.model.short_id,
-            attachment_url: response.model.attachment_url,
-            metadata: None,
-        }
+    pub fn read(path: &Path) -> Result<Self, ParsingError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
     }
-}
 
-impl From<client::PartToPartMatch> for ModelMatch {
-    fn from(m: client::PartToPartMatch) -> Self {
-        let model = Model::from((m.matched_model).clone());
-        let percentage = m.match_percentage;
-        ModelMatch::new(model, percentage, None)
+    pub fn write(&self, path: &Path) -> Result<(), ParsingError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Compares the number of models per folder and state with an earlier snapshot, regardless of the file type.
+    /// Only the counts that changed are listed
+    pub fn compare(&self, previous: &StatusSnapshot) -> ListOfStatusChanges {
+        let mut counts: BTreeMap<(String, u32, String), (u64, u64)> = BTreeMap::new();
+        for (stats, current) in [(&previous.stats, false), (&self.stats, true)] {
+            for stat in stats {
+                let key = (
+                    stat.folder_name.to_owned(),
+                    stat.folder_id,
+                    stat.state.to_uppercase(),
+                );
+                let count = counts.entry(key).or_default();
+                if current {
+                    count.1 += stat.count;
+                } else {
+                    count.0 += stat.count;
+                }
+            }
+        }
+
+        ListOfStatusChanges {
+            since: previous.timestamp,
+            changes: counts
+                .into_iter()
+                .filter(|(_, (previous, current))| previous != current)
+                .map(
+                    |((folder_name, folder_id, state), (previous, current))| StatusChange {
+                        folder_id,
+                        folder_name,
+                        state,
+                        previous,
+                        current,
+                        change: current as i64 - previous as i64,
+                    },
+                )
+                .collect(),
+        }
     }
 }
 
-pub struct ModelMatchReport {
-    pub duplicates: SimpleDuplicatesMatchReport,
-    pub dictionary: HashMap<Uuid, PartNodeDictionaryItem>,
-    pub graph: MatrixGraph<String, f64>,
-    //pub matrix: Compressed<f64>,
+/// The change of the number of models in one state in a folder since a snapshot
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusChange {
+    #[serde(rename = "FOLDER_ID")]
+    pub folder_id: u32,
+    #[serde(rename = "FOLDER_NAME")]
+    pub folder_name: String,
+    #[serde(rename = "STATE")]
+    pub state: String,
+    #[serde(rename = "PREVIOUS")]
+    pub previous: u64,
+    #[serde(rename = "CURRENT")]
+    pub current: u64,
+    #[serde(rename = "CHANGE")]
+    pub change: i64,
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct PartNodeDictionaryItem {
-    pub name: String,
-    pub node: usize,
-    pub uuid: Uuid,
+pub struct ListOfStatusChanges {
+    /// The time of the snapshot compared against, in seconds since the Unix epoch
+    #[serde(skip)]
+    pub since: u64,
+    pub changes: Vec<StatusChange>,
 }
 
-#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct FolderCreateResponse {
-    #[serde(rename = "folder")]
-    pub folder: Folder,
+impl ToJson for ListOfStatusChanges {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.changes)
+        } else {
+            serde_json::to_string(&self.changes)
+        }
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ModelCreateMetadataResponse {
-    #[serde(rename = "metadata")]
-    pub metadata: ModelMetadataItem,
+impl ToYaml for ListOfStatusChanges {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.changes)
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct GeoLabel {
-    #[serde(rename = "id")]
-    pub id: u32,
-    #[serde(rename = "name")]
+impl WriteJsonLines for ListOfStatusChanges {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.changes)
+    }
+}
+
+impl WriteCsv for ListOfStatusChanges {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec![
+            "FOLDER_ID",
+            "FOLDER_NAME",
+            "STATE",
+            "PREVIOUS",
+            "CURRENT",
+            "CHANGE",
+        ];
+        writer.write_header(&columns)?;
+
+        for change in &self.changes {
+            let values = vec![
+                change.folder_id.to_string(),
+                change.folder_name.to_owned(),
+                change.state.to_owned(),
+                change.previous.to_string(),
+                change.current.to_string(),
+                format!("{:+}", change.change),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A row of a metadata file that does not comply with the metadata schema
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetadataViolation {
+    /// The line in the file, counting the header
+    #[serde(rename = "ROW")]
+    pub row: u64,
+    #[serde(rename = "UUID", skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    #[serde(rename = "NAME")]
     pub name: String,
-    #[serde(rename = "geoClassifierId")]
-    pub geo_classifier_id: u32,
+    #[serde(rename = "VALUE")]
+    pub value: String,
+    #[serde(rename = "REASON")]
+    pub reason: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ListOfGeoLabels {
-    #[serde(rename = "geoLabels")]
-    pub labels: Vec<GeoLabel>,
+pub struct ListOfMetadataViolations {
+    pub violations: Vec<MetadataViolation>,
 }
 
-impl ListOfGeoLabels {
-    pub fn new(labels: Vec<GeoLabel>) -> Self {
-        ListOfGeoLabels { labels }
+impl ListOfMetadataViolations {
+    pub fn new(violations: Vec<MetadataViolation>) -> Self {
+        Self { violations }
     }
-}
 
-impl From<Vec<GeoLabel>> for ListOfGeoLabels {
-    fn from(labels: Vec<GeoLabel>) -> Self {
-        ListOfGeoLabels::new(labels)
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
     }
 }
 
-impl ToJson for ListOfGeoLabels {
+impl ToJson for ListOfMetadataViolations {
     fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
-            serde_json::to_string_pretty(self)
+            serde_json::to_string_pretty(&self.violations)
         } else {
-            serde_json::to_string(self)
+            serde_json::to_string(&self.violations)
         }
     }
 }
 
-impl ToCsv for ListOfGeoLabels {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
-
-        if pretty {
-            let columns = vec!["ID", "NAME", "CLASSIFIER_ID"];
-            writer.write_record(&columns)?;
-        }
-
-        for label in &self.labels {
-            let id = label.id.to_string();
-            let name = label.name.to_owned();
-            let classifier_id = label.geo_classifier_id.to_string();
+impl ToYaml for ListOfMetadataViolations {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.violations)
+    }
+}
 
-            let mut values: Vec<String> = Vec::new();
-            values.push(id);
-            values.push(name);
-            values.push(classifier_id);
+impl WriteJsonLines for ListOfMetadataViolations {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.violations)
+    }
+}
 
+impl WriteCsv for ListOfMetadataViolations {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["ROW", "UUID", "NAME", "VALUE", "REASON"];
+        writer.write_header(&columns)?;
+
+        for violation in &self.violations {
+            let values = vec![
+                violation.row.to_string(),
+                violation.uuid.map(|u| u.to_string()).unwrap_or_default(),
+                violation.name.to_owned(),
+                violation.value.to_owned(),
+                violation.reason.to_owned(),
+            ];
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-pub struct GeoMatch {
-    #[serde(rename = "matchedModel")]
-    pub model: Model,
-    #[serde(rename = "confidence")]
-    pub confidence: f64,
-    #[serde(rename = "geoLabelId")]
-    pub label_id: u32,
+/// A model found by its name, with how closely the name matches the query
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FoundModel {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "FOLDER")]
+    pub folder: Option<String>,
+    #[serde(rename = "STATE")]
+    pub state: String,
+    /// From 0 (unrelated) to 1 (every word of the query is in the name)
+    #[serde(rename = "SCORE")]
+    pub score: f64,
 }
 
-impl PartialEq for GeoMatch {
-    fn eq(&self, other: &Self) -> bool {
-        self.model.name.eq(&other.model.name)
-    }
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfFoundModels {
+    pub models: Vec<FoundModel>,
 }
-impl Eq for GeoMatch {}
 
-impl GeoMatch {
-    pub fn new(model: Model, confidence: f64, label_id: u32) -> GeoMatch {
-        GeoMatch {
-            model,
-            confidence,
-            label_id,
-        }
+impl ListOfFoundModels {
+    pub fn new(models: Vec<FoundModel>) -> Self {
+        Self { models }
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-pub struct ImageMatch {
-    #[serde(rename = "matchedModel")]
-    pub model: Model,
+impl ToJson for ListOfFoundModels {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.models)
+        } else {
+            serde_json::to_string(&self.models)
+        }
+    }
 }
 
-impl PartialEq for ImageMatch {
-    fn eq(&self, other: &Self) -> bool {
-        self.model.name.eq(&other.model.name)
+impl ToYaml for ListOfFoundModels {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.models)
     }
 }
-impl Eq for ImageMatch {}
 
-impl ImageMatch {
-    pub fn new(model: Model) -> Self {
-        Self { model }
+impl WriteJsonLines for ListOfFoundModels {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.models)
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ListOfGeoClassifierPredictions {
-    #[serde(rename = "matches")]
-    pub matches: Vec<GeoMatch>,
-}
+impl WriteCsv for ListOfFoundModels {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["UUID", "NAME", "FOLDER", "STATE", "SCORE"];
+        writer.write_header(&columns)?;
+
+        for model in &self.models {
+            let values = vec![
+                model.uuid.to_string(),
+                model.name.to_owned(),
+                model.folder.to_owned().unwrap_or_default(),
+                model.state.to_owned(),
+                format!("{:.2}", model.score),
+            ];
+            writer.write_record(&values)?;
+        }
 
-impl ListOfGeoClassifierPredictions {
-    pub fn new(matches: Vec<GeoMatch>) -> Self {
-        ListOfGeoClassifierPredictions { matches }
+        Ok(())
     }
 }
 
-impl From<Vec<GeoMatch>> for ListOfGeoClassifierPredictions {
-    fn from(matches: Vec<GeoMatch>) -> Self {
-        ListOfGeoClassifierPredictions::new(matches)
-    }
+/// How serious a problem found in an input file is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The row cannot be imported as it is
+    Error,
+    /// The row can be imported, but probably not as intended
+    Warning,
 }
 
-impl ToJson for ListOfGeoClassifierPredictions {
-    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
-        if pretty {
-            serde_json::to_string_pretty(self)
-        } else {
-            serde_json::to_string(self)
+impl LintSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
         }
     }
 }
 
-impl ToCsv for ListOfGeoClassifierPredictions {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+/// A problem found in an input file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LintIssue {
+    /// The line in the file, counting the header
+    #[serde(rename = "ROW")]
+    pub row: u64,
+    #[serde(rename = "SEVERITY")]
+    pub severity: LintSeverity,
+    #[serde(rename = "COLUMN", skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    #[serde(rename = "MESSAGE")]
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfLintIssues {
+    pub issues: Vec<LintIssue>,
+}
+
+impl ListOfLintIssues {
+    pub fn new(issues: Vec<LintIssue>) -> Self {
+        Self { issues }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn count(&self, severity: LintSeverity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+}
+
+impl ToJson for ListOfLintIssues {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.issues)
+        } else {
+            serde_json::to_string(&self.issues)
+        }
+    }
+}
+
+impl ToYaml for ListOfLintIssues {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.issues)
+    }
+}
+
+impl WriteJsonLines for ListOfLintIssues {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.issues)
+    }
+}
+
+impl WriteCsv for ListOfLintIssues {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["ROW", "SEVERITY", "COLUMN", "MESSAGE"];
+        writer.write_header(&columns)?;
+
+        for issue in &self.issues {
+            let values = vec![
+                issue.row.to_string(),
+                issue.severity.as_str().to_string(),
+                issue.column.to_owned().unwrap_or_default(),
+                issue.message.to_owned(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How "upload-model-meta" treats the properties a model already has
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MetadataImportMode {
+    /// Sets every property in the input, whether or not the model has it already
+    #[default]
+    Upsert,
+    /// Like upsert, and also deletes the properties of the model that are not in the input
+    Replace,
+    /// Only sets the properties the model does not have yet
+    AddOnly,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataImportStatus {
+    Created,
+    Updated,
+    Deleted,
+    Skipped,
+    Error,
+}
+
+impl MetadataImportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataImportStatus::Created => "created",
+            MetadataImportStatus::Updated => "updated",
+            MetadataImportStatus::Deleted => "deleted",
+            MetadataImportStatus::Skipped => "skipped",
+            MetadataImportStatus::Error => "error",
+        }
+    }
+}
+
+/// The outcome of one row of a metadata import, or of a property deleted because it was not in the input
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetadataImportResult {
+    /// The line in the input file, counting the header
+    #[serde(rename = "ROW", skip_serializing_if = "Option::is_none")]
+    pub row: Option<u64>,
+    #[serde(rename = "UUID", skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "VALUE")]
+    pub value: String,
+    #[serde(rename = "PREVIOUS_VALUE", skip_serializing_if = "Option::is_none")]
+    pub previous_value: Option<String>,
+    #[serde(rename = "STATUS")]
+    pub status: MetadataImportStatus,
+    #[serde(rename = "REASON", skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfMetadataImportResults {
+    pub results: Vec<MetadataImportResult>,
+}
+
+impl ListOfMetadataImportResults {
+    pub fn new(results: Vec<MetadataImportResult>) -> Self {
+        Self { results }
+    }
+
+    pub fn count(&self, status: MetadataImportStatus) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    }
+}
+
+impl ToJson for ListOfMetadataImportResults {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.results)
+        } else {
+            serde_json::to_string(&self.results)
+        }
+    }
+}
+
+impl ToYaml for ListOfMetadataImportResults {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.results)
+    }
+}
+
+impl WriteJsonLines for ListOfMetadataImportResults {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.results)
+    }
+}
+
+impl WriteCsv for ListOfMetadataImportResults {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec![
+            "ROW",
+            "UUID",
+            "NAME",
+            "VALUE",
+            "PREVIOUS_VALUE",
+            "STATUS",
+            "REASON",
+        ];
+        writer.write_header(&columns)?;
+
+        for result in &self.results {
+            let values = vec![
+                result.row.map(|row| row.to_string()).unwrap_or_default(),
+                result.uuid.map(|u| u.to_string()).unwrap_or_default(),
+                result.name.to_owned(),
+                result.value.to_owned(),
+                result.previous_value.to_owned().unwrap_or_default(),
+                result.status.as_str().to_string(),
+                result.reason.to_owned().unwrap_or_default(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How "property-merge" resolves a model that already has a different value for the target property
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeConflictPolicy {
+    /// The value of the target property is kept and the source property is removed
+    #[default]
+    KeepTarget,
+    /// The value of the source property replaces the value of the target property
+    UseSource,
+    /// The model is left unchanged, so the source property cannot be deleted
+    Skip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyMergeAction {
+    /// The value was moved to the target property, which had no value or the same value
+    Moved,
+    KeptTarget,
+    Replaced,
+    Skipped,
+}
+
+impl PropertyMergeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertyMergeAction::Moved => "moved",
+            PropertyMergeAction::KeptTarget => "kept_target",
+            PropertyMergeAction::Replaced => "replaced",
+            PropertyMergeAction::Skipped => "skipped",
+        }
+    }
+}
+
+/// What "property-merge" did (or would do, in a dry run) with one model carrying the source property
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PropertyMerge {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "SOURCE_VALUE")]
+    pub source_value: String,
+    #[serde(rename = "TARGET_VALUE", skip_serializing_if = "Option::is_none")]
+    pub target_value: Option<String>,
+    #[serde(rename = "ACTION")]
+    pub action: PropertyMergeAction,
+    #[serde(rename = "ERROR", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfPropertyMerges {
+    pub merges: Vec<PropertyMerge>,
+    /// True if the source property was deleted after all of its values were merged
+    #[serde(skip)]
+    pub source_deleted: bool,
+}
+
+impl ListOfPropertyMerges {
+    pub fn new(merges: Vec<PropertyMerge>) -> Self {
+        Self {
+            merges,
+            source_deleted: false,
+        }
+    }
+
+    /// Returns true if any of the models could not be changed
+    pub fn has_failures(&self) -> bool {
+        self.merges.iter().any(|merge| merge.error.is_some())
+    }
+}
+
+impl ToJson for ListOfPropertyMerges {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.merges)
+        } else {
+            serde_json::to_string(&self.merges)
+        }
+    }
+}
+
+impl ToYaml for ListOfPropertyMerges {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.merges)
+    }
+}
+
+impl WriteJsonLines for ListOfPropertyMerges {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.merges)
+    }
+}
+
+impl WriteCsv for ListOfPropertyMerges {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec![
+            "UUID",
+            "NAME",
+            "SOURCE_VALUE",
+            "TARGET_VALUE",
+            "ACTION",
+            "ERROR",
+        ];
+        writer.write_header(&columns)?;
+
+        for merge in &self.merges {
+            let values = vec![
+                merge.uuid.to_string(),
+                merge.name.to_owned(),
+                merge.source_value.to_owned(),
+                merge.target_value.to_owned().unwrap_or_default(),
+                merge.action.as_str().to_string(),
+                merge.error.to_owned().unwrap_or_default(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What "cleanup" looks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupKind {
+    /// A folder without models
+    EmptyFolder,
+    /// A model that failed to process
+    FailedModel,
+    /// A metadata property that no model carries
+    UnusedProperty,
+}
+
+impl CleanupKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CleanupKind::EmptyFolder => "empty_folder",
+            CleanupKind::FailedModel => "failed_model",
+            CleanupKind::UnusedProperty => "unused_property",
+        }
+    }
+}
+
+/// A folder, model or property found by "cleanup", and whether it was deleted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CleanupItem {
+    #[serde(rename = "KIND")]
+    pub kind: CleanupKind,
+    /// The folder ID, the model UUID or the property ID
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    /// When the failed model was created
+    #[serde(rename = "CREATED_AT", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(rename = "DELETED")]
+    pub deleted: bool,
+    #[serde(rename = "ERROR", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CleanupItem {
+    pub fn new(kind: CleanupKind, id: String, name: String) -> Self {
+        CleanupItem {
+            kind,
+            id,
+            name,
+            created_at: None,
+            deleted: false,
+            error: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfCleanupItems {
+    pub items: Vec<CleanupItem>,
+}
+
+impl ListOfCleanupItems {
+    pub fn new(items: Vec<CleanupItem>) -> Self {
+        Self { items }
+    }
+
+    /// Returns the number of items of the kind
+    pub fn count(&self, kind: CleanupKind) -> usize {
+        self.items.iter().filter(|item| item.kind == kind).count()
+    }
+
+    /// Returns true if any of the items could not be deleted
+    pub fn has_failures(&self) -> bool {
+        self.items.iter().any(|item| item.error.is_some())
+    }
+}
+
+impl ToJson for ListOfCleanupItems {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.items)
+        } else {
+            serde_json::to_string(&self.items)
+        }
+    }
+}
+
+impl ToYaml for ListOfCleanupItems {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.items)
+    }
+}
+
+impl WriteJsonLines for ListOfCleanupItems {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.items)
+    }
+}
+
+impl WriteCsv for ListOfCleanupItems {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["KIND", "ID", "NAME", "CREATED_AT", "DELETED", "ERROR"];
+        writer.write_header(&columns)?;
+
+        for item in &self.items {
+            let values = vec![
+                item.kind.as_str().to_string(),
+                item.id.to_owned(),
+                item.name.to_owned(),
+                item.created_at.to_owned().unwrap_or_default(),
+                item.deleted.to_string(),
+                item.error.to_owned().unwrap_or_default(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A source file written by "download-many", or the reason it could not be written
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelDownload {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "FOLDER")]
+    pub folder: String,
+    /// The path or the URL the file was written to
+    #[serde(rename = "FILE", skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(rename = "SIZE")]
+    pub size: u64,
+    #[serde(rename = "ERROR", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfModelDownloads {
+    pub downloads: Vec<ModelDownload>,
+}
+
+impl ListOfModelDownloads {
+    pub fn new(downloads: Vec<ModelDownload>) -> Self {
+        Self { downloads }
+    }
+
+    /// Returns true if any of the files could not be written
+    pub fn has_failures(&self) -> bool {
+        self.downloads
+            .iter()
+            .any(|download| download.error.is_some())
+    }
+}
+
+impl ToJson for ListOfModelDownloads {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.downloads)
+        } else {
+            serde_json::to_string(&self.downloads)
+        }
+    }
+}
+
+impl ToYaml for ListOfModelDownloads {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.downloads)
+    }
+}
+
+impl WriteJsonLines for ListOfModelDownloads {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.downloads)
+    }
+}
+
+impl WriteCsv for ListOfModelDownloads {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["UUID", "NAME", "FOLDER", "FILE", "SIZE", "ERROR"];
+        writer.write_header(&columns)?;
+
+        for download in &self.downloads {
+            let values = vec![
+                download.uuid.to_string(),
+                download.name.to_owned(),
+                download.folder.to_owned(),
+                download.file.to_owned().unwrap_or_default(),
+                download.size.to_string(),
+                download.error.to_owned().unwrap_or_default(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<client::Folder> for Folder {
+    fn from(folder: client::Folder) -> Self {
+        Folder::new(folder.id, folder.name)
+    }
+}
+
+impl From<client::FolderListResponse> for ListOfFolders {
+    fn from(response: client::FolderListResponse) -> Self {
+        let folders = response.folders.into_iter().map(Folder::from).collect();
+        ListOfFolders { folders }
+    }
+}
+
+impl From<client::SingleModelResponse> for Model {
+    fn from(response: client::SingleModelResponse) -> Self {
+        Model {
+            uuid: response.model.uuid,
+            is_assembly: response.model.is_assembly,
+            name: response.model.name,
+            folder_id: response.model.folder_id,
+            folder_name: None,
+            file_type: response.model.file_type,
+            thumbnail: response.model.thumbnail,
+            owner_id: response.model.owner_id,
+            owner: None,
+            created_at: response.model.created_at,
+            units: response.model.units,
+            state: response.model.state,
+            short_id: response.model.short_id,
+            attachment_url: response.model.attachment_url,
+            metadata: None,
+        }
+    }
+}
+
+impl From<client::PartToPartMatch> for ModelMatch {
+    fn from(m: client::PartToPartMatch) -> Self {
+        let model = (m.matched_model).clone();
+        let percentage = m.match_percentage;
+        ModelMatch::new(model, percentage, None)
+    }
+}
+
+pub struct ModelMatchReport {
+    pub duplicates: SimpleDuplicatesMatchReport,
+    /// The share of each top-level assembly's components that have duplicates
+    pub coverage: ListOfAssemblyCoverage,
+    pub dictionary: HashMap<Uuid, PartNodeDictionaryItem>,
+    pub graph: MatrixGraph<String, f64>,
+    //pub matrix: Compressed<f64>,
+}
+
+/// How much of the bill of materials of a top-level assembly has duplicates elsewhere in the tenant
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssemblyCoverage {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    /// The number of components in the bill of materials, counting each occurrence
+    #[serde(rename = "COMPONENTS")]
+    pub components: u32,
+    /// The number of those components that have duplicates
+    #[serde(rename = "DUPLICATED")]
+    pub duplicated: u32,
+    /// The duplicated components as a percentage of all components
+    #[serde(rename = "COVERAGE")]
+    pub coverage: f64,
+}
+
+impl AssemblyCoverage {
+    pub fn new(uuid: Uuid, name: String, components: u32, duplicated: u32) -> Self {
+        let coverage = match components {
+            0 => 0.0,
+            _ => (duplicated as f64 * 10000.0 / components as f64).round() / 100.0,
+        };
+        Self {
+            uuid,
+            name,
+            components,
+            duplicated,
+            coverage,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfAssemblyCoverage {
+    pub items: Vec<AssemblyCoverage>,
+}
+
+impl ListOfAssemblyCoverage {
+    pub fn new(items: Vec<AssemblyCoverage>) -> Self {
+        Self { items }
+    }
+}
+
+impl ToJson for ListOfAssemblyCoverage {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(&self.items)
+        } else {
+            serde_json::to_string(&self.items)
+        }
+    }
+}
+
+impl ToYaml for ListOfAssemblyCoverage {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.items)
+    }
+}
+
+impl WriteJsonLines for ListOfAssemblyCoverage {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.items)
+    }
+}
+
+impl WriteCsv for ListOfAssemblyCoverage {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["UUID", "NAME", "COMPONENTS", "DUPLICATED", "COVERAGE"];
+        writer.write_header(&columns)?;
+
+        for item in &self.items {
+            let values = vec![
+                item.uuid.to_string(),
+                item.name.to_owned(),
+                item.components.to_string(),
+                item.duplicated.to_string(),
+                item.coverage.to_string(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct PartNodeDictionaryItem {
+    pub name: String,
+    pub node: usize,
+    pub uuid: Uuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct FolderCreateResponse {
+    #[serde(rename = "folder")]
+    pub folder: Folder,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ModelCreateMetadataResponse {
+    #[serde(rename = "metadata")]
+    pub metadata: ModelMetadataItem,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct GeoLabel {
+    #[serde(rename = "id")]
+    pub id: u32,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "geoClassifierId")]
+    pub geo_classifier_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfGeoLabels {
+    #[serde(rename = "geoLabels")]
+    pub labels: Vec<GeoLabel>,
+}
+
+impl ListOfGeoLabels {
+    pub fn new(labels: Vec<GeoLabel>) -> Self {
+        ListOfGeoLabels { labels }
+    }
+}
+
+impl From<Vec<GeoLabel>> for ListOfGeoLabels {
+    fn from(labels: Vec<GeoLabel>) -> Self {
+        ListOfGeoLabels::new(labels)
+    }
+}
+
+impl ToJson for ListOfGeoLabels {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ToYaml for ListOfGeoLabels {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteCsv for ListOfGeoLabels {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["ID", "NAME", "CLASSIFIER_ID"];
+        writer.write_header(&columns)?;
+
+        for label in &self.labels {
+            let id = label.id.to_string();
+            let name = label.name.to_owned();
+            let classifier_id = label.geo_classifier_id.to_string();
+
+            let values: Vec<String> = vec![id, name, classifier_id];
+
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GeoMatch {
+    #[serde(rename = "matchedModel")]
+    pub model: Model,
+    #[serde(rename = "confidence")]
+    pub confidence: f64,
+    #[serde(rename = "geoLabelId")]
+    pub label_id: u32,
+}
+
+impl PartialEq for GeoMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.model.name.eq(&other.model.name)
+    }
+}
+impl Eq for GeoMatch {}
+
+impl GeoMatch {
+    pub fn new(model: Model, confidence: f64, label_id: u32) -> GeoMatch {
+        GeoMatch {
+            model,
+            confidence,
+            label_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImageMatch {
+    #[serde(rename = "matchedModel")]
+    pub model: Model,
+}
+
+impl PartialEq for ImageMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.model.name.eq(&other.model.name)
+    }
+}
+impl Eq for ImageMatch {}
+
+impl ImageMatch {
+    pub fn new(model: Model) -> Self {
+        Self { model }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfGeoClassifierPredictions {
+    #[serde(rename = "matches")]
+    pub matches: Vec<GeoMatch>,
+}
+
+impl ListOfGeoClassifierPredictions {
+    pub fn new(matches: Vec<GeoMatch>) -> Self {
+        ListOfGeoClassifierPredictions { matches }
+    }
+}
+
+impl From<Vec<GeoMatch>> for ListOfGeoClassifierPredictions {
+    fn from(matches: Vec<GeoMatch>) -> Self {
+        ListOfGeoClassifierPredictions::new(matches)
+    }
+}
+
+impl ToJson for ListOfGeoClassifierPredictions {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ToYaml for ListOfGeoClassifierPredictions {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteJsonLines for ListOfGeoClassifierPredictions {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.matches)
+    }
+}
+
+impl WriteCsv for ListOfGeoClassifierPredictions {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["ID", "NAME", "CONFIDENCE", "IS_ASSEMBLY", "FOLDER"];
+        writer.write_header(&columns)?;
+
+        for m in &self.matches {
+            let id = m.model.uuid.to_string();
+            let name = m.model.name.to_owned();
+            let confidence = m.confidence.to_string();
+            let is_assembly = m.model.is_assembly.to_string();
+            let folder = m.model.folder_id.to_string();
+
+            let values: Vec<String> = vec![id, name, confidence, is_assembly, folder];
+
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct MatchedMetadataItem {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "VALUE")]
+    pub value: String,
+    #[serde(rename = "MATCH_PERCENT")]
+    pub score: f64,
+}
+
+impl MatchedMetadataItem {
+    pub fn new(uuid: Uuid, name: String, value: String, score: f64) -> Self {
+        Self {
+            uuid,
+            name,
+            value,
+            score,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfMatchedMetadataItems {
+    pub items: Vec<MatchedMetadataItem>,
+    /// Requested keys for which no value could be inferred
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved: Vec<String>,
+    /// Results for each sub-assembly and part when inference is cascaded through an assembly
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub children: BTreeMap<Uuid, ListOfMatchedMetadataItems>,
+}
+
+impl ListOfMatchedMetadataItems {
+    pub fn new(items: Vec<MatchedMetadataItem>) -> Self {
+        Self {
+            items,
+            unresolved: Vec::new(),
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl ToJson for ListOfMatchedMetadataItems {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ToYaml for ListOfMatchedMetadataItems {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteCsv for ListOfMatchedMetadataItems {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["UUID", "NAME", "VALUE", "MATCH_SCORE"];
+        writer.write_header(&columns)?;
+
+        let children = self.children.values().flat_map(|c| c.items.iter());
+        for item in self.items.iter().chain(children) {
+            let uuid = item.uuid.to_string();
+            let name = item.name.to_owned();
+            let value = item.value.to_owned();
+            let score = item.score.to_string();
+
+            let values: Vec<String> = vec![uuid, name, value, score];
+
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A change of the classification property of a model proposed by label-folder
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ClassificationChange {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "OLD_VALUE", skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<String>,
+    #[serde(rename = "NEW_VALUE", skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<String>,
+    #[serde(rename = "SOURCE_UUID", skip_serializing_if = "Option::is_none")]
+    pub source_uuid: Option<Uuid>,
+    #[serde(rename = "MATCH_SCORE", skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
+impl ClassificationChange {
+    pub fn new(
+        uuid: Uuid,
+        old_value: Option<String>,
+        new_value: Option<String>,
+        source_uuid: Option<Uuid>,
+        score: Option<f64>,
+    ) -> Self {
+        Self {
+            uuid,
+            old_value,
+            new_value,
+            source_uuid,
+            score,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfClassificationChanges {
+    pub changes: Vec<ClassificationChange>,
+}
+
+impl ListOfClassificationChanges {
+    pub fn new(changes: Vec<ClassificationChange>) -> Self {
+        Self { changes }
+    }
+}
 
+impl ToJson for ListOfClassificationChanges {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
-            let columns = vec!["ID", "NAME", "CONFIDENCE", "IS_ASSEMBLY", "FOLDER"];
-            writer.write_record(&columns)?;
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
         }
+    }
+}
 
-        for m in &self.matches {
-            let id = m.model.uuid.to_string();
-            let name = m.model.name.to_owned();
-            let confidence = m.confidence.to_string();
-            let is_assembly = m.model.is_assembly.to_string();
-            let folder = m.model.folder_id.to_string();
+impl ToYaml for ListOfClassificationChanges {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
 
-            let mut values: Vec<String> = Vec::new();
-            values.push(id);
-            values.push(name);
-            values.push(confidence);
-            values.push(is_assembly);
-            values.push(folder);
+impl WriteJsonLines for ListOfClassificationChanges {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.changes)
+    }
+}
 
+impl WriteCsv for ListOfClassificationChanges {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec![
+            "UUID",
+            "OLD_VALUE",
+            "NEW_VALUE",
+            "SOURCE_UUID",
+            "MATCH_SCORE",
+        ];
+        writer.write_header(&columns)?;
+
+        for change in &self.changes {
+            let values = vec![
+                change.uuid.to_string(),
+                change.old_value.to_owned().unwrap_or_default(),
+                change.new_value.to_owned().unwrap_or_default(),
+                change
+                    .source_uuid
+                    .map(|u| u.to_string())
+                    .unwrap_or_default(),
+                change.score.map(|s| s.to_string()).unwrap_or_default(),
+            ];
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
+    }
+}
+
+/// How a file differs between the local directory and the folder compared by sync
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncDifference {
+    /// The file exists only in the local directory
+    LocalOnly,
+    /// The model exists only in the folder
+    RemoteOnly,
+    /// The checksum of the local file differs from the one stored with the model
+    Changed,
+}
+
+impl SyncDifference {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncDifference::LocalOnly => "local_only",
+            SyncDifference::RemoteOnly => "remote_only",
+            SyncDifference::Changed => "changed",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    Upload,
+    Download,
+    Delete,
+    /// The difference is only reported
+    None,
+}
+
+impl SyncAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncAction::Upload => "upload",
+            SyncAction::Download => "download",
+            SyncAction::Delete => "delete",
+            SyncAction::None => "none",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    Done,
+    /// The action was not performed, because of a dry run
+    Planned,
+    Skipped,
+    Failed,
+    /// The action was not performed, because the maximum duration of the run was exceeded
+    NotStarted,
+}
+
+impl SyncStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncStatus::Done => "done",
+            SyncStatus::Planned => "planned",
+            SyncStatus::Skipped => "skipped",
+            SyncStatus::Failed => "failed",
+            SyncStatus::NotStarted => "not_started",
+        }
     }
 }
 
+/// A difference found by sync and what was done about it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SyncChange {
+    #[serde(rename = "FILE")]
+    pub file: String,
+    #[serde(rename = "UUID", skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    #[serde(rename = "DIFFERENCE")]
+    pub difference: SyncDifference,
+    #[serde(rename = "ACTION")]
+    pub action: SyncAction,
+    #[serde(rename = "STATUS")]
+    pub status: SyncStatus,
+    #[serde(rename = "ERROR", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct MatchedMetadataItem {
-    #[serde(rename = "UUID")]
-    pub uuid: Uuid,
-    #[serde(rename = "NAME")]
-    pub name: String,
-    #[serde(rename = "VALUE")]
-    pub value: String,
-    #[serde(rename = "MATCH_PERCENT")]
-    pub score: f64,
+pub struct ListOfSyncChanges {
+    pub changes: Vec<SyncChange>,
 }
 
-impl MatchedMetadataItem {
-    pub fn new(uuid: Uuid, name: String, value: String, score: f64) -> Self {
-        Self {
-            uuid,
-            name,
-            value,
-            score,
+impl ListOfSyncChanges {
+    pub fn new(changes: Vec<SyncChange>) -> Self {
+        Self { changes }
+    }
+
+    /// Returns true if any of the actions failed
+    pub fn has_failures(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.status == SyncStatus::Failed)
+    }
+
+    /// Returns the number of actions that were not performed, because the maximum duration of the run was exceeded
+    pub fn not_started(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.status == SyncStatus::NotStarted)
+            .count()
+    }
+}
+
+impl ToJson for ListOfSyncChanges {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ToYaml for ListOfSyncChanges {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteJsonLines for ListOfSyncChanges {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.changes)
+    }
+}
+
+impl WriteCsv for ListOfSyncChanges {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["FILE", "UUID", "DIFFERENCE", "ACTION", "STATUS", "ERROR"];
+        writer.write_header(&columns)?;
+
+        for change in &self.changes {
+            let values = vec![
+                change.file.to_owned(),
+                change.uuid.map(|u| u.to_string()).unwrap_or_default(),
+                change.difference.as_str().to_string(),
+                change.action.as_str().to_string(),
+                change.status.as_str().to_string(),
+                change.error.to_owned().unwrap_or_default(),
+            ];
+            writer.write_record(&values)?;
         }
+
+        Ok(())
     }
 }
 
+/// A newly uploaded model that duplicates a model already in the tenant
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateUpload {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "DUPLICATE_OF")]
+    pub duplicate_of: Uuid,
+    #[serde(rename = "DUPLICATE_NAME")]
+    pub duplicate_name: String,
+    #[serde(rename = "DUPLICATE_FOLDER", skip_serializing_if = "Option::is_none")]
+    pub duplicate_folder: Option<String>,
+    #[serde(rename = "MATCH_PERCENTAGE")]
+    pub percentage: f64,
+    /// True if the new model was deleted
+    #[serde(rename = "DELETED")]
+    pub deleted: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct ListOfMatchedMetadataItems {
-    pub items: Vec<MatchedMetadataItem>,
+pub struct ListOfDuplicateUploads {
+    pub duplicates: Vec<DuplicateUpload>,
 }
 
-impl ListOfMatchedMetadataItems {
-    pub fn new(items: Vec<MatchedMetadataItem>) -> Self {
-        Self { items }
+impl ListOfDuplicateUploads {
+    pub fn new(duplicates: Vec<DuplicateUpload>) -> Self {
+        Self { duplicates }
     }
 }
 
-impl ToJson for ListOfMatchedMetadataItems {
+impl ToJson for ListOfDuplicateUploads {
     fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
             serde_json::to_string_pretty(self)
@@ -1687,37 +4086,119 @@ impl ToJson for ListOfMatchedMetadataItems {
     }
 }
 
-impl ToCsv for ListOfMatchedMetadataItems {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl WriteCsv for ListOfDuplicateUploads {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec![
+            "UUID",
+            "NAME",
+            "DUPLICATE_OF",
+            "DUPLICATE_NAME",
+            "DUPLICATE_FOLDER",
+            "MATCH_PERCENTAGE",
+            "DELETED",
+        ];
+        writer.write_header(&columns)?;
+
+        for duplicate in &self.duplicates {
+            let values = vec![
+                duplicate.uuid.to_string(),
+                duplicate.name.to_owned(),
+                duplicate.duplicate_of.to_string(),
+                duplicate.duplicate_name.to_owned(),
+                duplicate.duplicate_folder.to_owned().unwrap_or_default(),
+                duplicate.percentage.to_string(),
+                duplicate.deleted.to_string(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The status of the cached access token of a configured tenant
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct CachedToken {
+    #[serde(rename = "tenant")]
+    pub tenant: String,
+    #[serde(rename = "store")]
+    pub store: String,
+    #[serde(rename = "cached")]
+    pub cached: bool,
+    #[serde(rename = "expiresAt", skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(rename = "expiresIn", skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<i64>,
+}
+
+impl CachedToken {
+    pub fn new(
+        tenant: String,
+        store: String,
+        cached: bool,
+        expires_at: Option<i64>,
+        expires_in: Option<i64>,
+    ) -> Self {
+        Self {
+            tenant,
+            store,
+            cached,
+            expires_at,
+            expires_in,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfCachedTokens {
+    pub tokens: Vec<CachedToken>,
+}
+
+impl From<Vec<CachedToken>> for ListOfCachedTokens {
+    fn from(tokens: Vec<CachedToken>) -> Self {
+        Self { tokens }
+    }
+}
 
+impl ToJson for ListOfCachedTokens {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
-            let columns = vec!["UUID", "NAME", "VALUE", "MATCH_SCORE"];
-            writer.write_record(&columns)?;
+            serde_json::to_string_pretty(&self.tokens)
+        } else {
+            serde_json::to_string(&self.tokens)
         }
+    }
+}
 
-        for item in &self.items {
-            let uuid = item.uuid.to_string();
-            let name = item.name.to_owned();
-            let value = item.value.to_owned();
-            let score = item.score.to_string();
+impl ToYaml for ListOfCachedTokens {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.tokens)
+    }
+}
 
-            let mut values: Vec<String> = Vec::new();
-            values.push(uuid);
-            values.push(name);
-            values.push(value);
-            values.push(score);
+impl WriteJsonLines for ListOfCachedTokens {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.tokens)
+    }
+}
 
+impl WriteCsv for ListOfCachedTokens {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["TENANT", "STORE", "CACHED", "EXPIRES_AT", "EXPIRES_IN"];
+        writer.write_header(&columns)?;
+
+        for token in self.tokens.iter() {
+            let values = vec![
+                token.tenant.to_owned(),
+                token.store.to_owned(),
+                token.cached.to_string(),
+                token.expires_at.map(|t| t.to_string()).unwrap_or_default(),
+                token.expires_in.map(|t| t.to_string()).unwrap_or_default(),
+            ];
             writer.write_record(&values)?;
         }
-        writer.flush()?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -1735,6 +4216,16 @@ pub struct User {
     pub department: String,
     #[serde(rename = "roles")]
     pub roles: Vec<String>,
+    #[serde(rename = "firstName", default, skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName", default, skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    #[serde(
+        rename = "lastActiveAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_active: Option<String>,
 }
 
 impl User {
@@ -1753,10 +4244,64 @@ impl User {
             status,
             department,
             roles,
+            first_name: None,
+            last_name: None,
+            last_active: None,
         }
     }
+
+    /// Returns the full name of the user
+    pub fn name(&self) -> String {
+        [self.first_name.as_deref(), self.last_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Returns true if the search text is found (case-insensitive) in the name, email, external ID or department
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+        [
+            self.name(),
+            self.email.to_owned(),
+            self.external_id.to_owned(),
+            self.department.to_owned(),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&search))
+    }
+
+    /// Returns true if the user has the role (case-insensitive)
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r.eq_ignore_ascii_case(role))
+    }
+
+    fn to_csv_values(&self) -> Vec<String> {
+        vec![
+            self.uuid.to_string(),
+            self.external_id.to_owned(),
+            self.email.to_owned(),
+            self.status.to_owned(),
+            self.department.to_owned(),
+            self.roles.join(","),
+            self.last_active.to_owned().unwrap_or_default(),
+            self.name(),
+        ]
+    }
 }
 
+const USER_CSV_COLUMNS: [&str; 8] = [
+    "UUID",
+    "EXTERNAL_ID",
+    "EMAIL",
+    "STATUS",
+    "DEPARTMENT",
+    "ROLES",
+    "LAST_ACTIVE",
+    "NAME",
+];
+
 impl ToJson for User {
     fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
@@ -1767,39 +4312,19 @@ impl ToJson for User {
     }
 }
 
-impl ToCsv for User {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
-
-        if pretty {
-            let columns = vec![
-                "UUID",
-                "EXTERNAL_ID",
-                "EMAIL",
-                "STATUS",
-                "DEPARTMENT",
-                "ROLES",
-            ];
-            writer.write_record(&columns)?;
-        }
+impl ToYaml for User {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
 
-        let mut values: Vec<String> = Vec::new();
-        values.push(self.uuid.to_string());
-        values.push(self.external_id.to_owned());
-        values.push(self.email.to_owned());
-        values.push(self.status.to_owned());
-        values.push(self.department.to_owned());
-        let roles: String = self.roles.join(",");
-        values.push(roles);
+impl WriteCsv for User {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        writer.write_header(USER_CSV_COLUMNS)?;
 
-        writer.flush()?;
+        writer.write_record(self.to_csv_values())?;
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -1818,48 +4343,239 @@ impl ToJson for ListOfUsers {
     }
 }
 
-impl ToCsv for ListOfUsers {
-    fn to_csv(&self, pretty: bool) -> Result<String, ParsingError> {
-        let buf = BufWriter::new(Vec::new());
-        let mut writer = WriterBuilder::new()
-            .terminator(Terminator::CRLF)
-            .from_writer(buf);
+impl ToYaml for ListOfUsers {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.users)
+    }
+}
+
+impl WriteJsonLines for ListOfUsers {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.users)
+    }
+}
+
+impl WriteCsv for ListOfUsers {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        writer.write_header(USER_CSV_COLUMNS)?;
+
+        for user in self.users.iter() {
+            writer.write_record(user.to_csv_values())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<User>> for ListOfUsers {
+    fn from(users: Vec<User>) -> Self {
+        Self { users }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestStatus {
+    Uploaded,
+    Failed,
+    /// The file was not uploaded, because the maximum duration of the run was exceeded
+    NotStarted,
+}
+
+impl ManifestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ManifestStatus::Uploaded => "uploaded",
+            ManifestStatus::Failed => "failed",
+            ManifestStatus::NotStarted => "not_started",
+        }
+    }
+}
+
+/// The outcome of uploading one file listed in a manifest
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestUpload {
+    #[serde(rename = "FILE")]
+    pub file: String,
+    #[serde(rename = "FOLDER")]
+    pub folder: String,
+    /// Set if the model was created, even if its metadata could not be applied
+    #[serde(rename = "UUID", skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    #[serde(rename = "STATUS")]
+    pub status: ManifestStatus,
+    #[serde(rename = "ERROR", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfManifestUploads {
+    pub uploads: Vec<ManifestUpload>,
+}
+
+impl ListOfManifestUploads {
+    pub fn new(uploads: Vec<ManifestUpload>) -> Self {
+        Self { uploads }
+    }
+
+    /// Returns true if any of the files failed to upload
+    pub fn has_failures(&self) -> bool {
+        self.uploads
+            .iter()
+            .any(|upload| upload.status == ManifestStatus::Failed)
+    }
+
+    /// Returns the number of files that were not uploaded, because the maximum duration of the run was exceeded
+    pub fn not_started(&self) -> usize {
+        self.uploads
+            .iter()
+            .filter(|upload| upload.status == ManifestStatus::NotStarted)
+            .count()
+    }
+}
 
+impl ToJson for ListOfManifestUploads {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
         if pretty {
-            let columns = vec![
-                "UUID",
-                "EXTERNAL_ID",
-                "EMAIL",
-                "STATUS",
-                "DEPARTMENT",
-                "ROLES",
-            ];
-            writer.write_record(&columns)?;
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
         }
+    }
+}
 
-        for user in self.users.clone() {
-            let mut values: Vec<String> = Vec::new();
-            values.push(user.uuid.to_string());
-            values.push(user.external_id.to_owned());
-            values.push(user.email.to_owned());
-            values.push(user.status.to_owned());
-            values.push(user.department.to_owned());
-            let roles: String = user.roles.join(",");
-            values.push(roles);
+impl ToYaml for ListOfManifestUploads {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteJsonLines for ListOfManifestUploads {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.uploads)
+    }
+}
 
+impl WriteCsv for ListOfManifestUploads {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["FILE", "FOLDER", "UUID", "STATUS", "ERROR"];
+        writer.write_header(&columns)?;
+
+        for upload in &self.uploads {
+            let values = vec![
+                upload.file.to_owned(),
+                upload.folder.to_owned(),
+                upload.uuid.map(|u| u.to_string()).unwrap_or_default(),
+                upload.status.as_str().to_string(),
+                upload.error.to_owned().unwrap_or_default(),
+            ];
             writer.write_record(&values)?;
         }
 
-        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// How the processing of a model ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingOutcome {
+    /// The model is FINISHED (or has no 3D data)
+    Finished,
+    Failed,
+    /// The model was still being processed when the wait ended
+    TimedOut,
+}
+
+impl ProcessingOutcome {
+    pub fn of(model: &Model) -> Self {
+        if model.is_healthy() {
+            ProcessingOutcome::Finished
+        } else if model.is_failed() {
+            ProcessingOutcome::Failed
+        } else {
+            ProcessingOutcome::TimedOut
+        }
+    }
 
-        let bytes = writer.into_inner()?.into_inner()?;
-        let result = String::from_utf8(bytes)?;
-        Ok(result)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProcessingOutcome::Finished => "finished",
+            ProcessingOutcome::Failed => "failed",
+            ProcessingOutcome::TimedOut => "timed_out",
+        }
     }
 }
 
-impl From<Vec<User>> for ListOfUsers {
-    fn from(users: Vec<User>) -> Self {
-        Self { users }
+/// The state of a model before and after it was reprocessed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReprocessOutcome {
+    #[serde(rename = "UUID")]
+    pub uuid: Uuid,
+    #[serde(rename = "NAME")]
+    pub name: String,
+    #[serde(rename = "PREVIOUS_STATE")]
+    pub previous_state: String,
+    #[serde(rename = "STATE")]
+    pub state: String,
+    #[serde(rename = "OUTCOME")]
+    pub outcome: ProcessingOutcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListOfReprocessOutcomes {
+    pub outcomes: Vec<ReprocessOutcome>,
+}
+
+impl ListOfReprocessOutcomes {
+    pub fn new(outcomes: Vec<ReprocessOutcome>) -> Self {
+        Self { outcomes }
+    }
+
+    /// Returns true if any of the models has this outcome
+    pub fn any(&self, outcome: ProcessingOutcome) -> bool {
+        self.outcomes.iter().any(|o| o.outcome == outcome)
+    }
+}
+
+impl ToJson for ListOfReprocessOutcomes {
+    fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ToYaml for ListOfReprocessOutcomes {
+    fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl WriteJsonLines for ListOfReprocessOutcomes {
+    fn write_json_lines<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        write_json_lines(writer, &self.outcomes)
+    }
+}
+
+impl WriteCsv for ListOfReprocessOutcomes {
+    fn write_csv<W: io::Write>(&self, writer: &mut CsvWriter<W>) -> Result<(), ParsingError> {
+        let columns = vec!["UUID", "NAME", "PREVIOUS_STATE", "STATE", "OUTCOME"];
+        writer.write_header(&columns)?;
+
+        for outcome in &self.outcomes {
+            let values = vec![
+                outcome.uuid.to_string(),
+                outcome.name.to_owned(),
+                outcome.previous_state.to_owned(),
+                outcome.state.to_owned(),
+                outcome.outcome.as_str().to_string(),
+            ];
+            writer.write_record(&values)?;
+        }
+
+        Ok(())
     }
 }
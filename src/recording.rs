@@ -0,0 +1,371 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use reqwest::{Request, Response, ResponseBuilderExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("I/O error: {0}")]
+    InputOutputError(#[from] std::io::Error),
+    #[error("Invalid recording {0}: {1}")]
+    InvalidExchange(String, serde_json::Error),
+    #[error("No recorded requests in {0}")]
+    Empty(String),
+}
+
+/// One HTTP request and the response received for it, as stored in the recording. The access token and the query of
+/// transfer URLs are not stored
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Exchange {
+    method: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    /// The response body, if it is text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// The response body, if it is binary (e.g. a downloaded model file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_base64: Option<String>,
+}
+
+/// Stands for the query of a transfer URL, which carries the signature of the pre-signed URL
+const REDACTED: &str = "REDACTED";
+
+/// Replaces the query of a transfer URL, if any
+fn redact(url: &Url, transfer: bool) -> Url {
+    let mut url = url.to_owned();
+    if transfer && url.query().is_some() {
+        url.set_query(Some(REDACTED));
+    }
+    url
+}
+
+impl Exchange {
+    /// Describes the request, before it is sent. Transfers go to pre-signed storage URLs, whose query is redacted
+    pub(crate) fn new(request: &Request, transfer: bool) -> Self {
+        Exchange {
+            method: request.method().to_string(),
+            url: redact(request.url(), transfer).to_string(),
+            request_body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .and_then(|body| String::from_utf8(body.to_vec()).ok()),
+            ..Default::default()
+        }
+    }
+
+    /// Requests are matched by method, path and query, so that a recording can be replayed against any host
+    fn key(method: &str, url: &Url) -> String {
+        match url.query() {
+            Some(query) => format!("{} {}?{}", method, url.path(), query),
+            None => format!("{} {}", method, url.path()),
+        }
+    }
+
+    fn body(&self) -> Vec<u8> {
+        match (&self.body, &self.body_base64) {
+            (Some(body), _) => body.as_bytes().to_vec(),
+            (None, Some(body)) => general_purpose::STANDARD.decode(body).unwrap_or_default(),
+            (None, None) => Vec::new(),
+        }
+    }
+}
+
+/// Writes every HTTP exchange into its own numbered JSON file in the recording directory
+#[derive(Debug)]
+pub struct Recorder {
+    directory: PathBuf,
+    sequence: Mutex<u32>,
+}
+
+impl Recorder {
+    /// Stores the response to the request and returns an equivalent one, because reading the body consumes the
+    /// original
+    pub(crate) async fn record(
+        &self,
+        mut exchange: Exchange,
+        response: Response,
+    ) -> Result<Response, reqwest::Error> {
+        let url = response.url().to_owned();
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().to_owned();
+        let bytes = response.bytes().await?;
+
+        exchange.status = status.as_u16();
+        for (name, value) in headers.iter() {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            exchange
+                .headers
+                .entry(name.to_string())
+                .and_modify(|values| *values = format!("{}, {}", values, value))
+                .or_insert_with(|| value.to_string());
+        }
+        match String::from_utf8(bytes.to_vec()) {
+            Ok(body) => exchange.body = Some(body),
+            Err(_) => exchange.body_base64 = Some(general_purpose::STANDARD.encode(&bytes)),
+        }
+        if let Err(e) = self.write(&exchange) {
+            log::warn!(
+                "Failed to record {} {}: {}",
+                exchange.method,
+                exchange.url,
+                e
+            );
+        }
+
+        let mut builder = http::Response::builder()
+            .status(status)
+            .version(version)
+            .url(url);
+        if let Some(response_headers) = builder.headers_mut() {
+            *response_headers = headers;
+        }
+        Ok(Response::from(builder.body(bytes).unwrap_or_default()))
+    }
+
+    fn write(&self, exchange: &Exchange) -> Result<(), RecordingError> {
+        let sequence = {
+            let mut sequence = self.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            *sequence += 1;
+            *sequence
+        };
+
+        // e.g. 0007-GET-v2-models.json
+        let path: String = Url::parse(&exchange.url)
+            .map(|url| url.path().to_owned())
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .take(80)
+            .collect();
+        let file = self
+            .directory
+            .join(format!("{:04}-{}-{}.json", sequence, exchange.method, path));
+
+        let json = serde_json::to_string_pretty(exchange)
+            .map_err(|e| RecordingError::InvalidExchange(file.to_string_lossy().to_string(), e))?;
+        fs::write(file, json)?;
+        Ok(())
+    }
+}
+
+/// Serves the responses of a recording instead of sending the requests. Identical requests receive the recorded
+/// responses in their original order; once those run out, the last one is repeated
+#[derive(Debug)]
+pub struct Replay {
+    directory: PathBuf,
+    exchanges: Mutex<HashMap<String, VecDeque<Exchange>>>,
+}
+
+impl Replay {
+    /// Transfers are matched without the query of their URL, which is redacted in the recording
+    pub(crate) fn respond(&self, request: &Request, transfer: bool) -> Response {
+        let key = Exchange::key(request.method().as_str(), &redact(request.url(), transfer));
+        let exchange = {
+            let mut exchanges = self.exchanges.lock().unwrap_or_else(|e| e.into_inner());
+            exchanges.get_mut(&key).and_then(|queue| {
+                if queue.len() > 1 {
+                    queue.pop_front()
+                } else {
+                    queue.front().cloned()
+                }
+            })
+        };
+
+        let response = match exchange {
+            Some(exchange) => {
+                log::trace!("Replaying {}", key);
+                let mut builder = http::Response::builder()
+                    .status(exchange.status)
+                    .url(request.url().to_owned());
+                for (name, value) in exchange.headers.iter() {
+                    builder = builder.header(name.as_str(), value.as_str());
+                }
+                builder.body(exchange.body())
+            }
+            None => {
+                log::warn!(
+                    "There is no recorded response for {} in {}",
+                    key,
+                    self.directory.to_string_lossy()
+                );
+                let message = serde_json::json!({
+                    "error": {
+                        "message": format!("There is no recorded response for {}", key)
+                    }
+                });
+                http::Response::builder()
+                    .status(http::StatusCode::NOT_FOUND)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .url(request.url().to_owned())
+                    .body(message.to_string().into_bytes())
+            }
+        };
+        Response::from(response.unwrap_or_default())
+    }
+}
+
+/// Captures the HTTP traffic of a run to disk, or serves a run from such a capture, e.g. for offline demos,
+/// reproducible bug reports and tests against real response payloads
+#[derive(Debug)]
+pub enum Recording {
+    Record(Recorder),
+    Replay(Replay),
+}
+
+impl Recording {
+    /// Records into the directory, which is created if needed. The exchanges are added after any that were
+    /// recorded there before
+    pub fn record(directory: &Path) -> Result<Recording, RecordingError> {
+        fs::create_dir_all(directory)?;
+        let last = fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.split('-').next().and_then(|n| n.parse::<u32>().ok())
+            })
+            .max()
+            .unwrap_or_default();
+        Ok(Recording::Record(Recorder {
+            directory: directory.to_owned(),
+            sequence: Mutex::new(last),
+        }))
+    }
+
+    /// Reads the recording from the directory
+    pub fn replay(directory: &Path) -> Result<Recording, RecordingError> {
+        let mut files: Vec<PathBuf> = fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect();
+        files.sort();
+
+        let mut exchanges: HashMap<String, VecDeque<Exchange>> = HashMap::new();
+        for file in files.iter() {
+            let name = file.to_string_lossy().to_string();
+            let exchange: Exchange = serde_json::from_str(&fs::read_to_string(file)?)
+                .map_err(|e| RecordingError::InvalidExchange(name.to_owned(), e))?;
+            let url = match Url::parse(&exchange.url) {
+                Ok(url) => url,
+                Err(e) => {
+                    log::warn!("Ignoring {}: {}", name, e);
+                    continue;
+                }
+            };
+            exchanges
+                .entry(Exchange::key(&exchange.method, &url))
+                .or_default()
+                .push_back(exchange);
+        }
+
+        if exchanges.is_empty() {
+            return Err(RecordingError::Empty(
+                directory.to_string_lossy().to_string(),
+            ));
+        }
+
+        Ok(Recording::Replay(Replay {
+            directory: directory.to_owned(),
+            exchanges: Mutex::new(exchanges),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use reqwest::Method;
+
+    #[test]
+    fn test_replay_in_recorded_order() {
+        let directory = tempfile::tempdir().unwrap();
+        for (sequence, state) in [(1, "processing"), (2, "finished")] {
+            let exchange = Exchange {
+                method: "GET".to_string(),
+                url: "https://api.physna.com/v2/models/1?meta=true".to_string(),
+                status: 200,
+                body: Some(state.to_string()),
+                ..Default::default()
+            };
+            fs::write(
+                directory
+                    .path()
+                    .join(format!("{:04}-GET-v2-models-1.json", sequence)),
+                serde_json::to_string(&exchange).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let replay = match Recording::replay(directory.path()).unwrap() {
+            Recording::Replay(replay) => replay,
+            Recording::Record(_) => panic!("expected a replay"),
+        };
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let request = |url: &str| Request::new(Method::GET, Url::parse(url).unwrap());
+
+        // the host does not matter and the last response is repeated
+        for expected in ["processing", "finished", "finished"] {
+            let response =
+                replay.respond(&request("http://localhost/v2/models/1?meta=true"), false);
+            assert_eq!(response.status(), 200);
+            assert_eq!(runtime.block_on(response.text()).unwrap(), expected);
+        }
+
+        let response = replay.respond(&request("http://localhost/v2/models/2?meta=true"), false);
+        assert_eq!(response.status(), 404);
+    }
+
+    #[test]
+    fn test_record_transfer_without_signature() {
+        let directory = tempfile::tempdir().unwrap();
+        let recorder = match Recording::record(directory.path()).unwrap() {
+            Recording::Record(recorder) => recorder,
+            Recording::Replay(_) => panic!("expected a recorder"),
+        };
+        let url = "https://storage.example.com/bucket/model.stl?X-Amz-Signature=secret";
+        let request = Request::new(Method::PUT, Url::parse(url).unwrap());
+
+        let mut exchange = Exchange::new(&request, true);
+        exchange.status = 201;
+        recorder.write(&exchange).unwrap();
+        let file = fs::read_dir(directory.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let recorded = fs::read_to_string(file).unwrap();
+        assert!(!recorded.contains("secret"));
+        assert!(recorded.contains("https://storage.example.com/bucket/model.stl?REDACTED"));
+
+        // the transfer is replayed with any signature
+        let replay = match Recording::replay(directory.path()).unwrap() {
+            Recording::Replay(replay) => replay,
+            Recording::Record(_) => panic!("expected a replay"),
+        };
+        let request = Request::new(
+            Method::PUT,
+            Url::parse("http://localhost/bucket/model.stl?X-Amz-Signature=other").unwrap(),
+        );
+        assert_eq!(replay.respond(&request, true).status(), 201);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::metadata_file::MetadataRow;
+use crate::model::{self, ListOfMetadataViolations, MetadataViolation};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("I/O error: {0}")]
+    InputOutputError(#[from] std::io::Error),
+    #[error("Invalid metadata schema: {0}")]
+    YamlParsingError(#[from] serde_yaml::Error),
+}
+
+/// The kind of values a metadata property holds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PropertyType {
+    #[default]
+    Text,
+    Number,
+    Integer,
+    Boolean,
+    /// A date (e.g. "2024-05-01") or an RFC 3339 timestamp
+    Date,
+}
+
+impl PropertyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertyType::Text => "text",
+            PropertyType::Number => "number",
+            PropertyType::Integer => "integer",
+            PropertyType::Boolean => "boolean",
+            PropertyType::Date => "date",
+        }
+    }
+
+    /// Reads the value as a number. Dates, booleans and text are not numbers
+    pub fn number(&self, value: &str) -> Option<f64> {
+        match self {
+            PropertyType::Number => value.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+            PropertyType::Integer => value.trim().parse::<i64>().ok().map(|n| n as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for PropertyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(PropertyType::Text),
+            "number" => Ok(PropertyType::Number),
+            "integer" => Ok(PropertyType::Integer),
+            "boolean" => Ok(PropertyType::Boolean),
+            "date" => Ok(PropertyType::Date),
+            _ => Err(format!(
+                "'{}' is not a property type (text, number, integer, boolean or date)",
+                s
+            )),
+        }
+    }
+}
+
+/// The types of metadata properties, given as a comma-separated list such as "cost=number,released=date". Property
+/// names are compared ignoring the case
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetadataTypes(HashMap<String, PropertyType>);
+
+impl MetadataTypes {
+    pub fn get(&self, name: &str) -> Option<PropertyType> {
+        self.0.get(&name.trim().to_lowercase()).copied()
+    }
+}
+
+impl FromStr for MetadataTypes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut types = HashMap::new();
+        for pair in s.split(',').filter(|pair| !pair.trim().is_empty()) {
+            match pair.split_once('=') {
+                Some((name, property_type)) if !name.trim().is_empty() => {
+                    types.insert(name.trim().to_lowercase(), property_type.parse()?);
+                }
+                _ => return Err(format!("'{}' is not a NAME=TYPE pair", pair)),
+            }
+        }
+        Ok(MetadataTypes(types))
+    }
+}
+
+/// Compares two values of a property. Without a type, the values are compared as numbers if both are numbers, as
+/// dates if both are dates, and as text otherwise. Text and booleans are compared ignoring the case
+pub fn compare_values(a: &str, b: &str, property_type: Option<PropertyType>) -> Ordering {
+    let as_number = |value: &str| value.trim().parse::<f64>().ok().filter(|n| n.is_finite());
+    let as_text = || a.trim().to_lowercase().cmp(&b.trim().to_lowercase());
+    match property_type {
+        Some(PropertyType::Number) | Some(PropertyType::Integer) => {
+            // values that are not numbers come last
+            match (as_number(a), as_number(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()).then_with(as_text),
+            }
+        }
+        Some(PropertyType::Date) => match (model::parse_timestamp(a), model::parse_timestamp(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()).then_with(as_text),
+        },
+        Some(PropertyType::Text) | Some(PropertyType::Boolean) => as_text(),
+        None => match (as_number(a), as_number(b)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            _ => match (model::parse_timestamp(a), model::parse_timestamp(b)) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => as_text(),
+            },
+        },
+    }
+}
+
+/// How a metadata condition compares the value of the property
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConditionOperator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// A condition on the value of a metadata property, such as "Material=steel" or "cost<100"
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataCondition {
+    pub name: String,
+    pub operator: ConditionOperator,
+    pub value: String,
+    pub property_type: Option<PropertyType>,
+}
+
+impl MetadataCondition {
+    /// Reads a condition with one of the operators =, !=, <, <=, > or >=. The type of the property, if known, decides
+    /// how the values are compared
+    pub fn parse(expression: &str, types: &MetadataTypes) -> Result<MetadataCondition, String> {
+        let operators = [
+            ("!=", ConditionOperator::NotEqual),
+            ("<=", ConditionOperator::LessOrEqual),
+            (">=", ConditionOperator::GreaterOrEqual),
+            ("=", ConditionOperator::Equal),
+            ("<", ConditionOperator::Less),
+            (">", ConditionOperator::Greater),
+        ];
+        // the first operator in the expression separates the name from the value
+        let found = operators
+            .iter()
+            .filter_map(|(symbol, operator)| {
+                expression
+                    .find(symbol)
+                    .map(|position| (position, symbol.len(), *operator))
+            })
+            .min_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        match found {
+            Some((position, length, operator)) if position > 0 => {
+                let name = expression[..position].trim().to_owned();
+                Ok(MetadataCondition {
+                    property_type: types.get(&name),
+                    name,
+                    operator,
+                    value: expression[position + length..].to_owned(),
+                })
+            }
+            _ => Err(format!(
+                "'{}' is not a condition such as NAME=VALUE or NAME<VALUE",
+                expression
+            )),
+        }
+    }
+
+    /// Returns true if the property is set and its value meets the condition. Without a type, equality compares the
+    /// values exactly
+    pub fn matches(&self, properties: &HashMap<String, String>) -> bool {
+        let value = match properties
+            .iter()
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(&self.name))
+        {
+            Some((_, value)) => value,
+            None => return false,
+        };
+        match self.operator {
+            ConditionOperator::Equal => self.equals(value),
+            ConditionOperator::NotEqual => !self.equals(value),
+            operator => {
+                let ordering = compare_values(value, &self.value, self.property_type);
+                match operator {
+                    ConditionOperator::Less => ordering == Ordering::Less,
+                    ConditionOperator::LessOrEqual => ordering != Ordering::Greater,
+                    ConditionOperator::Greater => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }
+            }
+        }
+    }
+
+    fn equals(&self, value: &str) -> bool {
+        match self.property_type {
+            None => value == self.value,
+            property_type => compare_values(value, &self.value, property_type) == Ordering::Equal,
+        }
+    }
+}
+
+/// The rules for the values of one property
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PropertySchema {
+    #[serde(rename = "type", default)]
+    pub property_type: PropertyType,
+    /// If not empty, the only values allowed
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+/// The metadata properties that may be set, read from a YAML or JSON file such as:
+///
+/// ```yaml
+/// properties:
+///   Material:
+///     values: [steel, aluminum, ABS]
+///   Weight:
+///     type: number
+/// ```
+///
+/// Property names are compared ignoring the case, values are compared exactly
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataSchema {
+    #[serde(default)]
+    pub properties: BTreeMap<String, PropertySchema>,
+    /// Accepts the properties that are not listed, without checking their values
+    #[serde(default)]
+    pub allow_other: bool,
+}
+
+impl MetadataSchema {
+    pub fn read(path: &Path) -> Result<MetadataSchema, SchemaError> {
+        Ok(serde_yaml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Returns the reason why the value is not allowed for the property. An empty value deletes the property, so it
+    /// is always allowed
+    pub fn check(&self, name: &str, value: &str) -> Option<String> {
+        if value.is_empty() {
+            return None;
+        }
+
+        let property = match self
+            .properties
+            .iter()
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case(name.trim()))
+        {
+            Some((_, property)) => property,
+            None if self.allow_other => return None,
+            None => return Some(format!("'{}' is not a known property", name)),
+        };
+
+        let valid = match property.property_type {
+            PropertyType::Text => true,
+            PropertyType::Number => value.trim().parse::<f64>().is_ok_and(|n| n.is_finite()),
+            PropertyType::Integer => value.trim().parse::<i64>().is_ok(),
+            PropertyType::Boolean => ["true", "false"]
+                .iter()
+                .any(|b| value.trim().eq_ignore_ascii_case(b)),
+            PropertyType::Date => model::parse_timestamp(value).is_some(),
+        };
+        if !valid {
+            return Some(format!(
+                "'{}' is not a valid {} value",
+                value,
+                property.property_type.as_str()
+            ));
+        }
+
+        if !property.values.is_empty() && !property.values.iter().any(|v| v == value) {
+            return Some(format!(
+                "'{}' is not one of the allowed values ({})",
+                value,
+                property.values.join(", ")
+            ));
+        }
+        None
+    }
+
+    /// Checks every row of a metadata file in the format read by "upload-model-meta" (modelId, name and value)
+    pub fn validate(&self, rows: &[MetadataRow]) -> ListOfMetadataViolations {
+        let mut violations = Vec::new();
+        for (row, item) in rows {
+            let item = match item {
+                Ok(item) => item,
+                Err(reason) => {
+                    violations.push(MetadataViolation {
+                        row: *row,
+                        uuid: None,
+                        name: String::default(),
+                        value: String::default(),
+                        reason: reason.to_owned(),
+                    });
+                    continue;
+                }
+            };
+            if let Some(reason) = self.check(&item.name, &item.value) {
+                violations.push(MetadataViolation {
+                    row: *row,
+                    uuid: Some(item.model_uuid),
+                    name: item.name.to_owned(),
+                    value: item.value.to_owned(),
+                    reason,
+                });
+            }
+        }
+        ListOfMetadataViolations::new(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let schema: MetadataSchema = serde_yaml::from_str(
+            "properties:\n  Material:\n    values: [steel, ABS]\n  Weight:\n    type: number\n  Released:\n    type: date\n",
+        )
+        .unwrap();
+
+        assert_eq!(schema.check("material", "steel"), None);
+        assert_eq!(schema.check("Weight", "1.5"), None);
+        assert_eq!(schema.check("Released", "2024-05-01"), None);
+        assert_eq!(schema.check("Color", ""), None);
+        assert!(schema.check("Material", "Steel").is_some());
+        assert!(schema.check("Weight", "heavy").is_some());
+        assert!(schema.check("Released", "yesterday").is_some());
+        assert!(schema.check("Color", "red").is_some());
+    }
+
+    #[test]
+    fn test_metadata_condition() {
+        let types: MetadataTypes = "cost=number,Released=date".parse().unwrap();
+        let properties: HashMap<String, String> = [
+            ("Cost", "25.50"),
+            ("Released", "2024-05-01"),
+            ("Revision", "10"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        let matches = |expression: &str| {
+            MetadataCondition::parse(expression, &types)
+                .unwrap()
+                .matches(&properties)
+        };
+
+        assert!(matches("cost<100"));
+        assert!(matches("cost=25.5"));
+        assert!(!matches("cost>=100"));
+        assert!(matches("released>2024-01-01"));
+        assert!(matches("Revision>9"));
+        assert!(!matches("Revision=10.0"));
+        assert!(matches("Revision!=9"));
+        assert!(!matches("Weight<1"));
+        assert!(MetadataCondition::parse("<5", &types).is_err());
+        assert!("cost=money".parse::<MetadataTypes>().is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use crate::token::KEYRING_SERVICE;
+use argon2::Argon2;
+use base64::engine::general_purpose;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("I/O error")]
+    InputOutputError(#[from] std::io::Error),
+    #[error("Keyring error")]
+    KeyringError(#[from] keyring::Error),
+    #[error("The encrypted client secret is malformed")]
+    Malformed,
+    #[error("Failed to decrypt the client secret. The key or the passphrase is wrong")]
+    DecryptionFailed,
+    #[error("Failed to encrypt the client secret")]
+    EncryptionFailed,
+    #[error("Failed to derive the key from the passphrase")]
+    KeyDerivationFailed,
+    #[error("The key of the encrypted client secrets is not in the keyring")]
+    MissingKey,
+    #[error("No passphrase available. Provide it via the PCLI_PASSPHRASE environment variable")]
+    MissingPassphrase,
+    #[error("The passphrases do not match")]
+    PassphraseMismatch,
+}
+
+/// The environment variable that may contain the passphrase of the encrypted client secrets
+pub const PASSPHRASE_VARIABLE: &str = "PCLI_PASSPHRASE";
+
+/// Marks an encrypted client secret in the configuration, e.g. "enc:keyring:..."
+const PREFIX: &str = "enc:";
+
+const KEYRING: &str = "keyring";
+const PASSPHRASE: &str = "passphrase";
+
+/// The keyring entry holding the key of the client secrets encrypted with the keyring
+const KEYRING_KEY_ENTRY: &str = "configuration.key";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Where the key that encrypts the client secrets comes from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SecretKey {
+    /// A random key, created on first use and kept in the operating system's keyring
+    Keyring,
+    /// A key derived from the passphrase with Argon2
+    Passphrase(String),
+}
+
+/// Whether the client secret from the configuration is encrypted
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// Encrypts the client secret with ChaCha20-Poly1305. The result names the source of the key, so that it can be
+/// decrypted without further settings
+pub fn encrypt(secret: &str, key: &SecretKey) -> Result<String, SecretError> {
+    let (kind, payload) = match key {
+        SecretKey::Keyring => (KEYRING, seal(&keyring_key(true)?, secret)?),
+        SecretKey::Passphrase(passphrase) => {
+            let mut salt = [0u8; SALT_LENGTH];
+            rand::thread_rng().fill_bytes(&mut salt);
+            let mut payload = salt.to_vec();
+            payload.extend(seal(&passphrase_key(passphrase, &salt)?, secret)?);
+            (PASSPHRASE, payload)
+        }
+    };
+    Ok(format!(
+        "{}{}:{}",
+        PREFIX,
+        kind,
+        general_purpose::STANDARD.encode(payload)
+    ))
+}
+
+/// Decrypts a client secret encrypted by [`encrypt`]. The passphrase is read from PCLI_PASSPHRASE or, on a
+/// terminal, from the console
+pub fn decrypt(value: &str) -> Result<String, SecretError> {
+    decrypt_with(value, || read_passphrase(false))
+}
+
+fn decrypt_with(
+    value: &str,
+    passphrase: impl FnOnce() -> Result<String, SecretError>,
+) -> Result<String, SecretError> {
+    let (kind, payload) = value
+        .strip_prefix(PREFIX)
+        .and_then(|value| value.split_once(':'))
+        .ok_or(SecretError::Malformed)?;
+    let payload = general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|_| SecretError::Malformed)?;
+
+    match kind {
+        KEYRING => open(&keyring_key(false)?, &payload),
+        PASSPHRASE if payload.len() > SALT_LENGTH => {
+            let (salt, payload) = payload.split_at(SALT_LENGTH);
+            open(&passphrase_key(&passphrase()?, salt)?, payload)
+        }
+        _ => Err(SecretError::Malformed),
+    }
+}
+
+/// Reads the passphrase from PCLI_PASSPHRASE or, on a terminal, from the console. A new passphrase is entered twice
+pub fn read_passphrase(confirm: bool) -> Result<String, SecretError> {
+    match std::env::var(PASSPHRASE_VARIABLE) {
+        Ok(passphrase) if !passphrase.is_empty() => return Ok(passphrase),
+        _ => (),
+    }
+    // never block waiting for input when there is no one to enter it (e.g. in a CI pipeline)
+    if !std::io::stdin().is_terminal() {
+        return Err(SecretError::MissingPassphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("Enter passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(SecretError::MissingPassphrase);
+    }
+    if confirm && rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+        return Err(SecretError::PassphraseMismatch);
+    }
+    Ok(passphrase)
+}
+
+/// Encrypts the plaintext client secrets in the configuration file in place and returns how many were encrypted.
+/// Only the lines with the secrets change, so the comments and the layout of the file are kept
+pub fn encrypt_configuration_file(path: &Path, key: &SecretKey) -> Result<usize, SecretError> {
+    let text = fs::read_to_string(path)?;
+    let mut count = 0;
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let secret = trimmed
+            .strip_prefix("client_secret:")
+            .and_then(|value| serde_yaml::from_str::<Option<String>>(value).ok())
+            .flatten()
+            .filter(|secret| !secret.is_empty() && !is_encrypted(secret));
+        match secret {
+            Some(secret) => {
+                let indentation = &line[..line.len() - trimmed.len()];
+                lines.push(format!(
+                    "{}client_secret: \"{}\"",
+                    indentation,
+                    encrypt(&secret, key)?
+                ));
+                count += 1;
+            }
+            None => lines.push(line.to_owned()),
+        }
+    }
+
+    if count > 0 {
+        let mut text = lines.join("\n");
+        text.push('\n');
+        fs::write(path, text)?;
+    }
+    Ok(count)
+}
+
+/// The key kept in the keyring, which is created if requested and missing
+fn keyring_key(create: bool) -> Result<[u8; 32], SecretError> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_KEY_ENTRY)?;
+    let encoded = match entry.get_password() {
+        Ok(encoded) => encoded,
+        Err(keyring::Error::NoEntry) if create => {
+            let mut key = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            let encoded = general_purpose::STANDARD.encode(key);
+            entry.set_password(&encoded)?;
+            encoded
+        }
+        Err(keyring::Error::NoEntry) => return Err(SecretError::MissingKey),
+        Err(e) => return Err(SecretError::from(e)),
+    };
+    general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+        .ok_or(SecretError::MissingKey)
+}
+
+fn passphrase_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], SecretError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|_| SecretError::KeyDerivationFailed)?;
+    Ok(key)
+}
+
+/// Encrypts with a random nonce, which is stored in front of the ciphertext
+fn seal(key: &[u8; 32], secret: &str) -> Result<Vec<u8>, SecretError> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(Nonce::from_slice(&nonce), secret.as_bytes())
+        .map_err(|_| SecretError::EncryptionFailed)?;
+    let mut payload = nonce.to_vec();
+    payload.extend(ciphertext);
+    Ok(payload)
+}
+
+fn open(key: &[u8; 32], payload: &[u8]) -> Result<String, SecretError> {
+    if payload.len() < NONCE_LENGTH {
+        return Err(SecretError::Malformed);
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+    let secret = ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SecretError::DecryptionFailed)?;
+    String::from_utf8(secret).map_err(|_| SecretError::DecryptionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encrypt_with_passphrase() {
+        let key = SecretKey::Passphrase("correct horse battery staple".to_string());
+        let encrypted = encrypt("s3cr3t", &key).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("s3cr3t"));
+
+        let decrypted = decrypt_with(
+            &encrypted,
+            || Ok("correct horse battery staple".to_string()),
+        )
+        .unwrap();
+        assert_eq!(decrypted, "s3cr3t");
+        assert!(matches!(
+            decrypt_with(&encrypted, || Ok("wrong".to_string())),
+            Err(SecretError::DecryptionFailed)
+        ));
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("pcli.conf");
+        fs::write(
+            &path,
+            "tenants:\n  # production\n  mytenant:\n    client_id: abc\n    client_secret: 'def'\n",
+        )
+        .unwrap();
+        assert_eq!(encrypt_configuration_file(&path, &key).unwrap(), 1);
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("# production"));
+        let configuration: crate::configuration::ClientConfiguration =
+            serde_yaml::from_str(&format!("base_path: x\nidentity_provider_url: y\n{}", text))
+                .unwrap();
+        let secret = configuration.tenants["mytenant"]
+            .client_secret
+            .clone()
+            .unwrap();
+        assert_eq!(
+            decrypt_with(&secret, || Ok("correct horse battery staple".to_string())).unwrap(),
+            "def"
+        );
+        // secrets that are already encrypted are left alone
+        assert_eq!(encrypt_configuration_file(&path, &key).unwrap(), 0);
+    }
+}
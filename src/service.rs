@@ -1,26 +1,48 @@
-use crate::client::{ApiClient, AssemblyTree, ClientError};
-use crate::format::{format_list_of_matched_properties, Format};
+use crate::cache::{self, Cache};
+use crate::client::{
+    build_http_client, ApiClient, AssemblyTree, ClientError, Conditional, ConnectionStatistics,
+    CurlCommands, PhysnaClient, RequestFailure, TokenRefresher, UploadProgress,
+};
+use crate::configuration::{ClientConfiguration, HttpTimeouts, ProxyConfiguration};
+use crate::deadline::{Deadline, NOT_STARTED};
+use crate::metadata_file::{self, MetadataColumns, MetadataRow};
 use crate::model::{
-    EnvironmentStatusReport, FlatBom, Folder, ListOfFolders, ListOfMatchedMetadataItems,
-    ListOfModelMatches, ListOfModels, ListOfUsers, ListOfVisualModelMatches, MatchedMetadataItem,
-    Model, ModelAssemblyTree, ModelMatch, ModelMatchReport, ModelMatchReportItem, ModelMetadata,
-    ModelMetadataItem, ModelMetadataItemShort, ModelStatusRecord, PartNodeDictionaryItem, Property,
-    PropertyCollection, SimpleDuplicatesMatchReport, VisuallyMatchedModel,
+    is_folder_pattern, AssemblyCoverage, BillOfMaterials, CleanupItem, CleanupKind,
+    DuplicateUpload, EnvironmentStatusReport, FlatBom, Folder, FolderCounts, FoundModel,
+    ListOfAssemblyCoverage, ListOfCleanupItems, ListOfDuplicateUploads, ListOfFolderCounts,
+    ListOfFolders, ListOfFoundModels, ListOfMatchedMetadataItems, ListOfMetadataImportResults,
+    ListOfModelDownloads, ListOfModelMatches, ListOfModels, ListOfPropertyMerges,
+    ListOfReprocessOutcomes, ListOfReuseItems, ListOfUsers, ListOfVisualModelMatches,
+    MatchedMetadataItem, MergeConflictPolicy, MetadataImportMode, MetadataImportResult,
+    MetadataImportStatus, Model, ModelAssemblyTree, ModelDownload, ModelMatch, ModelMatchReport,
+    ModelMatchReportItem, ModelMetadata, ModelMetadataItem, ModelStatusRecord,
+    PartNodeDictionaryItem, ProcessingOutcome, Property, PropertyCollection, PropertyMerge,
+    PropertyMergeAction, ReprocessOutcome, ReuseItem, Rollup, RollupItem,
+    SimpleDuplicatesMatchReport, StatusGrouping, ToCsv, ToJson, TransferReport,
+    VisuallyMatchedModel,
 };
+use crate::recording::Recording;
+use crate::schema::{MetadataCondition, MetadataTypes, PropertyType};
+use crate::storage;
+use crate::token;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
 use log::debug;
-use log::{error, trace, warn};
+use log::{trace, warn};
 use petgraph::matrix_graph::MatrixGraph;
 use petgraph::matrix_graph::NodeIndex;
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::{Cell, RefCell};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::fs::File;
+use std::future::Future;
 use std::hash::{Hash, Hasher};
-use std::io::{Seek, SeekFrom, Write};
-use std::path::PathBuf;
-use std::rc::Rc;
-use tempfile::tempfile;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
+use tokio::runtime::Runtime;
 use unicase::UniCase;
 use url::Url;
 use uuid::Uuid;
@@ -31,6 +53,10 @@ pub enum ApiError {
     ClientError(#[from] ClientError),
     #[error("Folder not found '{0}'")]
     FolderNotFound(String),
+    #[error("Property not found '{0}'")]
+    PropertyNotFound(String),
+    #[error("No model has the short ID {0}")]
+    ShortIdNotFound(u64),
     #[error("I/O error")]
     InputOutputError(#[from] std::io::Error),
     #[error("CSV error")]
@@ -38,28 +64,488 @@ pub enum ApiError {
     #[error("Failed to read data: {0}")]
     FailedToRead(String),
     #[error("Data format error: {0}")]
-    FormatError(#[from] crate::format::FormatError),
+    FormatError(Box<crate::format::FormatError>),
+    #[error("JSON error")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Parsing error")]
+    ParsingError(Box<crate::model::ParsingError>),
+    #[error("Invalid UUID")]
+    UuidError(#[from] uuid::Error),
+    #[error("Invalid URL")]
+    UrlError(#[from] url::ParseError),
+    #[error("Token error: {0}")]
+    TokenError(#[from] token::TokenError),
+    #[error("The {0} is not configured")]
+    MissingSetting(&'static str),
+    #[error("{0}")]
+    StorageError(#[from] crate::storage::StorageError),
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("The maximum duration of the run was exceeded")]
+    DeadlineExceeded,
+}
+
+// the formatting and parsing errors are boxed, so that they do not make every result of the API large
+
+impl From<crate::format::FormatError> for ApiError {
+    fn from(error: crate::format::FormatError) -> Self {
+        ApiError::FormatError(Box::new(error))
+    }
+}
+
+impl From<crate::model::ParsingError> for ApiError {
+    fn from(error: crate::model::ParsingError) -> Self {
+        ApiError::ParsingError(Box::new(error))
+    }
+}
+
+/// The name of the manifest that "download-many" writes next to the files
+pub const DOWNLOAD_MANIFEST: &str = "manifest.csv";
+
+/// How many models are requested per page when listing models, unless configured otherwise
+pub const DEFAULT_MODELS_PAGE_SIZE: u32 = 200;
+
+/// How often the state of models being processed is checked, unless specified otherwise
+pub const PROCESSING_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Configures and creates an [`Api`]. The base URL, the tenant and an access token (or the PCLI configuration, which
+/// provides them) are required; everything else has defaults
+#[derive(Debug, Default)]
+pub struct ApiBuilder {
+    base_url: Option<String>,
+    tenant: Option<String>,
+    access_token: Option<String>,
+    token_provider: Option<TokenRefresher>,
+    timeouts: HttpTimeouts,
+    max_retries: Option<u32>,
+    concurrency: Option<usize>,
+    page_size: Option<u32>,
+    folders_page_size: Option<u32>,
+    metadata_page_size: Option<u32>,
+    proxy: Option<ProxyConfiguration>,
+    http_client: Option<reqwest::Client>,
+    recording: Option<Recording>,
+    upload_chunk_size: Option<usize>,
+    upload_progress: Option<UploadProgress>,
+    web_url: Option<String>,
+    curl_commands: Option<CurlCommands>,
+    deadline: Deadline,
+}
+
+impl ApiBuilder {
+    /// Takes the base URL, the HTTP settings of the tenant (proxy, page sizes, timeouts, retries and jobs) and a token
+    /// provider from the PCLI configuration. The access token itself is not read until it is needed
+    pub fn configuration(mut self, configuration: &ClientConfiguration, tenant: &str) -> Self {
+        let tenant_configuration = configuration.tenants.get(tenant);
+        let refresh = configuration.clone();
+        let name = tenant.to_owned();
+
+        self.base_url = Some(configuration.base_path.to_owned());
+        self.tenant = Some(tenant.to_owned());
+        self.token_provider = Some(TokenRefresher::new(move || {
+            token::invalidate_token(&refresh, &name)?;
+            token::get_token_for_tenant(&refresh, &name)
+        }));
+        self.timeouts = configuration.http_timeouts;
+        self.max_retries = Some(configuration.max_retries);
+        self.concurrency = configuration.jobs;
+        self.page_size = tenant_configuration.and_then(|t| t.page_size);
+        self.folders_page_size = tenant_configuration.and_then(|t| t.folders_page_size);
+        self.metadata_page_size = tenant_configuration.and_then(|t| t.metadata_page_size);
+        self.proxy = tenant_configuration.and_then(|t| t.proxy.to_owned());
+        self.web_url = tenant_configuration.and_then(|t| t.web_url.to_owned());
+        self.upload_chunk_size = configuration
+            .upload_chunk_size
+            .map(|size| (size as usize).saturating_mul(1024 * 1024));
+        self
+    }
+
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_owned());
+        self
+    }
+
+    pub fn tenant(mut self, tenant: &str) -> Self {
+        self.tenant = Some(tenant.to_owned());
+        self
+    }
+
+    /// The access token used for the first requests
+    pub fn access_token(mut self, access_token: &str) -> Self {
+        self.access_token = Some(access_token.to_owned());
+        self
+    }
+
+    pub fn timeouts(mut self, timeouts: HttpTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// How many times a request that failed with a transient error is retried
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// How many requests may be in flight at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// The number of records requested per page when listing models or matches
+    pub fn page_size(mut self, page_size: Option<u32>) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// The number of folders requested per page
+    pub fn folders_page_size(mut self, page_size: Option<u32>) -> Self {
+        self.folders_page_size = page_size;
+        self
+    }
+
+    /// The number of metadata properties requested for a model. Only the first page is read
+    pub fn metadata_page_size(mut self, page_size: Option<u32>) -> Self {
+        self.metadata_page_size = page_size;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Option<ProxyConfiguration>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Sends the requests with this HTTP client instead of one created by the `Api`. The proxy setting is then
+    /// ignored, because the client already defines how it connects
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Records the HTTP traffic to disk, or replays a previous recording instead of contacting the server
+    pub(crate) fn recording(mut self, recording: Option<Recording>) -> Self {
+        self.recording = recording;
+        self
+    }
+
+    /// The size in bytes of the chunks in which files are sent when the storage supports resumable uploads. It is
+    /// rounded up to a multiple of 256 KiB
+    pub fn upload_chunk_size(mut self, upload_chunk_size: usize) -> Self {
+        self.upload_chunk_size = Some(upload_chunk_size);
+        self
+    }
+
+    /// Reports the progress of file uploads, e.g. to display a progress bar
+    pub(crate) fn upload_progress(mut self, upload_progress: UploadProgress) -> Self {
+        self.upload_progress = Some(upload_progress);
+        self
+    }
+
+    /// Passes an equivalent curl command for every request sent to the function, e.g. to reproduce an issue without
+    /// PCLI. The access token in the command is replaced by a reference to an environment variable
+    pub fn curl_commands(mut self, curl_commands: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.curl_commands = Some(CurlCommands::new(curl_commands));
+        self
+    }
+
+    /// The base URL of the Physna web application, used for links to models and comparisons. By default it is
+    /// "https://<tenant>.physna.com"
+    pub fn web_url(mut self, web_url: Option<String>) -> Self {
+        self.web_url = web_url;
+        self
+    }
+
+    /// When the run must end. The operations that work through many files or models stop starting new ones at the
+    /// deadline and report the rest as not started
+    pub(crate) fn deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub fn build(self) -> Result<Api, ApiError> {
+        let base_url = self.base_url.ok_or(ApiError::MissingSetting("base URL"))?;
+        let tenant = self.tenant.ok_or(ApiError::MissingSetting("tenant"))?;
+        let access_token = match (self.access_token, &self.token_provider) {
+            (Some(access_token), _) => access_token,
+            (None, Some(token_provider)) => token_provider.token()?,
+            (None, None) => return Err(ApiError::MissingSetting("access token")),
+        };
+
+        let http_client = match (self.http_client, &self.proxy) {
+            (Some(http_client), _) => http_client,
+            (None, Some(proxy)) => {
+                build_http_client(Some(proxy.to_proxy().map_err(ClientError::from)?))
+                    .map_err(ClientError::from)?
+            }
+            (None, None) => build_http_client(None).map_err(ClientError::from)?,
+        };
+
+        let mut client = ApiClient::connect(http_client, &base_url, &tenant, &access_token);
+        client.set_timeouts(self.timeouts);
+        if let Some(max_retries) = self.max_retries {
+            client.set_max_retries(max_retries);
+        }
+        if let Some(concurrency) = self.concurrency {
+            client.set_concurrency(concurrency);
+        }
+        if let Some(token_provider) = self.token_provider {
+            client.set_token_refresher(token_provider);
+        }
+        if let Some(recording) = self.recording {
+            client.set_recording(recording);
+        }
+        if let Some(upload_chunk_size) = self.upload_chunk_size {
+            client.set_upload_chunk_size(upload_chunk_size);
+        }
+        if let Some(upload_progress) = self.upload_progress {
+            client.set_upload_progress(upload_progress);
+        }
+        if let Some(page_size) = self.folders_page_size {
+            client.set_folders_page_size(page_size);
+        }
+        if let Some(page_size) = self.metadata_page_size {
+            client.set_metadata_page_size(page_size);
+        }
+        if let Some(curl_commands) = self.curl_commands {
+            client.set_curl_commands(curl_commands);
+        }
+
+        let mut api = Api::with_client(Box::new(client), self.page_size)?;
+        api.web_url = self.web_url;
+        api.deadline = self.deadline;
+        Ok(api)
+    }
+}
+
+/// A change to the metadata of a model, sent by an import: setting a property or deleting it
+enum MetadataChange {
+    Set(Uuid, u64, ModelMetadataItem),
+    Delete(Uuid, u64),
+}
+
+/// Reads a field of a record in a snapshot file. A row with fewer fields than expected is reported with its line number
+fn snapshot_field<'a>(
+    path: &Path,
+    record: &'a csv::StringRecord,
+    index: usize,
+) -> Result<&'a str, ApiError> {
+    record.get(index).ok_or_else(|| {
+        ApiError::FailedToRead(format!(
+            "{}, line {}: expected at least {} fields, found {}",
+            path.to_string_lossy(),
+            record
+                .position()
+                .map(|position| position.line())
+                .unwrap_or_default(),
+            index + 1,
+            record.len()
+        ))
+    })
+}
+
+/// Selects the matches from which the metadata of a model is inferred
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InferenceOptions {
+    /// The match threshold, e.g. 0.8
+    pub threshold: f64,
+    /// Matches with a lower score do not contribute values
+    pub min_confidence: Option<f64>,
+    /// Only these properties are inferred. If None, all properties are inferred
+    pub keys: Option<Vec<String>>,
+    /// Only matches in these folders contribute values. If None, matches in all folders do
+    pub folders: Option<HashSet<String>>,
 }
 
+/// Access to the Physna API of one tenant. The methods block until the operation completes; requests that can be
+/// sent independently are still executed concurrently. Failures are returned as errors, never printed
 pub struct Api {
     model_cache: HashMap<Uuid, Model>,
-    client: Box<ApiClient>,
+    client: Box<dyn PhysnaClient>,
+    /// Drives the asynchronous HTTP client. Independent requests are executed concurrently on it,
+    /// bounded by the client's concurrency limit
+    runtime: Runtime,
+    cache: Option<Cache>,
+    /// The complete list of folders, once read during this run
+    folders: RefCell<Option<ListOfFolders>>,
+    /// Overrides the number of records requested per page when listing models or matches
+    page_size: Option<u32>,
+    /// Overrides the base URL of the web application
+    web_url: Option<String>,
+    deadline: Deadline,
 }
 
 impl Api {
-    pub fn new(base_url: String, tenant: String, access_token: String) -> Api {
-        Api {
+    /// Starts configuring an `Api`
+    pub fn builder() -> ApiBuilder {
+        ApiBuilder::default()
+    }
+
+    /// Creates an `Api` on top of any implementation of the Physna API operations
+    pub(crate) fn with_client(
+        client: Box<dyn PhysnaClient>,
+        page_size: Option<u32>,
+    ) -> Result<Api, ApiError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Api {
             model_cache: HashMap::new(),
-            client: Box::new(ApiClient::connect(
-                &base_url.to_owned(),
-                &tenant.to_owned(),
-                &access_token.to_owned(),
-            )),
+            client,
+            runtime,
+            cache: None,
+            folders: RefCell::new(None),
+            page_size,
+            web_url: None,
+            deadline: Deadline::default(),
+        })
+    }
+
+    /// Sets the number of records requested per page when listing models or matches
+    pub fn set_page_size(&mut self, page_size: Option<u32>) {
+        self.page_size = page_size;
+    }
+
+    /// Returns the configured page size, or the given default for the endpoint
+    fn page_size(&self, default: u32) -> u32 {
+        self.page_size.unwrap_or(default)
+    }
+
+    /// Enables the on-disk cache of models, folders and metadata
+    pub fn enable_cache(&mut self, cache: Cache) {
+        self.cache = Some(cache);
+    }
+
+    /// Reads a record from the cache, if enabled
+    fn cached<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Option<T> {
+        self.cache
+            .as_ref()
+            .and_then(|cache| cache.get(self.client.tenant(), kind, key))
+    }
+
+    /// Stores a record in the cache, if enabled. Failing to do so is not an error
+    fn store<T: Serialize>(&self, kind: &str, key: &str, value: &T) {
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(self.client.tenant(), kind, key, value) {
+                warn!("Failed to update the cache: {}", e);
+            }
+        }
+    }
+
+    /// Reads a record from the API, unless the expired copy in the cache is still current. The API confirms that with
+    /// 304 Not Modified when the ETag of the copy is sent with the request. The record is cached with its new ETag
+    fn revalidate<T, F, R>(&self, kind: &str, key: &str, request: F) -> Result<T, ApiError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(Option<String>) -> R,
+        R: Future<Output = Result<Conditional<T>, ClientError>>,
+    {
+        let tenant = self.client.tenant();
+        let (cached, etag) = match self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get_tagged::<T>(tenant, kind, key))
+        {
+            Some((cached, etag)) => (Some(cached), Some(etag)),
+            None => (None, None),
+        };
+
+        match (self.runtime.block_on(request(etag))?, cached) {
+            (Conditional::Modified { value, etag }, _) => {
+                if let Some(cache) = &self.cache {
+                    if let Err(e) = cache.put_tagged(tenant, kind, key, &value, etag.as_deref()) {
+                        warn!("Failed to update the cache: {}", e);
+                    }
+                }
+                Ok(value)
+            }
+            (Conditional::NotModified, Some(cached)) => {
+                trace!("The cached {} {} is still current", kind, key);
+                if let Some(cache) = &self.cache {
+                    if let Err(e) = cache.touch(tenant, kind, key) {
+                        warn!("Failed to update the cache: {}", e);
+                    }
+                }
+                Ok(cached)
+            }
+            (response, None) => Ok(response.into_modified()?),
+        }
+    }
+
+    /// Drops the cached list of folders, as well as the model listings that depend on it, after a folder was changed
+    fn forget_folders(&self) {
+        self.folders.replace(None);
+        if let Some(cache) = &self.cache {
+            for kind in [cache::FOLDERS, cache::MODELS] {
+                if let Err(e) = cache.invalidate(self.client.tenant(), kind, None) {
+                    warn!("Failed to update the cache: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Drops the cached model listings after a model was added to or removed from a folder
+    fn forget_model_listings(&self) {
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.invalidate(self.client.tenant(), cache::MODELS, None) {
+                warn!("Failed to update the cache: {}", e);
+            }
+        }
+    }
+
+    /// Drops the cached records of a model after it was changed
+    fn forget_model(&self, uuid: &Uuid) {
+        if let Some(cache) = &self.cache {
+            for kind in [cache::MODEL, cache::METADATA] {
+                let key = uuid.to_string();
+                if let Err(e) = cache.invalidate(self.client.tenant(), kind, Some(&key)) {
+                    warn!("Failed to update the cache: {}", e);
+                }
+            }
         }
     }
 
+    /// Returns how many HTTP requests were sent so far and how many connections were opened for them
+    pub fn statistics(&self) -> ConnectionStatistics {
+        self.client.statistics()
+    }
+
+    /// Returns the last HTTP request that failed, if any
+    pub fn last_failure(&self) -> Option<RequestFailure> {
+        self.client.last_failure()
+    }
+
     pub fn tenant(&self) -> String {
-        self.client.tenant.to_owned()
+        self.client.tenant().to_owned()
+    }
+
+    /// When the run must end
+    pub fn deadline(&self) -> Deadline {
+        self.deadline
+    }
+
+    /// The base URL of the Physna web application of the tenant, without a trailing slash
+    fn web_url(&self) -> String {
+        match &self.web_url {
+            Some(web_url) => web_url.trim_end_matches('/').to_owned(),
+            None => format!("https://{}.physna.com", self.client.tenant()),
+        }
+    }
+
+    /// The address of the model in the Physna web application of the tenant
+    pub fn model_url(&self, uuid: &Uuid) -> String {
+        format!("{}/app/models/{}", self.web_url(), uuid)
+    }
+
+    /// The address of the side-by-side comparison of two models in the Physna web application of the tenant
+    pub fn comparison_url(&self, uuid: &Uuid, other: &Uuid) -> String {
+        format!(
+            "{}/app/compare?modelAId={}&modelBId={}",
+            self.web_url(),
+            uuid,
+            other
+        )
     }
 
     pub fn get_list_of_folders(
@@ -67,19 +553,121 @@ impl Api {
         desired_folders: Option<HashSet<String>>,
     ) -> Result<ListOfFolders, ApiError> {
         log::trace!("Listing folders...");
-        let list = self.client.get_list_of_folders(desired_folders)?;
+        if let Some(desired) = &desired_folders {
+            if desired.iter().any(|name| is_folder_pattern(name)) {
+                let folders = self
+                    .get_list_of_folders(None)?
+                    .into_iter()
+                    .filter(|f| desired.iter().any(|name| f.matches(name)))
+                    .collect::<Vec<_>>();
+                return Ok(ListOfFolders::from(folders));
+            }
+        }
+
+        let cached = match self.folders.borrow().as_ref() {
+            Some(list) => Some(list.folders.clone()),
+            None => self.cached::<Vec<Folder>>(cache::FOLDERS, "all"),
+        };
+        if let Some(folders) = cached {
+            if self.folders.borrow().is_none() {
+                self.folders
+                    .replace(Some(ListOfFolders::from(folders.clone())));
+            }
+            let folders = folders
+                .into_iter()
+                .filter(|f| {
+                    desired_folders
+                        .as_ref()
+                        .is_none_or(|desired| desired.contains(&f.name))
+                })
+                .collect::<Vec<_>>();
+            return Ok(ListOfFolders::from(folders));
+        }
+
+        if desired_folders.is_none() {
+            let folders = self.revalidate(cache::FOLDERS, "all", |etag| async move {
+                Ok(self
+                    .client
+                    .get_list_of_folders_conditional(etag)
+                    .await?
+                    .map(|list| ListOfFolders::from(list).folders))
+            })?;
+            let list = ListOfFolders::from(folders);
+            self.folders.replace(Some(list.clone()));
+            return Ok(list);
+        }
+
+        let list = self
+            .runtime
+            .block_on(self.client.get_list_of_folders(desired_folders))?;
         Ok(ListOfFolders::from(list))
     }
 
-    pub fn get_list_of_users(&self) -> Result<ListOfUsers, ApiError> {
+    /// Returns the list of folders with the number of models in each of them
+    ///
+    /// Parameters:
+    ///
+    /// desired_folders - list of folder names to be included. If none, all folders are included
+    /// by_state - if true, the models are also counted by state. This requires listing all models in the folders
+    pub fn get_list_of_folder_counts(
+        &self,
+        desired_folders: Option<HashSet<String>>,
+        by_state: bool,
+    ) -> Result<ListOfFolderCounts, ApiError> {
+        log::trace!("Counting models in folders...");
+        let folders = self.get_list_of_folders(desired_folders)?;
+
+        let pages = self
+            .runtime
+            .block_on(join_all(folders.folders.iter().map(|folder| {
+                self.client
+                    .get_list_of_models_page(Some(HashSet::from([folder.id])), None, 1, 1)
+            })));
+
+        let mut counts: Vec<FolderCounts> = Vec::new();
+        for (folder, page) in folders.folders.iter().zip(pages) {
+            counts.push(FolderCounts::new(folder.to_owned(), page?.page_data.total));
+        }
+
+        if by_state && !counts.is_empty() {
+            let names: HashSet<String> = counts.iter().map(|f| f.name.to_owned()).collect();
+            let models = self.list_all_models(Some(names), None)?;
+            for model in models.models {
+                if let Some(folder) = counts.iter_mut().find(|f| f.id == model.folder_id) {
+                    *folder.states.entry(model.state.to_uppercase()).or_default() += 1;
+                }
+            }
+        }
+
+        Ok(ListOfFolderCounts::from(counts))
+    }
+
+    /// Returns the list of users in the tenant
+    ///
+    /// Parameters:
+    ///
+    /// search - optional text to be found in the user's name, email, external ID or department
+    /// role - optional role the users must have
+    pub fn get_list_of_users(
+        &self,
+        search: Option<&String>,
+        role: Option<&String>,
+    ) -> Result<ListOfUsers, ApiError> {
         log::trace!("Listing users...");
-        let list = self.client.get_list_of_users()?;
-        Ok(list)
+        let list = self.runtime.block_on(self.client.get_list_of_users())?;
+        let users = list
+            .users
+            .into_iter()
+            .filter(|u| search.is_none_or(|s| u.matches(s)))
+            .filter(|u| role.is_none_or(|r| u.has_role(r)))
+            .collect::<Vec<_>>();
+        Ok(ListOfUsers::from(users))
     }
 
     pub fn create_folder(&self, name: &String) -> Result<Folder, ApiError> {
         log::trace!("Creating folder {}...", name);
-        let folder = self.client.create_folder(name)?;
+        let folder = self.runtime.block_on(self.client.create_folder(name))?;
+        self.forget_folders();
         Ok(Folder::from(folder))
     }
 
@@ -94,22 +682,153 @@ impl Api {
         let folders = self.get_list_of_folders(Some(folders))?;
         let folder_ids: HashSet<u32> = folders.into_iter().map(|f| f.id).collect();
 
-        if folder_ids.len() > 0 {
-            self.client.delete_folder(&folder_ids)?;
+        if !folder_ids.is_empty() {
+            self.runtime
+                .block_on(self.client.delete_folder(&folder_ids))?;
+            self.forget_folders();
             Ok(())
         } else {
             Err(ApiError::FolderNotFound(folder_names))
         }
     }
 
+    pub fn rename_folder(&self, name: &String, new_name: &String) -> Result<Folder, ApiError> {
+        log::trace!("Renaming folder {} to {}...", name, new_name);
+        let folders = self.get_list_of_folders(Some(HashSet::from([name.to_owned()])))?;
+        let folder = match folders.into_iter().find(|f| &f.name == name) {
+            Some(folder) => folder,
+            None => return Err(ApiError::FolderNotFound(name.to_owned())),
+        };
+
+        let folder = self
+            .runtime
+            .block_on(self.client.rename_folder(&folder.id, new_name))?;
+        self.forget_folders();
+        Ok(Folder::from(folder))
+    }
+
+    /// Moves all models from the source folders into the target folder and deletes the emptied source folders
+    ///
+    /// The API cannot move a model between folders, therefore each model is copied with its metadata (see
+    /// copy_model_to_folder). An original is deleted only once its copy has been processed successfully; otherwise,
+    /// both are kept and the model is reported as failed. Source folders are deleted only if all of their models were
+    /// moved. Assemblies are refused, because their copies would lose the links to their parts.
+    ///
+    /// Parameters:
+    ///
+    /// from - the names of the source folders
+    /// into - the name of the target folder. It is created if it does not exist
+    /// timeout - how long to wait for the copies to be processed
+    pub fn merge_folders(
+        &mut self,
+        from: &HashSet<String>,
+        into: &String,
+        timeout: Duration,
+    ) -> Result<TransferReport, ApiError> {
+        let existing_folders = self.get_list_of_folders(None)?;
+        let from: HashSet<String> = self
+            .validate_folders(&existing_folders, from)?
+            .into_iter()
+            .map(|f| f.name)
+            .filter(|name| name != into)
+            .collect();
+
+        let mut report = TransferReport::new();
+        if from.is_empty() {
+            return Ok(report);
+        }
+
+        let models = self.list_all_models(Some(from.clone()), None)?;
+        if let Some(assembly) = models.models.iter().find(|model| model.is_assembly) {
+            return Err(ApiError::Unsupported(format!(
+                "The folders cannot be merged, because they contain assemblies (e.g. {}), which would lose the links to their parts",
+                assembly.name
+            )));
+        }
+
+        if existing_folders.into_iter().all(|f| &f.name != into) {
+            self.create_folder(into)?;
+        }
+
+        // maps the copy to the original model
+        let mut originals: HashMap<Uuid, Model> = HashMap::new();
+        for model in models.models {
+            // the originals of the models left once the run is out of time stay where they are
+            if self.deadline.exceeded() {
+                report.failed.push((model, String::from(NOT_STARTED)));
+                continue;
+            }
+            trace!("Copying model {} to folder {}...", model.uuid, into);
+            match self.copy_model_to_folder(&model.uuid, into) {
+                Ok(copy) => {
+                    originals.insert(copy.uuid, model);
+                }
+                Err(e) => report.failed.push((model, e.to_string())),
+            }
+        }
+
+        let copies: Vec<Uuid> = originals.keys().copied().collect();
+        for copy in self.wait_for_models(&copies, timeout, PROCESSING_POLL_INTERVAL)? {
+            let model = match originals.remove(&copy.uuid) {
+                Some(model) => model,
+                None => continue,
+            };
+            if !copy.is_healthy() {
+                let reason = format!(
+                    "its copy {} is in state {}, therefore the original was kept",
+                    copy.uuid, copy.state
+                );
+                report.failed.push((model, reason));
+                continue;
+            }
+            trace!(
+                "Deleting model {}, which was moved to {}...",
+                model.uuid,
+                copy.uuid
+            );
+            match self.delete_model(&model.uuid) {
+                Ok(()) => report.succeeded.push(copy),
+                Err(e) => report.failed.push((model, e.to_string())),
+            }
+        }
+
+        if report.failed.is_empty() {
+            self.delete_folder(from)?;
+        } else {
+            warn!("Some models could not be moved. The source folders were not deleted");
+        }
+
+        Ok(report)
+    }
+
     pub fn get_model_metadata(&self, uuid: &Uuid) -> Result<Option<ModelMetadata>, ApiError> {
+        self.read_model_metadata(uuid, true)
+    }
+
+    /// Reads the metadata of a model. Without using the cache, the cached copy is only used once the API confirms it
+    /// is current
+    fn read_model_metadata(
+        &self,
+        uuid: &Uuid,
+        use_cache: bool,
+    ) -> Result<Option<ModelMetadata>, ApiError> {
         log::trace!("Reading model metadata for {}...", uuid.to_string());
-        Ok(self.client.get_model_metadata(uuid)?)
+        if use_cache {
+            if let Some(metadata) = self.cached(cache::METADATA, &uuid.to_string()) {
+                return Ok(metadata);
+            }
+        }
+
+        self.revalidate(cache::METADATA, &uuid.to_string(), |etag| {
+            self.client.get_model_metadata_conditional(uuid, etag)
+        })
     }
 
     pub fn delete_model_metadata_property(&self, uuid: &Uuid, id: &u64) -> Result<(), ApiError> {
         log::trace!("Deleting model metadata property...");
-        self.client.delete_model_property(uuid, id)?;
+        self.runtime
+            .block_on(self.client.delete_model_property(uuid, id))?;
+        self.forget_model(uuid);
         Ok(())
     }
 
@@ -126,19 +845,34 @@ impl Api {
                 return Ok(model.clone());
             }
         }
-        let model = self.client.get_model(uuid)?;
-        let mut model = Model::from(model);
+        let cached = if use_cache {
+            self.cached::<Model>(cache::MODEL, &uuid.to_string())
+        } else {
+            None
+        };
+        let mut model = match cached {
+            Some(model) => model,
+            // even without using the cache, the cached copy can be used once the API confirms it is current
+            None => {
+                let client = &self.client;
+                self.revalidate(cache::MODEL, &uuid.to_string(), |etag| async move {
+                    Ok(client
+                        .get_model_conditional(uuid, etag)
+                        .await?
+                        .map(Model::from))
+                })?
+            }
+        };
 
         if meta {
-            let metadata = self.get_model_metadata(uuid);
-            match metadata {
-                Ok(metadata) => match metadata {
+            let metadata = self.read_model_metadata(uuid, use_cache);
+            if let Ok(metadata) = metadata {
+                match metadata {
                     Some(metadata) => {
                         model.metadata = Some(metadata.properties.to_owned());
                     }
                     None => model.metadata = None,
-                },
-                Err(_) => (),
+                }
             }
         }
 
@@ -149,115 +883,545 @@ impl Api {
 
     pub fn reprocess_model(&self, uuid: &Uuid) -> Result<(), ApiError> {
         trace!("Reprocessing {}...", uuid.to_string());
-        self.client.reprocess_model(uuid)?;
+        self.runtime.block_on(self.client.reprocess_model(uuid))?;
+        self.forget_model(uuid);
+        self.forget_model_listings();
         Ok(())
     }
 
+    /// Reprocesses the models and waits until the processing ends or the timeout expires
+    ///
+    /// Physna does not change the state of a model as soon as it is asked to reprocess it, therefore the state is
+    /// first checked after one interval.
+    ///
+    /// Parameters:
+    ///
+    /// uuids - the model UUIDs
+    /// timeout - how long to wait for the models to be processed
+    /// interval - how often to check the state of the models
+    ///
+    /// Returns the outcome for each model
+    pub fn reprocess_models_and_wait(
+        &mut self,
+        uuids: &[Uuid],
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<ListOfReprocessOutcomes, ApiError> {
+        let mut previous_states = Vec::new();
+        for uuid in uuids {
+            previous_states.push(self.get_model(uuid, false, false)?.state);
+            self.reprocess_model(uuid)?;
+        }
+
+        let started = std::time::Instant::now();
+        std::thread::sleep(interval.min(timeout));
+        let models =
+            self.wait_for_models(uuids, timeout.saturating_sub(started.elapsed()), interval)?;
+
+        Ok(ListOfReprocessOutcomes::new(
+            models
+                .into_iter()
+                .zip(previous_states)
+                .map(|(model, previous_state)| ReprocessOutcome {
+                    outcome: ProcessingOutcome::of(&model),
+                    uuid: model.uuid,
+                    name: model.name,
+                    previous_state,
+                    state: model.state,
+                })
+                .collect(),
+        ))
+    }
+
     pub fn delete_model(&self, uuid: &Uuid) -> Result<(), ApiError> {
-        self.client.delete_model(uuid)?;
+        self.runtime.block_on(self.client.delete_model(uuid))?;
+        self.forget_model(uuid);
+        self.forget_model_listings();
         Ok(())
     }
 
-    pub fn get_model_assembly_tree(&mut self, uuid: &Uuid) -> Result<ModelAssemblyTree, ApiError> {
+    /// Reads the assembly tree of the model. The subassemblies below the given depth are not expanded, where the
+    /// direct children of the model are at depth 1. The models of all nodes are read concurrently
+    pub fn get_model_assembly_tree(
+        &mut self,
+        uuid: &Uuid,
+        depth: Option<u32>,
+    ) -> Result<ModelAssemblyTree, ApiError> {
         trace!("Reading assembly tree data for {}...", uuid.to_string());
-        let tree = self.client.get_assembly_tree_for_model(uuid)?;
-        Ok(self.enhance_assembly_tree_with_model(uuid, &tree)?)
+        let tree = self
+            .runtime
+            .block_on(self.client.get_assembly_tree_for_model(uuid))?;
+
+        let mut uuids: HashSet<Uuid> = HashSet::from([uuid.to_owned()]);
+        Self::collect_assembly_tree_uuids(&tree, depth, &mut uuids)?;
+        self.prefetch_models(uuids)?;
+
+        self.enhance_assembly_tree_with_model(uuid, &tree, depth)
+    }
+
+    /// Reads the models that are in neither cache concurrently, so that looking them up one by one afterwards does
+    /// not send a request for each
+    fn prefetch_models(&mut self, uuids: HashSet<Uuid>) -> Result<(), ApiError> {
+        let missing: Vec<Uuid> = uuids
+            .into_iter()
+            .filter(|uuid| {
+                !self.model_cache.contains_key(uuid)
+                    && self
+                        .cached::<Model>(cache::MODEL, &uuid.to_string())
+                        .is_none()
+            })
+            .collect();
+        trace!("Reading {} models...", missing.len());
+
+        let models = self.runtime.block_on(join_all(
+            missing.iter().map(|uuid| self.client.get_model(uuid)),
+        ));
+        for model in models {
+            let model = Model::from(model?);
+            self.store(cache::MODEL, &model.uuid.to_string(), &model);
+            self.model_cache.insert(model.uuid.to_owned(), model);
+        }
+        Ok(())
     }
 
     fn enhance_assembly_tree_with_model(
         &mut self,
         uuid: &Uuid,
         tree: &AssemblyTree,
+        depth: Option<u32>,
     ) -> Result<ModelAssemblyTree, ApiError> {
         trace!("Enhancing model data for {}...", uuid.to_string());
 
         let model = self.get_model(uuid, true, false)?;
-        let assembly_tree = match &tree.children {
-            Some(tree_children) => {
+        let assembly_tree = match (&tree.children, depth) {
+            (_, Some(0)) => ModelAssemblyTree::new(model, None),
+            (Some(tree_children), _) => {
                 let mut assembly_children: Vec<ModelAssemblyTree> = Vec::new();
                 for child in tree_children {
-                    let child_uuid = Uuid::parse_str(&child.uuid.as_str()).unwrap();
-                    assembly_children
-                        .push(self.enhance_assembly_tree_with_model(&child_uuid, child)?);
+                    let child_uuid = Uuid::parse_str(child.uuid.as_str())?;
+                    assembly_children.push(self.enhance_assembly_tree_with_model(
+                        &child_uuid,
+                        child,
+                        depth.map(|depth| depth - 1),
+                    )?);
                 }
                 ModelAssemblyTree::new(model, Some(assembly_children))
             }
-            None => ModelAssemblyTree::new(model, None),
+            (None, _) => ModelAssemblyTree::new(model, None),
         };
 
         Ok(assembly_tree)
     }
 
-    /// Returns a list of models that match the search and filter criteria
-    ///
-    /// Parameters:
-    ///
-    /// folders - list of folder names to be used as a filter. If empty, all folders are included
-    /// search - search text
-    /// meta - if true, the metadata is included in the response
-    pub fn list_all_models(
-        &self,
-        folders: Option<HashSet<String>>,
-        search: Option<&String>,
-    ) -> Result<ListOfModels, ApiError> {
-        trace!("Listing all models...");
+    /// Collects the UUIDs of the children in the tree, down to the given depth
+    fn collect_assembly_tree_uuids(
+        tree: &AssemblyTree,
+        depth: Option<u32>,
+        uuids: &mut HashSet<Uuid>,
+    ) -> Result<(), ApiError> {
+        if depth == Some(0) {
+            return Ok(());
+        }
+        for child in tree.children.iter().flatten() {
+            uuids.insert(Uuid::parse_str(&child.uuid)?);
+            Self::collect_assembly_tree_uuids(child, depth.map(|depth| depth - 1), uuids)?;
+        }
+        Ok(())
+    }
 
-        let folder_ids: Option<HashSet<u32>> = match folders {
-            Some(folders) => {
-                if folders.len() > 0 {
-                    let existing_folders = self.get_list_of_folders(None)?;
+    /// Lists the sub-assemblies and parts of the assembly with their quantities. The values of the given metadata
+    /// properties are included for each component, matching the property names regardless of the case
+    pub fn get_bill_of_materials(
+        &mut self,
+        uuid: &Uuid,
+        properties: &[String],
+        parts_only: bool,
+    ) -> Result<BillOfMaterials, ApiError> {
+        let tree = self.get_model_assembly_tree(uuid, None)?;
+        let mut bom = BillOfMaterials::from(&tree);
+        if parts_only {
+            bom.items.retain(|item| !item.is_assembly);
+        }
+        self.read_bom_properties(&mut bom, properties)?;
+        Ok(bom)
+    }
+
+    /// Reads the values of the metadata properties of every component
+    fn read_bom_properties(
+        &self,
+        bom: &mut BillOfMaterials,
+        properties: &[String],
+    ) -> Result<(), ApiError> {
+        bom.properties = properties.to_vec();
+        let uuids: Vec<Uuid> = bom.items.iter().map(|item| item.uuid).collect();
+        let mut values = self.read_properties(&uuids, properties)?;
+        for item in bom.items.iter_mut() {
+            item.properties = values.remove(&item.uuid).unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Reads the values of the metadata properties of the models concurrently, matching the property names regardless
+    /// of the case. Models without any of the properties are left out
+    fn read_properties(
+        &self,
+        uuids: &[Uuid],
+        properties: &[String],
+    ) -> Result<HashMap<Uuid, BTreeMap<String, String>>, ApiError> {
+        if properties.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let metadata = self.runtime.block_on(self.get_metadata_of_models(uuids))?;
+        Ok(metadata
+            .into_iter()
+            .filter_map(|(uuid, metadata)| {
+                let metadata = metadata?;
+                let values: BTreeMap<String, String> = properties
+                    .iter()
+                    .filter_map(|property| {
+                        metadata
+                            .properties
+                            .iter()
+                            .find(|item| item.name.eq_ignore_ascii_case(property))
+                            .map(|item| (property.to_owned(), item.value.to_owned()))
+                    })
+                    .collect();
+                Some((uuid, values))
+            })
+            .collect())
+    }
+
+    /// Totals the numeric metadata properties of the parts of one unit of the assembly and of each of its
+    /// sub-assemblies. A part counts as often as it occurs in the tree. Values that are missing or not a number are
+    /// left out of the totals, and the part is counted as missing. Properties without a type are read as numbers
+    pub fn rollup(
+        &mut self,
+        uuid: &Uuid,
+        properties: &[String],
+        types: &MetadataTypes,
+    ) -> Result<Rollup, ApiError> {
+        let tree = self.get_model_assembly_tree(uuid, None)?;
+        let bom = BillOfMaterials::from(&tree);
+        let mut uuids: Vec<Uuid> = bom.items.iter().map(|item| item.uuid).collect();
+        uuids.push(tree.model.uuid);
+        let values = self.read_properties(&uuids, properties)?;
+
+        let mut subtotals: HashMap<Uuid, RollupItem> = HashMap::new();
+        let total = Self::rollup_tree(&tree, &values, properties, types, &mut subtotals);
+
+        let mut items = vec![total];
+        for item in bom.items.iter().filter(|item| item.is_assembly) {
+            if let Some(subtotal) = subtotals.get(&item.uuid) {
+                items.push(RollupItem {
+                    quantity: item.quantity,
+                    ..subtotal.to_owned()
+                });
+            }
+        }
+        // hides the rounding errors of adding up decimal values (e.g. 0.30000000000000004)
+        for item in items.iter_mut() {
+            for total in item.totals.values_mut() {
+                *total = (*total * 1e6).round() / 1e6;
+            }
+        }
+        Ok(Rollup {
+            properties: properties.to_vec(),
+            items,
+        })
+    }
+
+    /// Totals one unit of the model in the tree, remembering the totals of every node
+    fn rollup_tree(
+        tree: &ModelAssemblyTree,
+        values: &HashMap<Uuid, BTreeMap<String, String>>,
+        properties: &[String],
+        types: &MetadataTypes,
+        subtotals: &mut HashMap<Uuid, RollupItem>,
+    ) -> RollupItem {
+        let mut subtotal = RollupItem {
+            uuid: tree.model.uuid,
+            name: tree.model.name.to_owned(),
+            quantity: 1,
+            totals: properties.iter().map(|p| (p.to_owned(), 0.0)).collect(),
+            missing: 0,
+        };
+
+        match tree
+            .children
+            .as_ref()
+            .filter(|children| !children.is_empty())
+        {
+            Some(children) => {
+                for child in children {
+                    let child = Self::rollup_tree(child, values, properties, types, subtotals);
+                    for (property, total) in subtotal.totals.iter_mut() {
+                        *total += child.totals.get(property).copied().unwrap_or_default();
+                    }
+                    subtotal.missing += child.missing;
+                }
+            }
+            None => {
+                let part = values.get(&tree.model.uuid);
+                let mut missing = false;
+                for (property, total) in subtotal.totals.iter_mut() {
+                    match part.and_then(|part| part.get(property)).and_then(|value| {
+                        types
+                            .get(property)
+                            .unwrap_or(PropertyType::Number)
+                            .number(value)
+                    }) {
+                        Some(value) => *total = value,
+                        None => missing = true,
+                    }
+                }
+                if missing {
+                    warn!(
+                        "Model {} ({}) has no numeric value for some of the properties",
+                        tree.model.name, tree.model.uuid
+                    );
+                    subtotal.missing = 1;
+                }
+            }
+        }
+
+        subtotals.insert(tree.model.uuid, subtotal.to_owned());
+        subtotal
+    }
+
+    /// The IDs of the folders with the given names, or None for all folders
+    fn folder_ids(
+        &self,
+        folders: Option<HashSet<String>>,
+    ) -> Result<Option<HashSet<u32>>, ApiError> {
+        match folders {
+            Some(folders) => {
+                if !folders.is_empty() {
+                    let existing_folders = self.get_list_of_folders(None)?;
 
                     let folders = self.validate_folders(&existing_folders, &folders)?;
 
                     let folder_ids: HashSet<u32> = folders.into_iter().map(|f| f.id).collect();
-                    Some(folder_ids)
+                    Ok(Some(folder_ids))
                 } else {
-                    None
+                    Ok(None)
                 }
             }
-            None => None,
-        };
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the number of models that match the search in the folders, from the total reported with the first page
+    /// of a single record instead of reading all pages
+    pub fn count_models(
+        &self,
+        folders: Option<HashSet<String>>,
+        search: Option<&String>,
+    ) -> Result<u32, ApiError> {
+        trace!("Counting models...");
+        let folder_ids = self.folder_ids(folders)?;
+        let page = self.runtime.block_on(
+            self.client
+                .get_list_of_models_page(folder_ids, search, 1, 1),
+        )?;
+        Ok(page.page_data.total)
+    }
+
+    /// Returns a list of models that match the search and filter criteria
+    ///
+    /// Parameters:
+    ///
+    /// folders - list of folder names to be used as a filter. If empty, all folders are included
+    /// search - search text
+    /// meta - if true, the metadata is included in the response
+    pub fn list_all_models(
+        &self,
+        folders: Option<HashSet<String>>,
+        search: Option<&String>,
+    ) -> Result<ListOfModels, ApiError> {
+        self.list_models(folders, search, true)
+    }
+
+    /// Lists the models like list_all_models. Without using the cache, the models are always read from the API, e.g.
+    /// when their current state matters. The cache is still updated with the listing
+    fn list_models(
+        &self,
+        folders: Option<HashSet<String>>,
+        search: Option<&String>,
+        use_cache: bool,
+    ) -> Result<ListOfModels, ApiError> {
+        trace!("Listing all models...");
+
+        let folder_ids = self.folder_ids(folders)?;
+
+        let mut key: Vec<String> = folder_ids
+            .iter()
+            .flatten()
+            .map(|id| id.to_string())
+            .collect();
+        key.sort();
+        let key = format!("{}|{}", key.join(","), search.cloned().unwrap_or_default());
+        if use_cache {
+            if let Some(models) = self.cached::<Vec<Model>>(cache::MODELS, &key) {
+                return Ok(ListOfModels::from(models));
+            }
+        }
 
         let all_folders = self.get_list_of_folders(None)?;
 
         let mut list_of_models: Vec<Model> = Vec::new();
 
-        let mut has_more = true;
-        let mut page: u32 = 1;
-        let per_page: u32 = 50;
-        while has_more {
-            let result = self.client.get_list_of_models_page(
-                folder_ids.clone(),
-                search.to_owned(),
-                per_page,
-                page,
-            )?;
+        let per_page = self.page_size(DEFAULT_MODELS_PAGE_SIZE);
+        let first_page = self.runtime.block_on(self.client.get_list_of_models_page(
+            folder_ids.clone(),
+            search.to_owned(),
+            per_page,
+            1,
+        ))?;
+
+        // once the number of pages is known, the remaining ones are read concurrently
+        let other_pages = self.runtime.block_on(join_all(
+            (first_page.page_data.current_page + 1..=first_page.page_data.last_page).map(|page| {
+                self.client.get_list_of_models_page(
+                    folder_ids.clone(),
+                    search.to_owned(),
+                    per_page,
+                    page,
+                )
+            }),
+        ));
+
+        let mut pages = vec![first_page];
+        for page in other_pages {
+            pages.push(page?);
+        }
+
+        for result in pages {
             if result.page_data.total > 0 {
                 let models = result.models;
                 if !models.is_empty() {
                     for m in models {
-                        let mut model = Model::from(m.clone());
+                        let mut model = m.clone();
                         let folder = all_folders.get_folder_by_id(&model.folder_id);
-                        let folder_name = match folder {
-                            Some(folder) => Some(folder.name.to_owned()),
-                            None => None,
-                        };
+                        let folder_name = folder.map(|folder| folder.name.to_owned());
                         model.folder_name = folder_name;
 
                         list_of_models.push(model);
                     }
                 }
             }
-            has_more = result.page_data.current_page < result.page_data.last_page;
-            page = result.page_data.current_page + 1;
         }
 
+        self.store(cache::MODELS, &key, &list_of_models);
         let result = ListOfModels::from(list_of_models);
 
         //trace!("List of Models: {:?}", result);
         Ok(result)
     }
 
+    /// Returns the UUIDs of the models with the short IDs shown in the web application, in the same order. The API
+    /// cannot look a model up by its short ID, so the models of the tenant are listed (or read from the cache)
+    pub fn resolve_short_ids(&self, short_ids: &[u64]) -> Result<Vec<Uuid>, ApiError> {
+        let models = self.list_all_models(None, None)?;
+        let uuids: HashMap<u64, Uuid> = models
+            .models
+            .iter()
+            .filter_map(|model| model.short_id.map(|short_id| (short_id, model.uuid)))
+            .collect();
+        short_ids
+            .iter()
+            .map(|short_id| {
+                uuids
+                    .get(short_id)
+                    .copied()
+                    .ok_or(ApiError::ShortIdNotFound(*short_id))
+            })
+            .collect()
+    }
+
+    /// Searches the whole tenant for models by name and ranks them by how closely their names match the query. The
+    /// query is searched for as a whole and, when it has several words, word by word, so that names with the words
+    /// in another order are found as well
+    ///
+    /// Parameters:
+    ///
+    /// query - the name, or words of the name
+    /// limit - the number of best matches to return
+    pub fn find_models(&self, query: &str, limit: usize) -> Result<ListOfFoundModels, ApiError> {
+        let mut searches = vec![query.trim().to_owned()];
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.len() > 1 {
+            searches.extend(words.into_iter().map(String::from));
+        }
+
+        let mut found: HashMap<Uuid, FoundModel> = HashMap::new();
+        for search in searches.iter() {
+            for model in self.list_all_models(None, Some(search))?.models {
+                found.entry(model.uuid).or_insert_with(|| FoundModel {
+                    uuid: model.uuid,
+                    score: Self::name_similarity(query, &model.name),
+                    name: model.name,
+                    folder: model.folder_name,
+                    state: model.state,
+                });
+            }
+        }
+
+        let mut models: Vec<FoundModel> = found.into_values().collect();
+        models.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        models.truncate(limit);
+        Ok(ListOfFoundModels::new(models))
+    }
+
+    /// Scores a name from 0 to 1 by how closely its words match the words of the query. A word that is in the name
+    /// counts fully, a word that a word of the name starts with or contains a little less, and a misspelled word by
+    /// how similar it is. The similarity of the whole name breaks ties
+    fn name_similarity(query: &str, name: &str) -> f64 {
+        let words = |text: &str| -> Vec<String> {
+            text.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(|word| word.to_lowercase())
+                .collect()
+        };
+        let (terms, name_words) = (words(query), words(name));
+        if terms.is_empty() || name_words.is_empty() {
+            return 0.0;
+        }
+
+        let word_score: f64 = terms
+            .iter()
+            .map(|term| {
+                name_words
+                    .iter()
+                    .map(|word| match word {
+                        word if word == term => 1.0,
+                        word if word.starts_with(term.as_str()) => 0.9,
+                        word if word.contains(term.as_str()) => 0.8,
+                        word => strsim::jaro_winkler(term, word) * 0.8,
+                    })
+                    .fold(0.0, f64::max)
+            })
+            .sum::<f64>()
+            / terms.len() as f64;
+        let whole = strsim::normalized_levenshtein(&terms.join(" "), &name_words.join(" "));
+        let score = 0.9 * word_score + 0.1 * whole;
+        (score * 1000.0).round() / 1000.0
+    }
+
+    /// Returns the number of matches of the model above the threshold, from the total reported with the first page of a
+    /// single match instead of reading all pages
+    pub fn count_model_matches(&self, uuid: &Uuid, threshold: f64) -> Result<u32, ApiError> {
+        trace!("Counting matches of model {}...", uuid);
+        let page = self
+            .runtime
+            .block_on(self.client.get_model_match_page(uuid, threshold, 1, 1))?;
+        Ok(page.page_data.total)
+    }
+
     pub fn match_model(
         &self,
         uuid: &Uuid,
@@ -266,9 +1430,28 @@ impl Api {
         with_reference_meta: bool,
         classification: Option<&String>,
         tag: Option<&String>,
+    ) -> Result<ListOfModelMatches, ApiError> {
+        self.runtime.block_on(self.match_model_async(
+            uuid,
+            threshold,
+            with_meta,
+            with_reference_meta,
+            classification,
+            tag,
+        ))
+    }
+
+    async fn match_model_async(
+        &self,
+        uuid: &Uuid,
+        threshold: f64,
+        with_meta: bool,
+        with_reference_meta: bool,
+        classification: Option<&String>,
+        tag: Option<&String>,
     ) -> Result<ListOfModelMatches, ApiError> {
         let reference_metadata: Option<ModelMetadata> = if with_reference_meta {
-            self.client.get_model_metadata(uuid)?
+            self.client.get_model_metadata(uuid).await?
         } else {
             None
         };
@@ -278,40 +1461,47 @@ impl Api {
 
         let mut has_more = true;
         let mut page: u32 = 1;
-        let per_page: u32 = 50;
+        let per_page = self.page_size(50);
         while has_more {
             let result = self
                 .client
-                .get_model_match_page(uuid, threshold, per_page, page)?;
+                .get_model_match_page(uuid, threshold, per_page, page)
+                .await?;
             if result.page_data.total > 0 {
                 let matches = result.matches;
                 if !matches.is_empty() {
                     trace!("Reading the list of properties for model {}...", uuid);
                     let properties = match classification {
-                        Some(_) => Some(self.client.get_list_of_properties()?),
+                        Some(_) => Some(self.client.get_list_of_properties().await?),
                         None => None,
                     };
 
+                    let mut metadata_of_matches = if with_meta {
+                        let uuids: Vec<Uuid> =
+                            matches.iter().map(|m| m.matched_model.uuid).collect();
+                        self.get_metadata_of_models(&uuids).await?
+                    } else {
+                        HashMap::new()
+                    };
+
                     for m in matches {
                         let mut model_match = ModelMatch::from(m);
                         let model = model_match.model.clone();
                         let metadata: Option<ModelMetadata> = if with_meta {
-                            let matching_metadata = self.get_model_metadata(&model.uuid)?;
+                            let matching_metadata =
+                                metadata_of_matches.remove(&model.uuid).flatten();
 
                             if matching_metadata.is_some() || reference_metadata.is_some() {
                                 let mut combined_meta = ModelMetadata::default();
 
                                 matching_metadata
-                                    .unwrap()
-                                    .properties
                                     .iter()
+                                    .flat_map(|metadata| metadata.properties.iter())
                                     .for_each(|item| combined_meta.add(item));
 
                                 reference_metadata
-                                    .as_ref()
-                                    .unwrap()
-                                    .properties
                                     .iter()
+                                    .flat_map(|metadata| metadata.properties.iter())
                                     .for_each(|item| {
                                         combined_meta.add(&ModelMetadataItem::new(
                                             item.key_id,
@@ -330,34 +1520,38 @@ impl Api {
 
                         //log::trace!("Model metadata: {:?}", &metadata);
 
-                        match classification {
-                            Some(classification) => {
-                                let property =
-                                    properties.as_ref().unwrap().properties.iter().find(|p| {
-                                        p.name.eq_ignore_ascii_case(classification.as_str())
-                                    });
-                                let property = match property {
-                                    Some(property) => property.clone(),
-                                    None => {
-                                        self.client.post_property(&String::from(classification))?
-                                    }
-                                };
-
-                                let item = ModelMetadataItem::new(
-                                    property.id.clone(),
-                                    String::from(classification),
-                                    String::from(tag.unwrap()),
-                                );
-
-                                trace!(
-                                    "Setting property {} to value of {} for model {}",
-                                    classification,
-                                    tag.unwrap(),
-                                    model.uuid
-                                );
-                                self.client.put_model_property(&uuid, &property.id, &item)?;
-                            }
-                            None => (),
+                        if let (Some(classification), Some(tag), Some(properties)) =
+                            (classification, tag, &properties)
+                        {
+                            let property = properties
+                                .properties
+                                .iter()
+                                .find(|p| p.name.eq_ignore_ascii_case(classification.as_str()));
+                            let property = match property {
+                                Some(property) => property.clone(),
+                                None => {
+                                    self.client
+                                        .post_property(&String::from(classification))
+                                        .await?
+                                }
+                            };
+
+                            let item = ModelMetadataItem::new(
+                                property.id,
+                                String::from(classification),
+                                String::from(tag),
+                            );
+
+                            trace!(
+                                "Setting property {} to value of {} for model {}",
+                                classification,
+                                tag,
+                                model.uuid
+                            );
+                            self.client
+                                .put_model_property(uuid, &property.id, &item)
+                                .await?;
+                            self.forget_model(uuid);
                         }
 
                         match metadata {
@@ -377,17 +1571,60 @@ impl Api {
         Ok(ListOfModelMatches::new(Box::new(list_of_matches)))
     }
 
+    /// Reads the metadata of the models concurrently, e.g. to sort them by a property
+    pub fn read_metadata(&self, models: &mut ListOfModels) -> Result<(), ApiError> {
+        let uuids: Vec<Uuid> = models.models.iter().map(|model| model.uuid).collect();
+        let mut metadata = self.runtime.block_on(self.get_metadata_of_models(&uuids))?;
+        for model in models.models.iter_mut() {
+            model.metadata = metadata
+                .remove(&model.uuid)
+                .flatten()
+                .map(|metadata| metadata.properties);
+        }
+        Ok(())
+    }
+
+    /// Reads the metadata of multiple models concurrently
+    async fn get_metadata_of_models(
+        &self,
+        uuids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Option<ModelMetadata>>, ApiError> {
+        let mut result = HashMap::new();
+        let mut missing: Vec<&Uuid> = Vec::new();
+        for uuid in uuids {
+            match self.cached::<Option<ModelMetadata>>(cache::METADATA, &uuid.to_string()) {
+                Some(metadata) => {
+                    result.insert(uuid.to_owned(), metadata);
+                }
+                None => missing.push(uuid),
+            }
+        }
+
+        let requests = missing.into_iter().map(|uuid| async move {
+            let metadata = self.client.get_model_metadata(uuid).await;
+            (uuid.to_owned(), metadata)
+        });
+
+        for (uuid, metadata) in join_all(requests).await {
+            let metadata = metadata?;
+            self.store(cache::METADATA, &uuid.to_string(), &metadata);
+            result.insert(uuid, metadata);
+        }
+        Ok(result)
+    }
+
     pub fn match_model_visual(&self, uuid: &Uuid) -> Result<ListOfVisualModelMatches, ApiError> {
         trace!("Matching model visual {}...", uuid);
         let mut list_of_matches: Vec<VisuallyMatchedModel> = Vec::new();
 
         let mut has_more = true;
         let mut page: u32 = 1;
-        let per_page: u32 = 100;
+        let per_page = self.page_size(100);
         while has_more {
-            let result = self
-                .client
-                .get_model_visual_match_page(uuid, per_page, page)?;
+            let result = self.runtime.block_on(
+                self.client
+                    .get_model_visual_match_page(uuid, per_page, page),
+            )?;
             if result.page_data.total > 0 {
                 let matches = result.matches;
                 if !matches.is_empty() {
@@ -404,7 +1641,7 @@ impl Api {
         if let Some(pos) = list_of_matches
             .iter()
             .cloned()
-            .position(|x| x.uuid == uuid.to_owned())
+            .position(|x| x.uuid == *uuid)
         {
             list_of_matches.remove(pos);
         }
@@ -428,58 +1665,65 @@ impl Api {
 
         let mut has_more = true;
         let mut page: u32 = 1;
-        let per_page: u32 = 50;
+        let per_page = self.page_size(50);
         while has_more {
-            let result = self
-                .client
-                .get_model_scan_match_page(uuid, threshold, per_page, page)?;
+            let result = self.runtime.block_on(
+                self.client
+                    .get_model_scan_match_page(uuid, threshold, per_page, page),
+            )?;
             if result.page_data.total > 0 {
                 let matches = result.matches;
                 if !matches.is_empty() {
                     trace!("Reading the list of properties for model {}...", uuid);
                     let properties = match classification {
-                        Some(_) => Some(self.client.get_list_of_properties()?),
+                        Some(_) => Some(
+                            self.runtime
+                                .block_on(self.client.get_list_of_properties())?,
+                        ),
                         None => None,
                     };
 
                     for m in matches {
                         let mut model_match = ModelMatch::from(m);
                         let model = model_match.model.clone();
-                        let metadata: Option<ModelMetadata>;
-                        if with_meta {
-                            metadata = self.get_model_metadata(&model.uuid)?;
+                        let metadata: Option<ModelMetadata> = if with_meta {
+                            self.get_model_metadata(&model.uuid)?
                         } else {
-                            metadata = None;
-                        }
+                            None
+                        };
 
-                        match classification {
-                            Some(classification) => {
-                                let property =
-                                    properties.as_ref().unwrap().properties.iter().find(|p| {
-                                        p.name.eq_ignore_ascii_case(classification.as_str())
-                                    });
-                                let property = match property {
-                                    Some(property) => property.clone(),
-                                    None => {
-                                        self.client.post_property(&String::from(classification))?
-                                    }
-                                };
-
-                                let item = ModelMetadataItem::new(
-                                    property.id.clone(),
-                                    String::from(classification),
-                                    String::from(tag.unwrap()),
-                                );
-
-                                trace!(
-                                    "Setting property {} to value of {} for model {}",
-                                    classification,
-                                    tag.unwrap(),
-                                    model.uuid
-                                );
-                                self.client.put_model_property(&uuid, &property.id, &item)?;
-                            }
-                            None => (),
+                        if let (Some(classification), Some(tag), Some(properties)) =
+                            (classification, tag, &properties)
+                        {
+                            let property = properties
+                                .properties
+                                .iter()
+                                .find(|p| p.name.eq_ignore_ascii_case(classification.as_str()));
+                            let property = match property {
+                                Some(property) => property.clone(),
+                                None => self.runtime.block_on(
+                                    self.client.post_property(&String::from(classification)),
+                                )?,
+                            };
+
+                            let item = ModelMetadataItem::new(
+                                property.id,
+                                String::from(classification),
+                                String::from(tag),
+                            );
+
+                            trace!(
+                                "Setting property {} to value of {} for model {}",
+                                classification,
+                                tag,
+                                model.uuid
+                            );
+                            self.runtime.block_on(self.client.put_model_property(
+                                uuid,
+                                &property.id,
+                                &item,
+                            ))?;
+                            self.forget_model(uuid);
                         }
 
                         match metadata {
@@ -499,8 +1743,8 @@ impl Api {
         Ok(ListOfModelMatches::new(Box::new(list_of_matches)))
     }
 
-    pub fn set_property(&self, name: &String) -> Result<Property, ApiError> {
-        Ok(self.client.post_property(name)?)
+    pub fn set_property(&self, name: &str) -> Result<Property, ApiError> {
+        Ok(self.runtime.block_on(self.client.post_property(name))?)
     }
 
     pub fn set_model_property(
@@ -509,7 +1753,11 @@ impl Api {
         id: &u64,
         item: &ModelMetadataItem,
     ) -> Result<ModelMetadataItem, ApiError> {
-        Ok(self.client.put_model_property(model_uuid, id, item)?)
+        let item = self
+            .runtime
+            .block_on(self.client.put_model_property(model_uuid, id, item))?;
+        self.forget_model(model_uuid);
+        Ok(item)
     }
 
     fn generate_graph_from_assembly_tree(
@@ -530,20 +1778,16 @@ impl Api {
             };
             dictionary.insert(node_dictionary_item.uuid, node_dictionary_item);
 
-            match parent_node_index {
-                Some(parent_node_index) => {
-                    graph.add_edge(parent_node_index, node_index, 1.0);
-                }
-                None => (),
+            if let Some(parent_node_index) = parent_node_index {
+                graph.add_edge(parent_node_index, node_index, 1.0);
             }
 
-            let children = tree.children.to_owned();
-            if tree.children.is_some() {
+            if let Some(children) = &tree.children {
                 self.generate_graph_from_assembly_tree(
                     Some(node_index),
                     graph,
                     dictionary,
-                    &children.unwrap(),
+                    children,
                 );
             }
         }
@@ -560,35 +1804,27 @@ impl Api {
         existing_folders: &ListOfFolders,
         desired_folder_names: &HashSet<String>,
     ) -> Result<ListOfFolders, ApiError> {
-        let existing_folder_names: HashSet<String> = existing_folders
-            .into_iter()
-            .map(|f| f.name.to_owned())
-            .collect();
+        // if there is no filter, include all folders
+        if desired_folder_names.is_empty() {
+            return Ok(existing_folders.clone());
+        }
 
-        // generate an error if any of the desired names are not existing folder names
-        let diff: HashSet<String> = desired_folder_names
-            .difference(&existing_folder_names)
+        // generate an error if any of the desired names (or patterns) does not match an existing folder
+        let mut missing: Vec<String> = desired_folder_names
+            .iter()
+            .filter(|name| !existing_folders.into_iter().any(|f| f.matches(name)))
             .cloned()
             .collect();
-
-        if diff.len() > 0 {
-            return Err(ApiError::FolderNotFound(
-                diff.into_iter().collect::<Vec<String>>().join(", "),
-            ));
+        if !missing.is_empty() {
+            missing.sort();
+            return Err(ApiError::FolderNotFound(missing.join(", ")));
         }
 
-        let validated_folders = if desired_folder_names.len() > 0 {
-            // if there is a filter, include only the folders that match the names
-            desired_folder_names
-                .iter()
-                .map(|n| existing_folders.get_folder_by_name(n.as_str()).unwrap())
-                .collect()
-        } else {
-            // if there is no filter, include all folders
-            existing_folders.clone()
-        };
-
-        Ok(validated_folders)
+        // include only the folders that match the names
+        Ok(existing_folders
+            .into_iter()
+            .filter(|f| desired_folder_names.iter().any(|name| f.matches(name)))
+            .collect())
     }
 
     pub fn generate_simple_model_match_report(
@@ -598,7 +1834,7 @@ impl Api {
         folders: Option<HashSet<String>>,
         exclusive: bool,
         with_meta: bool,
-        metadata_filter: Option<HashMap<String, String>>,
+        metadata_filter: Option<Vec<MetadataCondition>>,
     ) -> Result<SimpleDuplicatesMatchReport, ApiError> {
         trace!("Generating simple match report...");
 
@@ -613,7 +1849,15 @@ impl Api {
             None => existing_folders.clone(),
         };
 
+        // Models that pass the state and metadata checks
+        let mut candidates: Vec<(Uuid, Model)> = Vec::new();
+
         for uuid in uuids {
+            // the models left once the run is out of time are not matched
+            if self.deadline.exceeded() {
+                simple_match_report.not_started += 1;
+                continue;
+            }
             let mut model = match self.get_model(&uuid, true, with_meta) {
                 Ok(model) => model,
                 Err(e) => {
@@ -636,10 +1880,8 @@ impl Api {
                     debug!("Applying metadata filter...");
                     match model.get_metadata_as_properties() {
                         Some(metadata) => {
-                            let all_exist = filter.iter().all(|(k, v)| match metadata.get(k) {
-                                Some(value) => value == v,
-                                None => false,
-                            });
+                            let all_exist =
+                                filter.iter().all(|condition| condition.matches(&metadata));
 
                             if !all_exist {
                                 debug!("Failed metadata filter condition(s)");
@@ -660,19 +1902,26 @@ impl Api {
             }
 
             let folder = existing_folders.get_folder_by_id(&model.folder_id);
-            model.folder_name = match folder {
-                Some(folder) => Some(folder.name.to_owned()),
-                None => None,
-            };
+            model.folder_name = folder.map(|folder| folder.name.to_owned());
 
-            let matches =
-                match self.match_model(&uuid, threshold.clone(), with_meta, false, None, None) {
-                    Ok(matches) => matches,
-                    Err(e) => {
-                        warn!("Failed to match model {}: {}", uuid, e);
-                        continue;
-                    }
-                };
+            candidates.push((uuid, model));
+        }
+
+        // Match all candidates concurrently
+        let matches = self
+            .runtime
+            .block_on(join_all(candidates.iter().map(|(uuid, _)| {
+                self.match_model_async(uuid, *threshold, with_meta, false, None, None)
+            })));
+
+        for ((uuid, model), matches) in candidates.into_iter().zip(matches) {
+            let matches = match matches {
+                Ok(matches) => matches,
+                Err(e) => {
+                    warn!("Failed to match model {}: {}", uuid, e);
+                    continue;
+                }
+            };
 
             let mut simple_duplicate_matches: Vec<ModelMatch> = Vec::new();
 
@@ -681,22 +1930,17 @@ impl Api {
                     !exclusive || folders.get_folder_by_id(&m.model.folder_id).is_some();
                 let is_name_different = model.name != m.model.name;
                 let is_type_different = model.is_assembly != m.model.is_assembly;
-                let is_not_duplicate = !simple_duplicate_matches.contains(&m);
+                let is_not_duplicate = !simple_duplicate_matches.contains(m);
 
                 if is_exclusive_valid
                     && (is_name_different || is_type_different)
                     && is_not_duplicate
                 {
                     let mut m1 = m.clone();
-                    m1.comparison_url = Some(format!(
-                        "https://{}.physna.com/app/compare?modelAId={}&modelBId={}",
-                        self.client.tenant, uuid, m1.model.uuid
-                    ));
-                    m1.model.folder_name =
-                        match existing_folders.get_folder_by_id(&m1.model.folder_id) {
-                            Some(folder) => Some(folder.name.to_owned()),
-                            None => None,
-                        };
+                    m1.comparison_url = Some(self.comparison_url(&uuid, &m1.model.uuid));
+                    m1.model.folder_name = existing_folders
+                        .get_folder_by_id(&m1.model.folder_id)
+                        .map(|folder| folder.name.to_owned());
 
                     simple_duplicate_matches.push(m1);
                 }
@@ -713,6 +1957,8 @@ impl Api {
                     uuid: uuid.to_string(),
                     name: model.name.clone(),
                     folder_name,
+                    owner_id: model.owner_id.to_owned(),
+                    owner: None,
                     matches: simple_duplicate_matches,
                 };
                 simple_match_report.inner.insert(uuid.to_string(), item);
@@ -727,17 +1973,20 @@ impl Api {
         uuids: Vec<Uuid>,
         threshold: f64,
         with_meta: bool,
-        meta_filter: Option<HashMap<String, String>>,
+        meta_filter: Option<Vec<MetadataCondition>>,
     ) -> Result<ModelMatchReport, ApiError> {
         let mut flat_bom = FlatBom::empty();
         let mut roots: HashMap<Uuid, ModelAssemblyTree> = HashMap::new();
         let mut dictionary: HashMap<Uuid, PartNodeDictionaryItem> = HashMap::new();
 
+        let mut order: Vec<Uuid> = Vec::new();
+
         // Create the Assembly Tree(s)
         for uuid in uuids {
-            let assembly_tree = self.get_model_assembly_tree(&uuid);
+            let assembly_tree = self.get_model_assembly_tree(&uuid, None);
             match assembly_tree {
                 Ok(assembly_tree) => {
+                    order.push(uuid);
                     roots.insert(uuid, assembly_tree.clone());
                     flat_bom.extend(&FlatBom::from(assembly_tree));
                 }
@@ -749,8 +1998,8 @@ impl Api {
             .inner
             .to_owned()
             .keys()
-            .map(|uuid| Uuid::parse_str(uuid.as_str()).unwrap())
-            .collect();
+            .map(|uuid| Uuid::parse_str(uuid.as_str()))
+            .collect::<Result<Vec<Uuid>, uuid::Error>>()?;
 
         let simple_match_report = self.generate_simple_model_match_report(
             target_uuids,
@@ -772,149 +2021,1424 @@ impl Api {
 
         //let matrix = generate_matrix_from_match_report(&simple_match_report, &dictionary);
 
+        // Count the components of each assembly that have duplicates, once for every occurrence
+        let coverage = order
+            .iter()
+            .filter_map(|uuid| roots.get(uuid))
+            .map(|tree| {
+                let bom = BillOfMaterials::from(tree);
+                let count = |duplicated_only: bool| {
+                    bom.items
+                        .iter()
+                        .filter(|item| {
+                            !duplicated_only
+                                || simple_match_report
+                                    .inner
+                                    .contains_key(&item.uuid.to_string())
+                        })
+                        .map(|item| item.quantity)
+                        .sum()
+                };
+                AssemblyCoverage::new(
+                    tree.model.uuid,
+                    tree.model.name.to_owned(),
+                    count(false),
+                    count(true),
+                )
+            })
+            .collect();
+
         Ok(ModelMatchReport {
             duplicates: simple_match_report,
+            coverage: ListOfAssemblyCoverage::new(coverage),
             dictionary,
             graph,
             //matrix: matrix,
         })
     }
 
+    /// Counts for each model in the folders how many assemblies in the folders have it as a direct component, and how
+    /// many other models match it above the threshold. If exclusive, only the matches within the folders are counted
+    pub fn generate_reuse_report(
+        &mut self,
+        folders: HashSet<String>,
+        threshold: f64,
+        exclusive: bool,
+    ) -> Result<ListOfReuseItems, ApiError> {
+        let existing_folders = self.get_list_of_folders(None)?;
+        let models = self.list_all_models(Some(folders), None)?.models;
+        let in_folders: HashSet<Uuid> = models.iter().map(|model| model.uuid).collect();
+
+        trace!("Reading the assembly trees...");
+        let assemblies: Vec<&Model> = models.iter().filter(|model| model.is_assembly).collect();
+        let trees = self.runtime.block_on(join_all(
+            assemblies
+                .iter()
+                .map(|assembly| self.client.get_assembly_tree_for_model(&assembly.uuid)),
+        ));
+        let mut parents: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        for (assembly, tree) in assemblies.iter().zip(trees) {
+            let tree = match tree {
+                Ok(tree) => tree,
+                Err(e) => {
+                    warn!(
+                        "Failed to read the assembly tree of {}: {}",
+                        assembly.uuid, e
+                    );
+                    continue;
+                }
+            };
+            for child in tree.children.iter().flatten() {
+                parents
+                    .entry(Uuid::parse_str(&child.uuid)?)
+                    .or_default()
+                    .insert(assembly.uuid);
+            }
+        }
+
+        trace!("Matching the models...");
+        let finished: Vec<&Model> = models
+            .iter()
+            .filter(|model| model.state == "finished")
+            .collect();
+        let matches = self.runtime.block_on(join_all(finished.iter().map(|model| {
+            self.match_model_async(&model.uuid, threshold, false, false, None, None)
+        })));
+        let mut duplicates: HashMap<Uuid, u32> = HashMap::new();
+        for (model, matches) in finished.iter().zip(matches) {
+            match matches {
+                Ok(matches) => {
+                    let count = matches
+                        .inner
+                        .iter()
+                        .filter(|m| m.model.uuid != model.uuid)
+                        .filter(|m| !exclusive || in_folders.contains(&m.model.uuid))
+                        .count();
+                    duplicates.insert(model.uuid, count as u32);
+                }
+                Err(e) => warn!("Failed to match model {}: {}", model.uuid, e),
+            }
+        }
+
+        Ok(ListOfReuseItems::new(
+            models
+                .iter()
+                .map(|model| ReuseItem {
+                    uuid: model.uuid,
+                    name: model.name.to_owned(),
+                    folder: existing_folders
+                        .get_folder_by_id(&model.folder_id)
+                        .map(|folder| folder.name.to_owned())
+                        .unwrap_or_default(),
+                    is_assembly: model.is_assembly,
+                    assemblies: parents
+                        .get(&model.uuid)
+                        .map(|parents| parents.len() as u32)
+                        .unwrap_or_default(),
+                    duplicates: duplicates.get(&model.uuid).copied().unwrap_or_default(),
+                })
+                .collect(),
+        ))
+    }
+
     pub fn tenant_stats(
         &mut self,
         folders: HashSet<String>,
         force_fix: bool,
         ignore_assemblies: bool,
+        group_by: Option<StatusGrouping>,
     ) -> Result<EnvironmentStatusReport, ApiError> {
         let all_folders = self.get_list_of_folders(None)?;
         let all_folders: HashMap<u32, Folder> =
             all_folders.into_iter().map(|f| (f.id, f)).collect();
+        let owners = match group_by {
+            Some(StatusGrouping::Owner) => self.user_names(),
+            _ => HashMap::new(),
+        };
 
-        let models = self.list_all_models(Some(folders), None)?;
+        // the states change while the models are processed, therefore a cached listing would be stale
+        let models = self.list_models(Some(folders), None, false)?;
         let models = models.models.to_owned();
         let mut result: HashMap<u64, ModelStatusRecord> = HashMap::new();
+        let mut repaired: Vec<Uuid> = Vec::new();
 
         for model in models {
             if force_fix
-                && !model.state.eq_ignore_ascii_case("FINISHED")
-                && !model.state.eq_ignore_ascii_case("NO 3D DATA")
+                && !model.is_healthy()
+                && (!model.is_assembly || !ignore_assemblies)
+                && self.reprocess_model(&model.uuid).is_ok()
             {
-                if !model.is_assembly || !ignore_assemblies {
-                    let _ = self.reprocess_model(&model.uuid);
-                }
+                repaired.push(model.uuid);
             }
 
             let folder_id = model.folder_id;
-            let folder_name = all_folders.get(&folder_id).unwrap().name.to_owned();
-            let folder_name2 = folder_name.to_owned();
-            let stat = ModelStatusRecord::new(
+            let folder_name = all_folders
+                .get(&folder_id)
+                .map(|folder| folder.name.to_owned())
+                .unwrap_or_default();
+            let mut stat = ModelStatusRecord::new(
                 folder_id,
                 folder_name,
                 model.file_type.to_uppercase(),
                 model.state.to_uppercase(),
                 1,
             );
+            match group_by {
+                None => (),
+                Some(StatusGrouping::Folder) => stat.file_type = String::default(),
+                Some(StatusGrouping::FileType) => {
+                    stat.folder_id = 0;
+                    stat.folder_name = String::default();
+                }
+                Some(StatusGrouping::Owner) => {
+                    stat.folder_id = 0;
+                    stat.folder_name = String::default();
+                    stat.file_type = String::default();
+                    stat.owner = owners
+                        .get(&model.owner_id)
+                        .cloned()
+                        .unwrap_or(model.owner_id.to_owned());
+                }
+            }
+
             let mut s = DefaultHasher::new();
             stat.hash(&mut s);
             let h = s.finish();
-            let stat_as_found = result.get(&h);
-            match stat_as_found {
-                Some(s) => {
-                    let s2 = ModelStatusRecord::new(
-                        folder_id,
-                        folder_name2,
-                        model.file_type.to_uppercase(),
-                        model.state.to_uppercase(),
-                        s.count + 1,
-                    );
-                    result.insert(h, s2);
-                }
+            match result.get_mut(&h) {
+                Some(s) => s.count += 1,
                 None => {
                     result.insert(h, stat);
                 }
             }
         }
 
-        let result: Vec<ModelStatusRecord> = result.into_iter().map(|(_, s)| s).collect();
+        let result: Vec<ModelStatusRecord> = result.into_values().collect();
         let mut stats: EnvironmentStatusReport = EnvironmentStatusReport::new();
         stats.stats = result;
+        stats.repaired = repaired;
+        stats.group_by = group_by;
         Ok(stats)
     }
 
-    pub fn upload_model(&self, folder: &str, path: &PathBuf) -> Result<Option<Model>, ApiError> {
-        Ok(self.client.upload_model(folder, path)?)
+    /// Maps the IDs of the users to their names, or to their email if the name is not known. If the users cannot
+    /// be read, the map is empty
+    pub fn user_names(&self) -> HashMap<String, String> {
+        match self.get_list_of_users(None, None) {
+            Ok(users) => users
+                .users
+                .into_iter()
+                .map(|user| {
+                    let name = match user.name() {
+                        name if name.is_empty() => user.email.to_owned(),
+                        name => name,
+                    };
+                    (user.uuid.to_string(), name)
+                })
+                .collect(),
+            Err(e) => {
+                warn!("Failed to read the users: {}", e);
+                HashMap::new()
+            }
+        }
     }
 
-    pub fn download_model(&self, uuid: &Uuid) -> Result<(), ApiError> {
-        Ok(self.client.download_model(uuid)?)
+    /// Verifies that previously reprocessed models have been repaired
+    ///
+    /// Parameters:
+    ///
+    /// uuids - the models that were sent for reprocessing
+    /// retries - how many more times to reprocess a model that failed again, and to wait for the models still being
+    ///           processed
+    /// delay - how long to wait for the reprocessing to complete before each check
+    ///
+    /// Returns the list of models that are still not repaired after all retries. They have failed again or are still
+    /// being processed
+    pub fn verify_repair(
+        &mut self,
+        uuids: &[Uuid],
+        retries: u32,
+        delay: Duration,
+    ) -> Result<ListOfModels, ApiError> {
+        let mut pending: Vec<Uuid> = uuids.to_vec();
+        let mut attempt: u32 = 0;
+
+        loop {
+            trace!(
+                "Waiting {} second(s) before verifying {} repaired model(s)...",
+                delay.as_secs(),
+                pending.len()
+            );
+            std::thread::sleep(delay);
+
+            // a model that is still being processed is checked again, but not reprocessed
+            let mut failed: Vec<Model> = Vec::new();
+            let mut processing: Vec<Model> = Vec::new();
+            for uuid in pending.iter() {
+                let model = self.get_model(uuid, false, false)?;
+                if model.is_healthy() {
+                    continue;
+                }
+                debug!("Model {} is still in state {}", uuid, model.state);
+                if model.is_processed() {
+                    failed.push(model);
+                } else {
+                    processing.push(model);
+                }
+            }
+
+            if (failed.is_empty() && processing.is_empty()) || attempt >= retries {
+                failed.append(&mut processing);
+                return Ok(ListOfModels::from(failed));
+            }
+
+            attempt += 1;
+            debug!(
+                "Reprocessing {} model(s), attempt {} of {}...",
+                failed.len(),
+                attempt,
+                retries
+            );
+            for model in failed.iter() {
+                let _ = self.reprocess_model(&model.uuid);
+            }
+            pending = failed
+                .into_iter()
+                .chain(processing)
+                .map(|m| m.uuid)
+                .collect();
+        }
     }
 
-    pub fn list_all_properties(&self) -> Result<PropertyCollection, ApiError> {
-        trace!("Listing all properties...");
-        Ok(self.client.get_list_of_properties()?)
+    pub fn upload_model(&self, folder: &str, path: &Path) -> Result<Option<Model>, ApiError> {
+        let model = self
+            .runtime
+            .block_on(self.client.upload_model(folder, path))?;
+        self.forget_model_listings();
+        Ok(model)
     }
 
-    pub fn upload_model_metadata(&self, input_file: &File, clean: bool) -> Result<(), ApiError> {
-        // Get all properties and cache them. The Physna API V2 does not allow me to get property by name
+    pub fn download_model(&self, uuid: &Uuid) -> Result<(), ApiError> {
+        Ok(self.runtime.block_on(self.client.download_model(uuid))?)
+    }
+
+    pub fn download_model_to(&self, uuid: &Uuid, directory: &Path) -> Result<PathBuf, ApiError> {
+        Ok(self
+            .runtime
+            .block_on(self.client.download_model_to(uuid, directory))?)
+    }
+
+    /// Downloads the source files of the models into a directory or into cloud object storage, each below a
+    /// subdirectory named after the model UUID, and writes a manifest of the files next to them. A file that cannot
+    /// be written does not stop the others, and its error is reported
+    ///
+    /// Parameters:
+    ///
+    /// models - the models to download
+    /// output - the path to the directory, or the URL of a prefix in object storage (e.g. "s3://bucket/archive")
+    pub fn download_models(
+        &self,
+        models: Vec<Model>,
+        output: &str,
+    ) -> Result<ListOfModelDownloads, ApiError> {
+        let folders: HashMap<u32, String> = self
+            .get_list_of_folders(None)?
+            .into_iter()
+            .map(|folder| (folder.id, folder.name))
+            .collect();
+        let is_object = storage::is_object_url(output);
+        // the files for object storage pass through this directory one at a time
+        let staging = tempfile::tempdir()?;
+        if !is_object {
+            std::fs::create_dir_all(output)?;
+        }
+
+        let mut downloads = Vec::new();
+        for model in models {
+            trace!("Downloading model {}...", model.uuid);
+            let mut download = ModelDownload {
+                uuid: model.uuid,
+                name: model.name,
+                folder: folders.get(&model.folder_id).cloned().unwrap_or_default(),
+                file: None,
+                size: 0,
+                error: None,
+            };
+            // the models left once the run is out of time are reported as not downloaded
+            if self.deadline.exceeded() {
+                download.error = Some(String::from(NOT_STARTED));
+                downloads.push(download);
+                continue;
+            }
+            match self.download_model_into(&model.uuid, output, is_object, staging.path()) {
+                Ok((file, size)) => {
+                    download.file = Some(file);
+                    download.size = size;
+                }
+                Err(e) => {
+                    warn!("Failed to download model {}: {}", model.uuid, e);
+                    download.error = Some(e.to_string());
+                }
+            }
+            downloads.push(download);
+        }
+
+        let report = ListOfModelDownloads::new(downloads);
+        let manifest = report.to_csv(true)?;
+        if is_object {
+            let path = staging.path().join(DOWNLOAD_MANIFEST);
+            std::fs::write(&path, manifest)?;
+            storage::store(&path, &storage::join(output, DOWNLOAD_MANIFEST)?)?;
+        } else {
+            std::fs::write(Path::new(output).join(DOWNLOAD_MANIFEST), manifest)?;
+        }
+        Ok(report)
+    }
+
+    /// Downloads the source file of the model and returns where it was written, and its size
+    fn download_model_into(
+        &self,
+        uuid: &Uuid,
+        output: &str,
+        is_object: bool,
+        staging: &Path,
+    ) -> Result<(String, u64), ApiError> {
+        if !is_object {
+            let directory = Path::new(output).join(uuid.to_string());
+            std::fs::create_dir_all(&directory)?;
+            let path = self.download_model_to(uuid, &directory)?;
+            let size = std::fs::metadata(&path)?.len();
+            return Ok((path.to_string_lossy().to_string(), size));
+        }
+
+        let directory = staging.join(uuid.to_string());
+        std::fs::create_dir_all(&directory)?;
+        let result = self.download_model_to(uuid, &directory).and_then(|path| {
+            let size = std::fs::metadata(&path)?.len();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let url = storage::join(output, &format!("{}/{}", uuid, name))?;
+            storage::store(&path, &url)?;
+            Ok((url, size))
+        });
+        let _ = std::fs::remove_dir_all(&directory);
+        result
+    }
+
+    /// Copies a model into another folder of the same tenant
+    ///
+    /// The Physna API does not support assigning a model to multiple folders, therefore the source file is
+    /// downloaded and uploaded again into the target folder. The metadata of the original model is carried over.
+    ///
+    /// Parameters:
+    ///
+    /// uuid - the model UUID
+    /// folder - the name of the target folder
+    ///
+    /// Returns the newly created model
+    pub fn copy_model_to_folder(&mut self, uuid: &Uuid, folder: &str) -> Result<Model, ApiError> {
+        trace!("Copying model {} to folder {}...", uuid, folder);
+        let source_model = self.get_model(uuid, false, true)?;
+
+        let directory = tempfile::tempdir()?;
+        let path = self.download_model_to(uuid, directory.path())?;
+        let target_model = match self.upload_model(folder, &path)? {
+            Some(model) => model,
+            None => {
+                return Err(ApiError::FailedToRead(format!(
+                    "no model was created when uploading {}",
+                    path.display()
+                )))
+            }
+        };
+
+        if let Some(metadata) = source_model.metadata {
+            self.set_model_metadata(&target_model.uuid, &ModelMetadata::new(metadata))?;
+        }
+
+        Ok(target_model)
+    }
+
+    /// Exports an offline snapshot of the models in the tenant into a directory
+    ///
+    /// The snapshot contains the following:
+    ///
+    /// folders.json - the list of exported folders
+    /// models.csv - the inventory of exported models
+    /// metadata.csv - the metadata of all exported models (compatible with upload-model-meta)
+    /// files/<UUID>/ - the source file for each model
+    ///
+    /// Parameters:
+    ///
+    /// folders - list of folder names to be exported. If empty, all folders are included
+    /// output - the output directory
+    pub fn export(
+        &mut self,
+        folders: &HashSet<String>,
+        output: &Path,
+    ) -> Result<TransferReport, ApiError> {
+        let existing_folders = self.get_list_of_folders(None)?;
+        let folders = self.validate_folders(&existing_folders, folders)?;
+        let folder_names: HashSet<String> =
+            folders.folders.iter().map(|f| f.name.to_owned()).collect();
+        let models = self.list_all_models(Some(folder_names), None)?;
+
+        self.write_snapshot(&folders, models.models, output, true)
+    }
+
+    /// Records the models and folders about to be deleted as a snapshot (see export), so that they can be restored
+    /// with the import
+    ///
+    /// Parameters:
+    ///
+    /// folders - the names of the folders to record, in addition to the folders of the models
+    /// uuids - the model UUIDs
+    /// output - the output directory
+    /// download - also downloads the source files. Without them, the snapshot is only a record of what was deleted
+    pub fn archive(
+        &mut self,
+        folders: &HashSet<String>,
+        uuids: &[Uuid],
+        output: &Path,
+        download: bool,
+    ) -> Result<TransferReport, ApiError> {
+        let mut models = Vec::new();
+        for uuid in uuids {
+            models.push(self.get_model(uuid, false, false)?);
+        }
+
+        let folders: Vec<Folder> = self
+            .get_list_of_folders(None)?
+            .into_iter()
+            .filter(|f| folders.contains(&f.name) || models.iter().any(|m| m.folder_id == f.id))
+            .collect();
+
+        self.write_snapshot(&ListOfFolders::from(folders), models, output, download)
+    }
+
+    fn write_snapshot(
+        &mut self,
+        folders: &ListOfFolders,
+        models: Vec<Model>,
+        output: &Path,
+        download: bool,
+    ) -> Result<TransferReport, ApiError> {
+        std::fs::create_dir_all(output)?;
+        std::fs::write(output.join("folders.json"), folders.to_json(true)?)?;
+
+        let mut metadata_writer = csv::Writer::from_path(output.join("metadata.csv"))?;
+        metadata_writer.write_record(["UUID", "NAME", "VALUE"])?;
+
+        let mut report = TransferReport::new();
+        for model in models {
+            // the inventory only lists the models exported before the run was out of time
+            if self.deadline.exceeded() {
+                report.failed.push((model, String::from(NOT_STARTED)));
+                continue;
+            }
+            trace!("Exporting model {}...", model.uuid);
+
+            if download {
+                let directory = output.join("files").join(model.uuid.to_string());
+                std::fs::create_dir_all(&directory)?;
+                if let Err(e) = self.download_model_to(&model.uuid, &directory) {
+                    warn!("Failed to download model {}: {}", model.uuid, e);
+                    report.failed.push((model, e.to_string()));
+                    continue;
+                }
+            }
+
+            match self.get_model_metadata(&model.uuid) {
+                Ok(Some(metadata)) => {
+                    for property in metadata.properties.iter() {
+                        metadata_writer.write_record([
+                            model.uuid.to_string(),
+                            property.name.to_owned(),
+                            property.value.to_owned(),
+                        ])?;
+                    }
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    warn!("Failed to read metadata for model {}: {}", model.uuid, e);
+                    report.failed.push((model, e.to_string()));
+                    continue;
+                }
+            }
+
+            report.succeeded.push(model);
+        }
+        metadata_writer.flush()?;
+
+        let inventory = ListOfModels::from(report.succeeded.clone());
+        std::fs::write(output.join("models.csv"), inventory.to_csv(true)?)?;
+
+        Ok(report)
+    }
+
+    /// Imports an offline snapshot previously created by the export into the tenant
+    ///
+    /// Folders are recreated (if they do not exist yet) and the source files are uploaded. The metadata
+    /// of each model is re-applied right after its upload, and the new models are then checked for
+    /// having been processed.
+    ///
+    /// Parameters:
+    ///
+    /// input - the directory containing the snapshot
+    /// folder_prefix - optional prefix to add to the names of all imported folders
+    /// timeout - how long to wait for the uploaded models to be processed
+    ///
+    /// Returns a report with the newly created models
+    pub fn import(
+        &mut self,
+        input: &Path,
+        folder_prefix: Option<&str>,
+        timeout: Duration,
+    ) -> Result<TransferReport, ApiError> {
+        let folders: Vec<Folder> =
+            serde_json::from_str(&std::fs::read_to_string(input.join("folders.json"))?)?;
+        let existing_folders: HashSet<String> = self
+            .get_list_of_folders(None)?
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+
+        // maps the original folder ID to the name of the folder in this tenant
+        let mut folder_names: HashMap<u32, String> = HashMap::new();
+        for folder in folders {
+            let name = format!("{}{}", folder_prefix.unwrap_or_default(), folder.name);
+            if !existing_folders.contains(&name) {
+                self.create_folder(&name)?;
+            }
+            folder_names.insert(folder.id, name);
+        }
+
+        let mut metadata: HashMap<Uuid, Vec<ModelMetadataItem>> = HashMap::new();
+        let path = input.join("metadata.csv");
+        let mut reader = csv::Reader::from_path(&path)?;
+        for record in reader.records() {
+            let record = record?;
+            let uuid = Uuid::parse_str(snapshot_field(&path, &record, 0)?)?;
+            metadata
+                .entry(uuid)
+                .or_default()
+                .push(ModelMetadataItem::new(
+                    0,
+                    snapshot_field(&path, &record, 1)?.to_owned(),
+                    snapshot_field(&path, &record, 2)?.to_owned(),
+                ));
+        }
+
+        let mut report = TransferReport::new();
+
+        // the metadata is applied right after the upload, like when copying a model, and the models are then
+        // checked for having been processed
+        let mut uploaded: Vec<Uuid> = Vec::new();
+        for model in Self::read_exported_models(&input.join("models.csv"))? {
+            if self.deadline.exceeded() {
+                report.failed.push((model, String::from(NOT_STARTED)));
+                continue;
+            }
+            trace!("Importing model {}...", model.uuid);
+
+            let folder = match folder_names.get(&model.folder_id) {
+                Some(folder) => folder,
+                None => {
+                    report
+                        .failed
+                        .push((model, String::from("unknown folder in snapshot")));
+                    continue;
+                }
+            };
+
+            let directory = input.join("files").join(model.uuid.to_string());
+            let path = match std::fs::read_dir(&directory)?.next() {
+                Some(entry) => entry?.path(),
+                None => {
+                    report
+                        .failed
+                        .push((model, String::from("source file missing in snapshot")));
+                    continue;
+                }
+            };
+
+            let target = match self.upload_model(folder, &path) {
+                Ok(Some(target)) => target,
+                Ok(None) => {
+                    report
+                        .failed
+                        .push((model, String::from("model was not created")));
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to upload model {}: {}", model.uuid, e);
+                    report.failed.push((model, e.to_string()));
+                    continue;
+                }
+            };
+
+            if let Some(items) = metadata.get(&model.uuid) {
+                let properties = ModelMetadata::new(items.to_owned());
+                if let Err(e) = self.set_model_metadata(&target.uuid, &properties) {
+                    warn!("Failed to set metadata for model {}: {}", target.uuid, e);
+                    report.failed.push((target, e.to_string()));
+                    continue;
+                }
+            }
+            uploaded.push(target.uuid);
+        }
+
+        for model in self.wait_for_models(&uploaded, timeout, PROCESSING_POLL_INTERVAL)? {
+            if model.is_healthy() {
+                report.succeeded.push(model);
+            } else {
+                let reason = format!("model is in state {}", model.state);
+                report.failed.push((model, reason));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Waits until the models are processed (successfully or not), or the timeout or the deadline of the run expires
+    ///
+    /// Parameters:
+    ///
+    /// uuids - the model UUIDs
+    /// timeout - how long to wait
+    /// interval - how often to check the state of the models
+    ///
+    /// Returns the models in their last known state, in the order of the UUIDs
+    pub fn wait_for_models(
+        &mut self,
+        uuids: &[Uuid],
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<Vec<Model>, ApiError> {
+        let started = std::time::Instant::now();
+        let timeout = self.deadline.limit(timeout);
+        let mut models = Vec::new();
+        for uuid in uuids {
+            models.push(self.get_model(uuid, false, false)?);
+        }
+
+        while models.iter().any(|model| !model.is_processed()) {
+            let remaining = timeout.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            std::thread::sleep(interval.min(remaining));
+
+            for model in models.iter_mut().filter(|model| !model.is_processed()) {
+                trace!("Checking the state of model {}...", model.uuid);
+                *model = self.get_model(&model.uuid, false, false)?;
+            }
+        }
+        Ok(models)
+    }
+
+    /// Finds the newly uploaded models that duplicate models already in the tenant and optionally deletes them
+    ///
+    /// Only processed models can be matched, therefore this waits for the uploaded models to be processed first.
+    /// Models that are not processed in time are not checked. Two identical models uploaded together are compared
+    /// in upload order, so that only the later one is a duplicate.
+    ///
+    /// Parameters:
+    ///
+    /// uploaded - the newly uploaded models, in upload order
+    /// threshold - the match percentage (e.g. 0.95) at or above which a model is a duplicate
+    /// delete - deletes the duplicates
+    /// timeout - how long to wait for the uploaded models to be processed
+    pub fn find_duplicate_uploads(
+        &mut self,
+        uploaded: &[Model],
+        threshold: f64,
+        delete: bool,
+        timeout: Duration,
+    ) -> Result<ListOfDuplicateUploads, ApiError> {
+        let uuids: Vec<Uuid> = uploaded.iter().map(|model| model.uuid).collect();
+        let mut pending: Vec<Uuid> = Vec::new();
+        for model in self.wait_for_models(&uuids, timeout, PROCESSING_POLL_INTERVAL)? {
+            if !model.is_healthy() {
+                warn!(
+                    "Model {} is in state {} and was not checked for duplicates",
+                    model.uuid, model.state
+                );
+                pending.push(model.uuid);
+            }
+        }
+
+        let folders = self.get_list_of_folders(None)?;
+        let mut duplicates: Vec<DuplicateUpload> = Vec::new();
+        for (index, model) in uploaded.iter().enumerate() {
+            if pending.contains(&model.uuid) {
+                continue;
+            }
+
+            trace!("Checking model {} for duplicates...", model.uuid);
+            let matches = self.match_model(&model.uuid, threshold, false, false, None, None)?;
+            let original = matches
+                .inner
+                .iter()
+                .filter(|m| m.model.uuid != model.uuid)
+                .filter(|m| {
+                    // the later of two models uploaded together is the duplicate
+                    uploaded
+                        .iter()
+                        .position(|u| u.uuid == m.model.uuid)
+                        .is_none_or(|position| position < index)
+                })
+                .max_by(|a, b| {
+                    a.percentage
+                        .partial_cmp(&b.percentage)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+            if let Some(original) = original {
+                let deleted = delete
+                    && match self.delete_model(&model.uuid) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            warn!("Failed to delete duplicate model {}: {}", model.uuid, e);
+                            false
+                        }
+                    };
+                duplicates.push(DuplicateUpload {
+                    uuid: model.uuid,
+                    name: model.name.to_owned(),
+                    duplicate_of: original.model.uuid,
+                    duplicate_name: original.model.name.to_owned(),
+                    duplicate_folder: folders
+                        .get_folder_by_id(&original.model.folder_id)
+                        .map(|folder| folder.name.to_owned()),
+                    percentage: original.percentage,
+                    deleted,
+                });
+            }
+        }
+
+        Ok(ListOfDuplicateUploads::new(duplicates))
+    }
+
+    fn read_exported_models(path: &Path) -> Result<Vec<Model>, ApiError> {
+        let mut reader = csv::Reader::from_path(path)?;
+        let mut models: Vec<Model> = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |index: usize| snapshot_field(path, &record, index);
+            models.push(Model {
+                uuid: Uuid::parse_str(field(0)?)?,
+                name: field(1)?.to_owned(),
+                folder_id: field(2)?.parse::<u32>().unwrap_or_default(),
+                folder_name: Some(field(3)?.to_owned()),
+                is_assembly: field(4)?.eq_ignore_ascii_case("true"),
+                file_type: field(5)?.to_owned(),
+                units: field(6)?.to_owned(),
+                state: field(7)?.to_owned(),
+                owner_id: field(8)?.to_owned(),
+                ..Default::default()
+            });
+        }
+        Ok(models)
+    }
+
+    /// Sets metadata properties for a model. Property names that do not exist in the tenant yet are registered
+    ///
+    /// Parameters:
+    ///
+    /// uuid - the model UUID
+    /// metadata - the properties to be set
+    pub fn set_model_metadata(
+        &self,
+        uuid: &Uuid,
+        metadata: &ModelMetadata,
+    ) -> Result<(), ApiError> {
         let properties = self.list_all_properties()?;
-        let all_props = Rc::new(properties.properties.clone());
         let mut reverse_lookup: HashMap<UniCase<String>, u64> = properties
             .properties
             .iter()
             .map(|p| (UniCase::new(p.name.to_owned()), p.id))
             .collect();
 
-        let mut uuids: Vec<Uuid> = Vec::new();
+        for item in metadata.properties.iter() {
+            let case_insensitive_name: UniCase<String> = UniCase::new(item.name.to_owned());
+            let id = match reverse_lookup.get(&case_insensitive_name) {
+                Some(id) => *id,
+                None => {
+                    let p = self
+                        .runtime
+                        .block_on(self.client.post_property(&item.name))?;
+                    reverse_lookup.insert(case_insensitive_name, p.id);
+                    p.id
+                }
+            };
 
-        let mut rdr = csv::Reader::from_reader(input_file);
-        for record in rdr.records() {
-            let (id, property) = match record {
-                Ok(record) => {
-                    let m: ModelMetadataItemShort = record.deserialize(None)?;
+            trace!(
+                "Set property '{}'='{}' for model {}",
+                &item.name,
+                &item.value,
+                uuid
+            );
+            let item = ModelMetadataItem::new(id, item.name.to_owned(), item.value.to_owned());
+            self.runtime
+                .block_on(self.client.put_model_property(uuid, &id, &item))?;
+        }
 
-                    if clean && !uuids.contains(&m.model_uuid) {
-                        trace!(
-                            "Deleting all properties for model {}...",
-                            m.model_uuid.to_string()
-                        );
+        self.forget_model(uuid);
+        Ok(())
+    }
 
-                        for property in all_props.clone().iter() {
-                            let _ = self
-                                .client
-                                .delete_model_property(&m.model_uuid, &property.id);
-                        }
-                        uuids.push(m.model_uuid.clone());
+    /// Moves the values of a metadata property to another property on every model in the tenant, e.g. to merge
+    /// "MATERIAL" into "Material". The names are matched exactly, and the target property is registered if it does
+    /// not exist yet. The source property is deleted once no model carries it anymore
+    ///
+    /// Parameters:
+    ///
+    /// from - the name of the source property
+    /// into - the name of the target property
+    /// policy - how to resolve a model with a different value for the target property
+    /// dry_run - only reports what would be done
+    pub fn merge_properties(
+        &self,
+        from: &str,
+        into: &str,
+        policy: MergeConflictPolicy,
+        dry_run: bool,
+    ) -> Result<ListOfPropertyMerges, ApiError> {
+        let properties = self.list_all_properties()?.properties;
+        let source = match properties.iter().find(|p| p.name == from) {
+            Some(source) => source.to_owned(),
+            None => return Err(ApiError::PropertyNotFound(from.to_owned())),
+        };
+        let target_id = match properties.iter().find(|p| p.name == into) {
+            Some(target) if target.id == source.id => return Ok(ListOfPropertyMerges::default()),
+            Some(target) => Some(target.id),
+            None => None,
+        };
+
+        let models = self.list_all_models(None, None)?.models;
+        let uuids: Vec<Uuid> = models.iter().map(|model| model.uuid).collect();
+        let mut metadata = self.runtime.block_on(self.get_metadata_of_models(&uuids))?;
+
+        let mut target_id = target_id;
+        let mut merges = Vec::new();
+        for model in models {
+            let items = match metadata.remove(&model.uuid).flatten() {
+                Some(metadata) => metadata.properties,
+                None => continue,
+            };
+            let source_value = match items.iter().find(|item| item.key_id == source.id) {
+                Some(item) => item.value.to_owned(),
+                None => continue,
+            };
+            let target_value = items
+                .iter()
+                .find(|item| Some(item.key_id) == target_id)
+                .map(|item| item.value.to_owned());
+
+            let action = match &target_value {
+                None => PropertyMergeAction::Moved,
+                Some(value) if *value == source_value => PropertyMergeAction::Moved,
+                Some(_) => match policy {
+                    MergeConflictPolicy::KeepTarget => PropertyMergeAction::KeptTarget,
+                    MergeConflictPolicy::UseSource => PropertyMergeAction::Replaced,
+                    MergeConflictPolicy::Skip => PropertyMergeAction::Skipped,
+                },
+            };
+
+            let mut merge = PropertyMerge {
+                uuid: model.uuid,
+                name: model.name,
+                source_value,
+                target_value,
+                action,
+                error: None,
+            };
+            if !dry_run && action != PropertyMergeAction::Skipped {
+                trace!("Merging property '{}' of model {}...", from, merge.uuid);
+                if let Err(e) = self.merge_property(&merge, &source, into, &mut target_id) {
+                    warn!(
+                        "Failed to merge property '{}' of model {}: {}",
+                        from, merge.uuid, e
+                    );
+                    merge.error = Some(e.to_string());
+                }
+            }
+            merges.push(merge);
+        }
+
+        let mut report = ListOfPropertyMerges::new(merges);
+        let complete = report
+            .merges
+            .iter()
+            .all(|merge| merge.error.is_none() && merge.action != PropertyMergeAction::Skipped);
+        if !dry_run && complete {
+            trace!("Deleting property '{}'...", from);
+            self.runtime
+                .block_on(self.client.delete_property(&source.id))?;
+            report.source_deleted = true;
+        } else if !dry_run {
+            warn!(
+                "Some models still have property '{}'. It was not deleted",
+                from
+            );
+        }
+        Ok(report)
+    }
+
+    /// Sets the target property of one model, unless its value is kept, and removes the source property. The target
+    /// property is registered the first time it is needed
+    fn merge_property(
+        &self,
+        merge: &PropertyMerge,
+        source: &Property,
+        into: &str,
+        target_id: &mut Option<u64>,
+    ) -> Result<(), ApiError> {
+        if merge.action != PropertyMergeAction::KeptTarget
+            && merge.target_value.as_ref() != Some(&merge.source_value)
+        {
+            let id = match target_id {
+                Some(id) => *id,
+                None => {
+                    let id = self.set_property(into)?.id;
+                    *target_id = Some(id);
+                    id
+                }
+            };
+            let item = ModelMetadataItem::new(id, into.to_owned(), merge.source_value.to_owned());
+            self.set_model_property(&merge.uuid, &id, &item)?;
+        }
+        self.delete_model_metadata_property(&merge.uuid, &source.id)
+    }
+
+    /// Finds the folders, models and properties that "cleanup" can delete. Models without a valid creation time are
+    /// never reported as failed, because their age is unknown
+    ///
+    /// Parameters:
+    ///
+    /// empty_folders - reports the folders without models
+    /// failed_before - reports the models that failed to process and were created before this time
+    /// unused_properties - reports the metadata properties that no model carries. This requires reading the metadata
+    /// of all models
+    pub fn find_cleanup_items(
+        &self,
+        empty_folders: bool,
+        failed_before: Option<DateTime<Utc>>,
+        unused_properties: bool,
+    ) -> Result<ListOfCleanupItems, ApiError> {
+        let mut items = Vec::new();
+
+        if empty_folders {
+            trace!("Looking for empty folders...");
+            let counts = self.get_list_of_folder_counts(None, false)?;
+            items.extend(
+                counts
+                    .folders
+                    .into_iter()
+                    .filter(|folder| folder.models == 0)
+                    .map(|folder| {
+                        CleanupItem::new(
+                            CleanupKind::EmptyFolder,
+                            folder.id.to_string(),
+                            folder.name,
+                        )
+                    }),
+            );
+        }
+
+        if failed_before.is_none() && !unused_properties {
+            return Ok(ListOfCleanupItems::new(items));
+        }
+        let models = self.list_all_models(None, None)?.models;
+
+        if let Some(failed_before) = failed_before {
+            trace!("Looking for models that failed before {}...", failed_before);
+            for model in models.iter().filter(|model| model.is_failed()) {
+                if model
+                    .created()
+                    .is_some_and(|created| created < failed_before)
+                {
+                    let mut item = CleanupItem::new(
+                        CleanupKind::FailedModel,
+                        model.uuid.to_string(),
+                        model.name.to_owned(),
+                    );
+                    item.created_at = Some(model.created_at.to_owned());
+                    items.push(item);
+                }
+            }
+        }
+
+        if unused_properties {
+            trace!("Looking for unused properties...");
+            let properties = self.list_all_properties()?.properties;
+            let uuids: Vec<Uuid> = models.iter().map(|model| model.uuid).collect();
+            let metadata = self.runtime.block_on(self.get_metadata_of_models(&uuids))?;
+            let used: HashSet<u64> = metadata
+                .into_values()
+                .flatten()
+                .flat_map(|metadata| metadata.properties)
+                .map(|item| item.key_id)
+                .collect();
+            items.extend(
+                properties
+                    .into_iter()
+                    .filter(|property| !used.contains(&property.id))
+                    .map(|property| {
+                        CleanupItem::new(
+                            CleanupKind::UnusedProperty,
+                            property.id.to_string(),
+                            property.name,
+                        )
+                    }),
+            );
+        }
+
+        Ok(ListOfCleanupItems::new(items))
+    }
+
+    /// Deletes the items found by [`Api::find_cleanup_items`]. The models are deleted first. An item that cannot be
+    /// deleted does not stop the others, and its error is reported
+    pub fn cleanup(&self, report: &mut ListOfCleanupItems) {
+        for kind in [
+            CleanupKind::FailedModel,
+            CleanupKind::EmptyFolder,
+            CleanupKind::UnusedProperty,
+        ] {
+            for item in report.items.iter_mut().filter(|item| item.kind == kind) {
+                trace!("Deleting {} {}...", kind.as_str(), item.id);
+                match self.delete_cleanup_item(item) {
+                    Ok(()) => item.deleted = true,
+                    Err(e) => {
+                        warn!("Failed to delete {} {}: {}", kind.as_str(), item.id, e);
+                        item.error = Some(e.to_string());
                     }
+                }
+            }
+        }
+    }
 
-                    let case_insensitive_name: UniCase<String> = UniCase::new(m.name.to_owned());
-                    match reverse_lookup.get(&case_insensitive_name) {
-                        Some(id) => (*id, m.to_item(*id)),
-                        None => {
-                            let p = self.client.post_property(&m.name)?;
-                            reverse_lookup.insert(case_insensitive_name.clone(), p.id);
-                            (p.id, m.to_item(p.id))
+    fn delete_cleanup_item(&self, item: &CleanupItem) -> Result<(), ApiError> {
+        match item.kind {
+            CleanupKind::FailedModel => {
+                let uuid = Uuid::parse_str(&item.id)?;
+                self.delete_model(&uuid)
+            }
+            CleanupKind::EmptyFolder => {
+                let id: u32 = item
+                    .id
+                    .parse()
+                    .map_err(|_| ApiError::FolderNotFound(item.id.to_owned()))?;
+                self.runtime
+                    .block_on(self.client.delete_folder(&HashSet::from([id])))?;
+                self.forget_folders();
+                Ok(())
+            }
+            CleanupKind::UnusedProperty => {
+                let id: u64 = item
+                    .id
+                    .parse()
+                    .map_err(|_| ApiError::PropertyNotFound(item.id.to_owned()))?;
+                Ok(self.runtime.block_on(self.client.delete_property(&id))?)
+            }
+        }
+    }
+
+    pub fn list_all_properties(&self) -> Result<PropertyCollection, ApiError> {
+        trace!("Listing all properties...");
+        Ok(self
+            .runtime
+            .block_on(self.client.get_list_of_properties())?)
+    }
+
+    /// Uploads the metadata read from a file with the columns modelId, name and value. An empty value deletes the
+    /// property. A row that cannot be read or applied does not stop the others, and the outcome of every row is
+    /// reported
+    ///
+    /// Parameters:
+    ///
+    /// rows - the rows of the metadata file
+    /// mode - how to treat the properties the models already have
+    /// dry_run - only compares the input with the current metadata and reports what would change
+    /// progress - receives the number of changes sent so far and the number of changes to send
+    pub fn upload_model_metadata(
+        &self,
+        rows: Vec<MetadataRow>,
+        mode: MetadataImportMode,
+        dry_run: bool,
+        progress: &dyn Fn(usize, usize),
+    ) -> Result<ListOfMetadataImportResults, ApiError> {
+        // Get all properties and cache them. The Physna API V2 does not allow me to get property by name
+        let properties = self.list_all_properties()?;
+        let mut reverse_lookup: HashMap<UniCase<String>, u64> = properties
+            .properties
+            .iter()
+            .map(|p| (UniCase::new(p.name.to_owned()), p.id))
+            .collect();
+
+        // the current metadata tells which properties are created, updated or left as they are
+        let mut uuids: Vec<Uuid> = rows
+            .iter()
+            .filter_map(|(_, item)| item.as_ref().ok().map(|item| item.model_uuid))
+            .collect();
+        uuids.sort();
+        uuids.dedup();
+        let requests = uuids.iter().map(|uuid| async move {
+            let metadata = self.client.get_model_metadata(uuid).await;
+            (*uuid, metadata)
+        });
+        let mut current: HashMap<Uuid, Result<Vec<ModelMetadataItem>, String>> = self
+            .runtime
+            .block_on(join_all(requests))
+            .into_iter()
+            .map(|(uuid, metadata)| {
+                let properties = metadata
+                    .map(|metadata| metadata.map(|m| m.properties).unwrap_or_default())
+                    .map_err(|e| e.to_string());
+                (uuid, properties)
+            })
+            .collect();
+        let original = current.clone();
+        let mut imported: HashSet<(Uuid, UniCase<String>)> = HashSet::new();
+
+        // the changes are planned first, with the index of the result each one belongs to
+        let mut results = Vec::new();
+        let mut changes: Vec<(usize, MetadataChange)> = Vec::new();
+        for (row, item) in rows {
+            let item = match item {
+                Ok(item) => item,
+                Err(reason) => {
+                    results.push(MetadataImportResult {
+                        row: Some(row),
+                        uuid: None,
+                        name: String::default(),
+                        value: String::default(),
+                        previous_value: None,
+                        status: MetadataImportStatus::Error,
+                        reason: Some(reason),
+                    });
+                    continue;
+                }
+            };
+            imported.insert((item.model_uuid, UniCase::new(item.name.trim().to_owned())));
+
+            let mut result = MetadataImportResult {
+                row: Some(row),
+                uuid: Some(item.model_uuid),
+                name: item.name.to_owned(),
+                value: item.value.to_owned(),
+                previous_value: None,
+                status: MetadataImportStatus::Skipped,
+                reason: None,
+            };
+            let properties = match current.get_mut(&item.model_uuid) {
+                Some(Ok(properties)) => properties,
+                Some(Err(reason)) => {
+                    result.status = MetadataImportStatus::Error;
+                    result.reason = Some(reason.to_owned());
+                    results.push(result);
+                    continue;
+                }
+                None => continue,
+            };
+            let position = properties
+                .iter()
+                .position(|p| p.name.trim().eq_ignore_ascii_case(item.name.trim()));
+            result.previous_value = position.map(|i| properties[i].value.to_owned());
+            let (status, reason) =
+                Self::import_status(mode, &item.value, result.previous_value.as_deref());
+            result.status = status;
+            result.reason = reason.map(String::from);
+
+            trace!(
+                "Row {}: property '{}'='{}' for model {} is {}",
+                row,
+                &item.name,
+                &item.value,
+                &item.model_uuid,
+                status.as_str()
+            );
+            match (status, position) {
+                (MetadataImportStatus::Deleted, Some(i)) => {
+                    let property = properties.remove(i);
+                    changes.push((
+                        results.len(),
+                        MetadataChange::Delete(item.model_uuid, property.key_id),
+                    ));
+                }
+                (MetadataImportStatus::Created, _) | (MetadataImportStatus::Updated, _) => {
+                    // new properties are not registered in a dry run
+                    let id = if dry_run {
+                        Ok(0)
+                    } else {
+                        self.property_id(&item.name, &mut reverse_lookup)
+                    };
+                    match id {
+                        Ok(id) => {
+                            let property =
+                                ModelMetadataItem::new(id, item.name.to_owned(), item.value);
+                            changes.push((
+                                results.len(),
+                                MetadataChange::Set(item.model_uuid, id, property.clone()),
+                            ));
+                            match position {
+                                Some(i) => properties[i] = property,
+                                None => properties.push(property),
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Failed to import row {}: {}", row, e);
+                            result.status = MetadataImportStatus::Error;
+                            result.reason = Some(e.to_string());
                         }
                     }
                 }
-                Err(e) => return Err(ApiError::FailedToRead(e.to_string())),
-            };
+                _ => (),
+            }
+            results.push(result);
+        }
 
-            if property.value.is_empty() {
-                self.client
-                    .delete_model_property(&property.model_uuid, &id)?;
-            } else {
-                trace!(
-                    "Set property '{}'='{}' for model {}",
-                    &property.name.to_owned(),
-                    &property.value.to_owned(),
-                    &property.model_uuid
-                );
-                self.client
-                    .put_model_property(&property.model_uuid, &id, &property.to_item())?;
+        // in replace mode, the input holds all the metadata of the models
+        if mode == MetadataImportMode::Replace {
+            for uuid in uuids.iter() {
+                let properties = match original.get(uuid) {
+                    Some(Ok(properties)) => properties,
+                    _ => continue,
+                };
+                for property in properties.iter().filter(|p| {
+                    !imported.contains(&(*uuid, UniCase::new(p.name.trim().to_owned())))
+                }) {
+                    changes.push((
+                        results.len(),
+                        MetadataChange::Delete(*uuid, property.key_id),
+                    ));
+                    results.push(MetadataImportResult {
+                        row: None,
+                        uuid: Some(*uuid),
+                        name: property.name.to_owned(),
+                        value: String::default(),
+                        previous_value: Some(property.value.to_owned()),
+                        status: MetadataImportStatus::Deleted,
+                        reason: Some("not in the input".to_string()),
+                    });
+                }
             }
         }
 
-        Ok(())
+        if !dry_run {
+            for (index, e) in self.apply_metadata_changes(changes, progress) {
+                let result = &mut results[index];
+                match result.row {
+                    Some(row) => warn!("Failed to import row {}: {}", row, e),
+                    None => warn!(
+                        "Failed to delete property '{}' of model {}: {}",
+                        result.name,
+                        result.uuid.map(|uuid| uuid.to_string()).unwrap_or_default(),
+                        e
+                    ),
+                }
+                result.status = MetadataImportStatus::Error;
+                result.reason = Some(e.to_string());
+            }
+        }
+
+        Ok(ListOfMetadataImportResults::new(results))
+    }
+
+    /// Decides what to do with a property of a model, given its new and its current value
+    fn import_status(
+        mode: MetadataImportMode,
+        value: &str,
+        previous: Option<&str>,
+    ) -> (MetadataImportStatus, Option<&'static str>) {
+        match (value.is_empty(), previous) {
+            (true, None) => (MetadataImportStatus::Skipped, Some("not set")),
+            (true, Some(_)) if mode == MetadataImportMode::AddOnly => (
+                MetadataImportStatus::Skipped,
+                Some("add-only mode does not delete"),
+            ),
+            (true, Some(_)) => (MetadataImportStatus::Deleted, None),
+            (false, None) => (MetadataImportStatus::Created, None),
+            (false, Some(previous)) if previous == value => {
+                (MetadataImportStatus::Skipped, Some("unchanged"))
+            }
+            (false, Some(_)) if mode == MetadataImportMode::AddOnly => {
+                (MetadataImportStatus::Skipped, Some("already set"))
+            }
+            (false, Some(_)) => (MetadataImportStatus::Updated, None),
+        }
+    }
+
+    /// Returns the ID of the property, registering the property if it does not exist yet
+    fn property_id(
+        &self,
+        name: &str,
+        reverse_lookup: &mut HashMap<UniCase<String>, u64>,
+    ) -> Result<u64, ApiError> {
+        let case_insensitive_name = UniCase::new(name.to_owned());
+        if let Some(id) = reverse_lookup.get(&case_insensitive_name) {
+            return Ok(*id);
+        }
+        let p = self.runtime.block_on(self.client.post_property(name))?;
+        reverse_lookup.insert(case_insensitive_name, p.id);
+        Ok(p.id)
+    }
+
+    /// Sends the changes concurrently, bounded by the concurrency limit of the client, which also retries transient
+    /// failures. The changes of the same property of a model are sent one after the other, in their order. Returns
+    /// the index and the error of every change that failed
+    fn apply_metadata_changes(
+        &self,
+        changes: Vec<(usize, MetadataChange)>,
+        progress: &dyn Fn(usize, usize),
+    ) -> Vec<(usize, ApiError)> {
+        let total = changes.len();
+        let mut sequences: HashMap<(Uuid, u64), Vec<(usize, MetadataChange)>> = HashMap::new();
+        let mut models: HashSet<Uuid> = HashSet::new();
+        for (index, change) in changes {
+            let key = match &change {
+                MetadataChange::Set(uuid, id, _) | MetadataChange::Delete(uuid, id) => (*uuid, *id),
+            };
+            models.insert(key.0);
+            sequences.entry(key).or_default().push((index, change));
+        }
+
+        let done = Cell::new(0);
+        let requests = sequences.into_values().map(|sequence| {
+            let done = &done;
+            async move {
+                let mut failures = Vec::new();
+                for (index, change) in sequence {
+                    let outcome = match &change {
+                        MetadataChange::Set(uuid, id, property) => self
+                            .client
+                            .put_model_property(uuid, id, property)
+                            .await
+                            .map(|_| ()),
+                        MetadataChange::Delete(uuid, id) => {
+                            self.client.delete_model_property(uuid, id).await
+                        }
+                    };
+                    if let Err(e) = outcome {
+                        failures.push((index, ApiError::from(e)));
+                    }
+                    done.set(done.get() + 1);
+                    progress(done.get(), total);
+                }
+                failures
+            }
+        });
+        let mut failures: Vec<(usize, ApiError)> = self
+            .runtime
+            .block_on(join_all(requests))
+            .into_iter()
+            .flatten()
+            .collect();
+        failures.sort_by_key(|(index, _)| *index);
+
+        for uuid in models.iter() {
+            self.forget_model(uuid);
+        }
+        failures
     }
 
     pub fn search_by_multiple_images(
@@ -927,46 +3451,67 @@ impl Api {
         let mut upload_ids: Vec<String> = Vec::new();
         for path in paths {
             let path = path.as_path();
-            let image_upload = self.client.get_image_upload_specs(&path)?;
-            let url = Url::parse(image_upload.upload_url.as_str()).unwrap();
+            let image_upload = self
+                .runtime
+                .block_on(self.client.get_image_upload_specs(path))?;
+            let url = Url::parse(image_upload.upload_url.as_str())?;
             let size_requirements = image_upload.file_size_requirements;
             let mime = image_upload.headers.content_type;
             let content_range = image_upload.headers.content_length_range;
             let id = image_upload.id;
             upload_ids.push(id.to_owned());
 
-            self.client
-                .upload_image_file(url, size_requirements, &path, mime, content_range)?;
+            self.runtime.block_on(self.client.upload_image_file(
+                url,
+                size_requirements,
+                path,
+                mime,
+                content_range,
+            ))?;
         }
 
-        let matches =
-            self.client
-                .get_image_search_maches(upload_ids, search, filter, max_results, 100)?;
+        let matches = self.runtime.block_on(self.client.get_image_search_maches(
+            upload_ids,
+            search,
+            filter,
+            max_results,
+            self.page_size(100),
+        ))?;
 
         Ok(matches)
     }
 
     pub fn search_by_image(
         &self,
-        path: &PathBuf,
+        path: &Path,
         max_results: u32,
         search: Option<&String>,
         filter: Option<&String>,
     ) -> Result<ListOfModels, ApiError> {
-        let path = path.as_path();
-        let image_upload = self.client.get_image_upload_specs(&path)?;
-        let url = Url::parse(image_upload.upload_url.as_str()).unwrap();
+        let image_upload = self
+            .runtime
+            .block_on(self.client.get_image_upload_specs(path))?;
+        let url = Url::parse(image_upload.upload_url.as_str())?;
         let size_requirements = image_upload.file_size_requirements;
         let mime = image_upload.headers.content_type;
         let content_range = image_upload.headers.content_length_range;
         let id = image_upload.id;
 
-        self.client
-            .upload_image_file(url, size_requirements, &path, mime, content_range)?;
-
-        let matches =
-            self.client
-                .get_image_search_maches(vec![id], search, filter, max_results, 100)?;
+        self.runtime.block_on(self.client.upload_image_file(
+            url,
+            size_requirements,
+            path,
+            mime,
+            content_range,
+        ))?;
+
+        let matches = self.runtime.block_on(self.client.get_image_search_maches(
+            vec![id],
+            search,
+            filter,
+            max_results,
+            self.page_size(100),
+        ))?;
 
         Ok(matches)
     }
@@ -974,15 +3519,14 @@ impl Api {
     pub fn label_inference(
         &mut self,
         uuid: &Uuid,
-        threshold: f64, // Changed from &f64 to f64 for simplicity
-        keys: &Option<Vec<String>>,
+        options: &InferenceOptions,
         cascade: bool,
         apply: bool,
-        folders: &Option<HashSet<String>>,
     ) -> Result<ListOfMatchedMetadataItems, ApiError> {
-        let matches = self.match_model(uuid, threshold, true, false, None, None)?;
+        let matches = self.match_model(uuid, options.threshold, true, false, None, None)?;
+        let keys = &options.keys;
 
-        let existing_folders = self.get_list_of_folders(folders.clone())?;
+        let existing_folders = self.get_list_of_folders(options.folders.clone())?;
 
         // retrieve the list of valid folders. If no filter explicitly specified, all existing folders
         let existing_folders: HashMap<u32, String> = existing_folders
@@ -1001,82 +3545,788 @@ impl Api {
         let mut props: HashMap<String, MatchedMetadataItem> = HashMap::new();
 
         // populate with the original values, so that they do not get overrided
-        match self.get_model_metadata(uuid)? {
-            Some(original_metadata) => {
-                for p in original_metadata.properties.iter() {
-                    let name = p.to_owned().name;
-                    props.insert(
+        if let Some(original_metadata) = self.get_model_metadata(uuid)? {
+            for p in original_metadata.properties.iter() {
+                let name = p.to_owned().name;
+                props.insert(
+                    name.to_owned(),
+                    MatchedMetadataItem::new(
+                        uuid.to_owned(),
                         name.to_owned(),
-                        MatchedMetadataItem::new(
-                            uuid.to_owned(),
-                            name.to_owned(),
-                            p.value.to_owned(),
-                            1.0,
-                        ),
-                    );
-                }
+                        p.value.to_owned(),
+                        1.0,
+                    ),
+                );
             }
-            None => (),
         };
 
         for m in matches.into_iter() {
-            if cascade && m.model.is_assembly {
-                let uuid = m.model.uuid.to_owned();
-                let tree = self.get_model_assembly_tree(&uuid)?;
-
-                match tree.children {
-                    Some(children) => {
-                        for child in children.into_iter() {
-                            let uuid = child.model.uuid;
-                            let partial_result = self
-                                .label_inference(&uuid, threshold, keys, cascade, false, folders)?;
-                            let _partial_props = partial_result.items;
-                        }
-                    }
-                    None => (),
-                }
-            }
-
             let score = m.percentage;
             let folder_id = m.model.folder_id;
 
-            if existing_folders.get(&folder_id).is_some() {
+            // values supported only by low-score matches are not inferred
+            if options
+                .min_confidence
+                .is_some_and(|min_confidence| score < min_confidence)
+            {
+                continue;
+            }
+
+            if existing_folders.contains_key(&folder_id) {
                 // the model belongs to a folder that is in the whitelist
 
                 if let Some(ref metadata) = m.model.metadata {
                     for p in metadata {
                         let name = &p.name;
-                        if keys.as_ref().map_or(true, |k| k.contains(name)) {
-                            if props.get(name).is_none() {
-                                let property = MatchedMetadataItem::new(
-                                    uuid.to_owned(),
-                                    name.clone(),
-                                    p.value.clone(),
-                                    score,
-                                );
-                                props.insert(name.clone(), property);
-                            }
+                        if keys.as_ref().is_none_or(|k| k.contains(name))
+                            && !props.contains_key(name)
+                        {
+                            let property = MatchedMetadataItem::new(
+                                uuid.to_owned(),
+                                name.clone(),
+                                p.value.clone(),
+                                score,
+                            );
+                            props.insert(name.clone(), property);
                         }
                     }
                 }
             }
         }
 
-        let result = ListOfMatchedMetadataItems::new(props.into_values().collect());
+        let mut result = ListOfMatchedMetadataItems::new(props.into_values().collect());
+        if let Some(keys) = keys {
+            result.unresolved = keys
+                .iter()
+                .filter(|k| !result.items.iter().any(|item| &item.name == *k))
+                .cloned()
+                .collect();
+        }
 
         if apply {
             trace!("Applying infered metadata...");
 
             // add the infered properties automatically
-            let mut file = tempfile()?;
-            let output = format_list_of_matched_properties(&result, &Format::Csv, true, None)?;
-            file.write_all(output.as_bytes())?;
-            file.flush()?;
-            file.seek(SeekFrom::Start(0))?;
+            let output = result.to_csv(true)?;
+            let columns = MetadataColumns {
+                uuid: "uuid".to_string(),
+                ..Default::default()
+            };
+            let rows = metadata_file::read_csv(output.as_bytes(), &columns);
+            let results =
+                self.upload_model_metadata(rows, MetadataImportMode::Upsert, false, &|_, _| ())?;
+            if let Some(failed) = results
+                .results
+                .iter()
+                .find(|result| result.status == MetadataImportStatus::Error)
+            {
+                return Err(ApiError::FailedToRead(
+                    failed.reason.to_owned().unwrap_or_default(),
+                ));
+            }
+        }
 
-            self.upload_model_metadata(&file, false)?;
+        if cascade {
+            let reference_model = self.get_model(uuid, true, false)?;
+            if reference_model.is_assembly {
+                let tree = self.get_model_assembly_tree(uuid, None)?;
+                let mut descendants: Vec<Uuid> = Vec::new();
+                Self::collect_descendants(&tree, &mut descendants);
+
+                for child_uuid in descendants {
+                    trace!("Inferring metadata for child model {}...", child_uuid);
+                    let child_result = self.label_inference(&child_uuid, options, false, apply)?;
+                    result.children.insert(child_uuid, child_result);
+                }
+            }
         }
 
         Ok(result)
     }
+
+    /// Collects the UUIDs of all sub-assemblies and parts in the assembly tree. Each model is listed only once
+    fn collect_descendants(tree: &ModelAssemblyTree, descendants: &mut Vec<Uuid>) {
+        if let Some(children) = &tree.children {
+            for child in children {
+                if !descendants.contains(&child.model.uuid) {
+                    descendants.push(child.model.uuid);
+                }
+                Self::collect_descendants(child, descendants);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::fake::FakeClient;
+    use crate::model::ListOfAssemblyTreeRows;
+
+    fn api(client: FakeClient) -> Api {
+        Api::with_client(Box::new(client), None).unwrap()
+    }
+
+    fn names(values: &[&str]) -> HashSet<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_folders() {
+        let api = api(FakeClient::new());
+        let existing = ListOfFolders::from(vec![
+            Folder::new(1, "parts".to_string()),
+            Folder::new(2, "archive".to_string()),
+        ]);
+
+        let validated = api.validate_folders(&existing, &names(&["parts"])).unwrap();
+        assert_eq!(validated.folders, vec![Folder::new(1, "parts".to_string())]);
+
+        let validated = api.validate_folders(&existing, &HashSet::new()).unwrap();
+        assert_eq!(validated.folders.len(), 2);
+
+        match api.validate_folders(&existing, &names(&["parts", "missing"])) {
+            Err(ApiError::FolderNotFound(name)) => assert_eq!(name, "missing"),
+            other => panic!("unexpected result: {:?}", other.map(|f| f.folders)),
+        }
+
+        let validated = api
+            .validate_folders(&existing, &names(&["p*", "archive"]))
+            .unwrap();
+        assert_eq!(validated.folders.len(), 2);
+
+        match api.validate_folders(&existing, &names(&["PROJ-*"])) {
+            Err(ApiError::FolderNotFound(name)) => assert_eq!(name, "PROJ-*"),
+            other => panic!("unexpected result: {:?}", other.map(|f| f.folders)),
+        }
+    }
+
+    #[test]
+    fn test_simple_model_match_report() {
+        let (reference, copy, same_name, elsewhere, pending) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_folder(2, "archive")
+            .with_model(reference, "bracket", 1, "finished")
+            .with_model(copy, "bracket-v2", 1, "finished")
+            .with_model(same_name, "bracket", 1, "finished")
+            .with_model(elsewhere, "bracket-old", 2, "finished")
+            .with_model(pending, "plate", 1, "processing")
+            .with_match(&reference, &copy, 0.97)
+            .with_match(&reference, &same_name, 0.99)
+            .with_match(&reference, &elsewhere, 0.96)
+            .with_match(&pending, &copy, 0.99);
+        let mut api = api(client);
+
+        let report = api
+            .generate_simple_model_match_report(
+                vec![reference, pending],
+                &0.95,
+                Some(names(&["parts"])),
+                true,
+                false,
+                None,
+            )
+            .unwrap();
+
+        // models with the same name are not duplicates, matches outside the folders are excluded and models
+        // that are not processed yet are skipped
+        assert_eq!(report.inner.len(), 1);
+        let item = &report.inner[&reference.to_string()];
+        assert_eq!(item.folder_name, "parts");
+        assert_eq!(item.matches.len(), 1);
+        let m = &item.matches[0];
+        assert_eq!(m.model.uuid, copy);
+        assert_eq!(m.model.folder_name.as_deref(), Some("parts"));
+        assert_eq!(
+            m.comparison_url.as_deref(),
+            Some(
+                format!(
+                    "https://fake.physna.com/app/compare?modelAId={}&modelBId={}",
+                    reference, copy
+                )
+                .as_str()
+            )
+        );
+
+        api.web_url = Some("https://physna.example.com/".to_string());
+        assert_eq!(
+            api.model_url(&copy),
+            format!("https://physna.example.com/app/models/{}", copy)
+        );
+    }
+
+    #[test]
+    fn test_label_inference() {
+        let (reference, close, distant, elsewhere) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_folder(2, "archive")
+            .with_model(reference, "bracket", 1, "finished")
+            .with_model(close, "bracket-v2", 1, "finished")
+            .with_model(distant, "bracket-v3", 1, "finished")
+            .with_model(elsewhere, "bracket-old", 2, "finished")
+            .with_metadata(&reference, "material", "steel")
+            .with_metadata(&close, "material", "aluminum")
+            .with_metadata(&close, "vendor", "acme")
+            .with_metadata(&distant, "finish", "anodized")
+            .with_metadata(&elsewhere, "color", "red")
+            .with_match(&reference, &close, 0.98)
+            .with_match(&reference, &distant, 0.90)
+            .with_match(&reference, &elsewhere, 0.99);
+        let mut api = api(client);
+
+        let result = api
+            .label_inference(
+                &reference,
+                &InferenceOptions {
+                    threshold: 0.8,
+                    min_confidence: Some(0.95),
+                    keys: Some(vec![
+                        "material".to_string(),
+                        "vendor".to_string(),
+                        "finish".to_string(),
+                        "color".to_string(),
+                    ]),
+                    folders: Some(names(&["parts"])),
+                },
+                false,
+                false,
+            )
+            .unwrap();
+
+        let inferred: HashMap<String, (String, f64)> = result
+            .items
+            .into_iter()
+            .map(|item| (item.name, (item.value, item.score)))
+            .collect();
+
+        // the model's own values are kept, values of low-score matches and of matches outside the folders
+        // are not inferred
+        assert_eq!(inferred.len(), 2);
+        assert_eq!(inferred["material"], ("steel".to_string(), 1.0));
+        assert_eq!(inferred["vendor"], ("acme".to_string(), 0.98));
+        assert_eq!(result.unresolved, vec!["finish", "color"]);
+    }
+
+    #[test]
+    fn test_assembly_tree_depth() {
+        let (assembly, subassembly, part, screw) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(assembly, "gearbox", 1, "finished")
+            .with_model(subassembly, "shaft", 1, "finished")
+            .with_model(part, "housing", 1, "finished")
+            .with_model(screw, "screw", 1, "finished")
+            .with_child(&assembly, &subassembly)
+            .with_child(&assembly, &part)
+            .with_child(&subassembly, &screw);
+        let mut api = api(client);
+
+        let tree = api.get_model_assembly_tree(&assembly, Some(1)).unwrap();
+        let rows: Vec<(Option<Uuid>, Uuid, u32)> = ListOfAssemblyTreeRows::from(&tree)
+            .rows
+            .into_iter()
+            .map(|row| (row.parent_uuid, row.uuid, row.level))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (None, assembly, 0),
+                (Some(assembly), subassembly, 1),
+                (Some(assembly), part, 1),
+            ]
+        );
+
+        let tree = api.get_model_assembly_tree(&assembly, None).unwrap();
+        assert_eq!(ListOfAssemblyTreeRows::from(&tree).rows[2].uuid, screw);
+    }
+
+    #[test]
+    fn test_bill_of_materials() {
+        let (assembly, subassembly, bolt, plate) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(assembly, "frame", 1, "finished")
+            .with_model(subassembly, "bracket", 1, "finished")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(plate, "plate", 1, "finished")
+            .with_child(&assembly, &subassembly)
+            .with_child(&assembly, &subassembly)
+            .with_child(&assembly, &bolt)
+            .with_child(&subassembly, &bolt)
+            .with_child(&subassembly, &plate)
+            .with_metadata(&bolt, "part number", "B-100");
+        let mut api = api(client);
+
+        let bom = api
+            .get_bill_of_materials(&assembly, &["Part Number".to_string()], true)
+            .unwrap();
+        let items: Vec<(Uuid, u32, Option<&str>)> = bom
+            .items
+            .iter()
+            .map(|item| {
+                (
+                    item.uuid,
+                    item.quantity,
+                    item.properties.get("Part Number").map(|v| v.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(items, vec![(bolt, 3, Some("B-100")), (plate, 2, None)]);
+    }
+
+    #[test]
+    fn test_rollup() {
+        let (assembly, subassembly, bolt, plate) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(assembly, "frame", 1, "finished")
+            .with_model(subassembly, "bracket", 1, "finished")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(plate, "plate", 1, "finished")
+            .with_child(&assembly, &subassembly)
+            .with_child(&assembly, &subassembly)
+            .with_child(&assembly, &bolt)
+            .with_child(&subassembly, &bolt)
+            .with_child(&subassembly, &plate)
+            .with_metadata(&bolt, "cost", "0.1")
+            .with_metadata(&plate, "cost", "2")
+            .with_metadata(&bolt, "weight", "5");
+        let mut api = api(client);
+
+        let rollup = api
+            .rollup(
+                &assembly,
+                &["cost".to_string(), "weight".to_string()],
+                &MetadataTypes::default(),
+            )
+            .unwrap();
+        let items: Vec<(Uuid, u32, f64, f64, u32)> = rollup
+            .items
+            .iter()
+            .map(|item| {
+                (
+                    item.uuid,
+                    item.quantity,
+                    item.totals["cost"],
+                    item.totals["weight"],
+                    item.missing,
+                )
+            })
+            .collect();
+        assert_eq!(
+            items,
+            vec![(assembly, 1, 4.3, 15.0, 2), (subassembly, 2, 2.1, 5.0, 1)]
+        );
+    }
+
+    #[test]
+    fn test_reuse_report() {
+        let (frame, stand, bolt, screw, plate) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_folder(2, "archive")
+            .with_model(frame, "frame", 1, "finished")
+            .with_model(stand, "stand", 1, "finished")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(screw, "screw", 2, "finished")
+            .with_model(plate, "plate", 1, "finished")
+            .with_child(&frame, &bolt)
+            .with_child(&frame, &bolt)
+            .with_child(&frame, &plate)
+            .with_child(&stand, &bolt)
+            .with_match(&bolt, &screw, 0.99)
+            .with_match(&bolt, &plate, 0.97);
+        let mut api = api(client);
+
+        let report = api
+            .generate_reuse_report(names(&["parts"]), 0.95, true)
+            .unwrap();
+        let items: Vec<(Uuid, u32, u32)> = report
+            .items
+            .iter()
+            .map(|item| (item.uuid, item.assemblies, item.duplicates))
+            .collect();
+        assert_eq!(
+            items,
+            vec![(bolt, 2, 1), (plate, 1, 0), (frame, 0, 0), (stand, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_unique_pairs() {
+        let (bolt, screw, nut) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(screw, "screw", 1, "finished")
+            .with_model(nut, "nut", 1, "finished")
+            .with_match(&bolt, &screw, 0.96)
+            .with_match(&screw, &bolt, 0.98)
+            .with_match(&nut, &bolt, 0.97);
+        let mut api = api(client);
+
+        let mut report = api
+            .generate_simple_model_match_report(
+                vec![bolt, screw, nut],
+                &0.95,
+                None,
+                false,
+                false,
+                None,
+            )
+            .unwrap();
+        report.unique_pairs();
+        let mut pairs: Vec<(String, String, f64)> = report
+            .inner
+            .values()
+            .flat_map(|item| {
+                item.matches
+                    .iter()
+                    .map(|m| (item.name.to_owned(), m.model.name.to_owned(), m.percentage))
+            })
+            .collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let (first, second) = if bolt < screw {
+            ("bolt", "screw")
+        } else {
+            ("screw", "bolt")
+        };
+        let mut expected = vec![
+            (first.to_string(), second.to_string(), 0.98),
+            ("nut".to_string(), "bolt".to_string(), 0.97),
+        ];
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_assembly_coverage() {
+        let (frame, bolt, plate, screw) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(frame, "frame", 1, "finished")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(plate, "plate", 1, "finished")
+            .with_model(screw, "screw", 1, "finished")
+            .with_child(&frame, &bolt)
+            .with_child(&frame, &bolt)
+            .with_child(&frame, &bolt)
+            .with_child(&frame, &plate)
+            .with_match(&bolt, &screw, 0.99);
+        let mut api = api(client);
+
+        let report = api
+            .generate_model_match_report(vec![frame], 0.95, false, None)
+            .unwrap();
+        assert_eq!(
+            report.coverage.items,
+            vec![AssemblyCoverage::new(frame, "frame".to_string(), 4, 3)]
+        );
+        assert_eq!(report.coverage.items[0].coverage, 75.0);
+    }
+
+    #[test]
+    fn test_merge_properties() {
+        let (bolt, nut, washer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(nut, "nut", 1, "finished")
+            .with_model(washer, "washer", 1, "finished")
+            .with_metadata(&bolt, "Material", "steel")
+            .with_metadata(&bolt, "MATERIAL", "brass")
+            .with_metadata(&nut, "MATERIAL", "ABS")
+            .with_metadata(&washer, "Material", "steel");
+        let api = api(client);
+
+        let report = api
+            .merge_properties(
+                "MATERIAL",
+                "Material",
+                MergeConflictPolicy::KeepTarget,
+                false,
+            )
+            .unwrap();
+        let mut actions: Vec<(String, PropertyMergeAction)> = report
+            .merges
+            .iter()
+            .map(|merge| (merge.name.to_owned(), merge.action))
+            .collect();
+        actions.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            actions,
+            vec![
+                ("bolt".to_string(), PropertyMergeAction::KeptTarget),
+                ("nut".to_string(), PropertyMergeAction::Moved),
+            ]
+        );
+        assert!(report.source_deleted);
+
+        let values = api
+            .read_properties(&[bolt, nut, washer], &["material".to_string()])
+            .unwrap();
+        for (uuid, value) in [(bolt, "steel"), (nut, "ABS"), (washer, "steel")] {
+            assert_eq!(
+                values[&uuid].get("material").map(|v| v.as_str()),
+                Some(value)
+            );
+        }
+        let properties: Vec<String> = api
+            .list_all_properties()
+            .unwrap()
+            .properties
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(properties, vec!["Material".to_string()]);
+    }
+
+    #[test]
+    fn test_cleanup() {
+        let (bolt, nut) = (Uuid::new_v4(), Uuid::new_v4());
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_folder(2, "empty")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(nut, "nut", 1, "failed")
+            .with_metadata(&bolt, "Material", "steel");
+        let api = api(client);
+        api.set_property("Color").unwrap();
+
+        // the failed model has no creation time, so its age is unknown
+        let mut report = api
+            .find_cleanup_items(true, Some(Utc::now()), true)
+            .unwrap();
+        let found: Vec<(CleanupKind, &str)> = report
+            .items
+            .iter()
+            .map(|item| (item.kind, item.name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (CleanupKind::EmptyFolder, "empty"),
+                (CleanupKind::UnusedProperty, "Color"),
+            ]
+        );
+
+        // the fake client cannot delete folders, which must not stop deleting the property
+        api.cleanup(&mut report);
+        assert!(report.has_failures());
+        assert!(report.items[0].error.is_some());
+        assert!(report.items[1].deleted);
+        let properties = api.list_all_properties().unwrap().properties;
+        assert!(properties.iter().all(|property| property.name != "Color"));
+    }
+
+    #[test]
+    fn test_resolve_short_ids() {
+        let (bolt, nut) = (Uuid::new_v4(), Uuid::new_v4());
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(nut, "nut", 1, "finished");
+        let api = api(client);
+
+        assert_eq!(api.resolve_short_ids(&[2, 1]).unwrap(), vec![nut, bolt]);
+        assert!(matches!(
+            api.resolve_short_ids(&[3]),
+            Err(ApiError::ShortIdNotFound(3))
+        ));
+    }
+
+    #[test]
+    fn test_find_models() {
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(Uuid::new_v4(), "m8_bracket.stl", 1, "finished")
+            .with_model(Uuid::new_v4(), "bracket_m10.stl", 1, "finished")
+            .with_model(Uuid::new_v4(), "bracket m8.stl", 1, "finished")
+            .with_model(Uuid::new_v4(), "washer_m8.stl", 1, "finished")
+            .with_model(Uuid::new_v4(), "nut.stl", 1, "finished");
+        let api = api(client);
+
+        let names: Vec<String> = api
+            .find_models("bracket m8", 3)
+            .unwrap()
+            .models
+            .into_iter()
+            .map(|model| model.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["bracket m8.stl", "m8_bracket.stl", "bracket_m10.stl"]
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        let (bracket, copy) = (Uuid::new_v4(), Uuid::new_v4());
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(bracket, "bracket.stl", 1, "finished")
+            .with_model(copy, "bracket-v2.stl", 1, "finished")
+            .with_model(Uuid::new_v4(), "nut.stl", 1, "finished")
+            .with_match(&bracket, &copy, 0.97);
+        let api = api(client);
+
+        let search = "bracket".to_string();
+        assert_eq!(api.count_models(None, Some(&search)).unwrap(), 2);
+        assert_eq!(api.count_model_matches(&bracket, 0.95).unwrap(), 1);
+        assert_eq!(api.count_model_matches(&bracket, 0.99).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_exported_models() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("models.csv");
+        let uuid = Uuid::new_v4();
+        std::fs::write(
+            &path,
+            format!("ID,NAME,FOLDER_ID\n{},bracket.stl,1\n", uuid),
+        )
+        .unwrap();
+
+        let error = Api::read_exported_models(&path).unwrap_err().to_string();
+        assert!(error.contains("line 2"), "{}", error);
+    }
+
+    #[test]
+    fn test_upload_model_metadata() {
+        let (bolt, nut) = (Uuid::new_v4(), Uuid::new_v4());
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(bolt, "bolt", 1, "finished")
+            .with_model(nut, "nut", 1, "finished")
+            .with_metadata(&bolt, "Material", "steel")
+            .with_metadata(&bolt, "Revision", "A")
+            .with_metadata(&nut, "Material", "ABS");
+        let api = api(client);
+
+        let input = format!(
+            "modelId,name,value\n{bolt},material,brass\n{bolt},Finish,zinc\nnot-a-uuid,Finish,zinc\n{nut},Material,ABS\n{nut},Revision,\n"
+        );
+        let statuses =
+            |results: ListOfMetadataImportResults| -> Vec<(Option<u64>, MetadataImportStatus)> {
+                results
+                    .results
+                    .into_iter()
+                    .map(|result| (result.row, result.status))
+                    .collect()
+            };
+
+        let results = api
+            .upload_model_metadata(
+                metadata_file::read_csv(input.as_bytes(), &MetadataColumns::default()),
+                MetadataImportMode::Replace,
+                true,
+                &|_, _| (),
+            )
+            .unwrap();
+        assert_eq!(results.count(MetadataImportStatus::Deleted), 1);
+        assert_eq!(
+            api.get_model_metadata(&bolt)
+                .unwrap()
+                .unwrap()
+                .properties
+                .len(),
+            2
+        );
+
+        let results = api
+            .upload_model_metadata(
+                metadata_file::read_csv(input.as_bytes(), &MetadataColumns::default()),
+                MetadataImportMode::AddOnly,
+                false,
+                &|_, _| (),
+            )
+            .unwrap();
+        assert_eq!(
+            statuses(results),
+            vec![
+                (Some(2), MetadataImportStatus::Skipped),
+                (Some(3), MetadataImportStatus::Created),
+                (Some(4), MetadataImportStatus::Error),
+                (Some(5), MetadataImportStatus::Skipped),
+                (Some(6), MetadataImportStatus::Skipped),
+            ]
+        );
+
+        let sent = Cell::new((0, 0));
+        let results = api
+            .upload_model_metadata(
+                metadata_file::read_csv(input.as_bytes(), &MetadataColumns::default()),
+                MetadataImportMode::Replace,
+                false,
+                &|done, total| sent.set((done, total)),
+            )
+            .unwrap();
+        assert_eq!(sent.get(), (2, 2));
+        assert_eq!(
+            statuses(results),
+            vec![
+                (Some(2), MetadataImportStatus::Updated),
+                (Some(3), MetadataImportStatus::Skipped),
+                (Some(4), MetadataImportStatus::Error),
+                (Some(5), MetadataImportStatus::Skipped),
+                (Some(6), MetadataImportStatus::Skipped),
+                (None, MetadataImportStatus::Deleted),
+            ]
+        );
+        let metadata = api.get_model_metadata(&bolt).unwrap().unwrap();
+        let mut properties: Vec<(String, String)> = metadata
+            .properties
+            .into_iter()
+            .map(|p| (p.name.to_lowercase(), p.value))
+            .collect();
+        properties.sort();
+        assert_eq!(
+            properties,
+            vec![
+                ("finish".to_string(), "zinc".to_string()),
+                ("material".to_string(), "brass".to_string()),
+            ]
+        );
+    }
 }
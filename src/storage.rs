@@ -0,0 +1,210 @@
+use futures::StreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, ObjectStoreScheme, PutPayload, WriteMultipart};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("I/O error")]
+    InputOutputError(#[from] std::io::Error),
+    #[error("Object storage error: {0}")]
+    ObjectStoreError(#[from] object_store::Error),
+    #[error("Invalid object storage URL: {0}")]
+    InvalidUrl(String),
+}
+
+/// The URL schemes of the supported object storage services
+const SCHEMES: [&str; 8] = ["s3", "s3a", "gs", "az", "azure", "adl", "abfs", "abfss"];
+
+/// Whether the input is the URL of an object or a prefix in cloud object storage, e.g. "s3://bucket/prefix",
+/// "gs://bucket/prefix" or "az://container/prefix"
+pub fn is_object_url(input: &str) -> bool {
+    input
+        .split_once("://")
+        .is_some_and(|(scheme, _)| SCHEMES.contains(&scheme.to_lowercase().as_str()))
+}
+
+/// One object in the storage
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageObject {
+    /// The URL of the object, in the same form as the URL it was listed from
+    pub url: String,
+    pub size: u64,
+}
+
+/// Lists the objects directly under the prefix, like the files in a directory. Objects in deeper "subdirectories"
+/// are not included
+pub fn list(url: &str) -> Result<Vec<StorageObject>, StorageError> {
+    let (url, store, prefix) = open(url)?;
+    let result = runtime()?.block_on(store.list_with_delimiter(Some(&prefix)))?;
+
+    // the object URLs keep the part of the URL before the prefix, e.g. the bucket
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    let base = &segments[..segments.len().saturating_sub(prefix.parts().count())];
+
+    let mut objects = Vec::new();
+    for object in result.objects {
+        let mut object_url = url.clone();
+        object_url
+            .path_segments_mut()
+            .map_err(|_| StorageError::InvalidUrl(url.to_string()))?
+            .clear()
+            .extend(base)
+            .extend(object.location.parts().map(|part| part.as_ref().to_owned()));
+        objects.push(StorageObject {
+            url: object_url.to_string(),
+            size: object.size,
+        });
+    }
+    Ok(objects)
+}
+
+/// Streams the object into a file with the same name in the directory and returns the path to the file
+pub fn fetch(url: &str, directory: &Path) -> Result<PathBuf, StorageError> {
+    let (_, store, location) = open(url)?;
+    let name = location
+        .filename()
+        .ok_or_else(|| StorageError::InvalidUrl(url.to_owned()))?
+        .to_owned();
+    let path = directory.join(name);
+
+    log::trace!("Downloading {} to {}...", url, path.to_string_lossy());
+    runtime()?.block_on(async {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        let mut stream = store.get(&location).await?.into_stream();
+        while let Some(bytes) = stream.next().await {
+            file.write_all(&bytes?)?;
+        }
+        file.flush()?;
+        Ok::<(), StorageError>(())
+    })?;
+    Ok(path)
+}
+
+/// Files larger than this are written to object storage in parts of this size
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// How many parts of a file are sent at the same time
+const PART_CONCURRENCY: usize = 4;
+
+/// Appends a key to the URL of a prefix, e.g. "s3://bucket/archive" and "a/part.step" into
+/// "s3://bucket/archive/a/part.step"
+pub fn join(url: &str, key: &str) -> Result<String, StorageError> {
+    let mut joined = Url::parse(url).map_err(|_| StorageError::InvalidUrl(url.to_owned()))?;
+    joined
+        .path_segments_mut()
+        .map_err(|_| StorageError::InvalidUrl(url.to_owned()))?
+        .pop_if_empty()
+        .extend(key.split('/').filter(|segment| !segment.is_empty()));
+    Ok(joined.to_string())
+}
+
+/// Writes the file to the object at the URL, replacing the object if it exists. A large file is streamed in parts
+/// with a multipart upload, so that it neither has to fit into memory nor into the size limit of a single request
+pub fn store(path: &Path, url: &str) -> Result<(), StorageError> {
+    let (_, store, location) = open(url)?;
+    let size = std::fs::metadata(path)?.len();
+
+    log::trace!("Uploading {} to {}...", path.to_string_lossy(), url);
+    runtime()?.block_on(async {
+        if size <= PART_SIZE as u64 {
+            store
+                .put(&location, PutPayload::from(std::fs::read(path)?))
+                .await?;
+            return Ok(());
+        }
+
+        let upload = store.put_multipart(&location).await?;
+        let mut writer = WriteMultipart::new_with_chunk_size(upload, PART_SIZE);
+        // an unfinished upload keeps its parts in the storage until it is aborted
+        if let Err(e) = write_parts(&mut writer, path).await {
+            let _ = writer.abort().await;
+            return Err(e);
+        }
+        writer.finish().await?;
+        Ok::<(), StorageError>(())
+    })
+}
+
+async fn write_parts(writer: &mut WriteMultipart, path: &Path) -> Result<(), StorageError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = vec![0u8; PART_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+        writer.wait_for_capacity(PART_CONCURRENCY).await?;
+        writer.write(&buffer[..read]);
+    }
+}
+
+/// Connects to the storage of the URL. The credentials are read from the environment in the way of each service's
+/// own tools, e.g. AWS_ACCESS_KEY_ID, GOOGLE_APPLICATION_CREDENTIALS or AZURE_STORAGE_ACCOUNT_KEY, with a fallback to
+/// the instance metadata of the cloud the command is running in
+fn open(url: &str) -> Result<(Url, Box<dyn ObjectStore>, ObjectPath), StorageError> {
+    let url = Url::parse(url).map_err(|_| StorageError::InvalidUrl(url.to_owned()))?;
+    let (scheme, _) =
+        ObjectStoreScheme::parse(&url).map_err(|_| StorageError::InvalidUrl(url.to_string()))?;
+    // the whole path of the URL is the location in the bucket or container, which is the host of the URL
+    let path = ObjectPath::from_url_path(url.path())
+        .map_err(|_| StorageError::InvalidUrl(url.to_string()))?;
+    let store: Box<dyn ObjectStore> = match scheme {
+        ObjectStoreScheme::AmazonS3 => {
+            Box::new(AmazonS3Builder::from_env().with_url(url.as_str()).build()?)
+        }
+        ObjectStoreScheme::GoogleCloudStorage => Box::new(
+            GoogleCloudStorageBuilder::from_env()
+                .with_url(url.as_str())
+                .build()?,
+        ),
+        ObjectStoreScheme::MicrosoftAzure => Box::new(
+            MicrosoftAzureBuilder::from_env()
+                .with_url(url.as_str())
+                .build()?,
+        ),
+        _ => return Err(StorageError::InvalidUrl(url.to_string())),
+    };
+    Ok((url, store, path))
+}
+
+fn runtime() -> Result<tokio::runtime::Runtime, StorageError> {
+    Ok(tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_is_object_url() {
+        assert!(is_object_url("s3://bucket/prefix"));
+        assert!(is_object_url("GS://bucket/part.step"));
+        assert!(is_object_url(
+            "abfss://container@account.dfs.core.windows.net/prefix"
+        ));
+        assert!(!is_object_url("/home/user/parts"));
+        assert!(!is_object_url("https://example.com/part.step"));
+
+        assert_eq!(
+            join("s3://bucket/archive/", "1/part 1.step").unwrap(),
+            "s3://bucket/archive/1/part%201.step"
+        );
+        assert_eq!(
+            join("gs://bucket", "manifest.csv").unwrap(),
+            "gs://bucket/manifest.csv"
+        );
+    }
+}
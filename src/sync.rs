@@ -0,0 +1,330 @@
+use crate::model::{
+    ListOfSyncChanges, Model, ModelMetadata, ModelMetadataItem, SyncAction, SyncChange,
+    SyncDifference, SyncStatus,
+};
+use crate::service::{Api, ApiError};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// What to compare and which differences to resolve. Local files missing from the folder are always uploaded
+#[derive(Clone, Debug, Default)]
+pub struct SyncOptions {
+    pub input: PathBuf,
+    pub folder: String,
+    /// The file extensions (in lower case) that are compared. Other files are ignored
+    pub extensions: Vec<String>,
+    /// The metadata property holding the SHA-256 checksum of the source file. If set, files that exist on both sides
+    /// are also compared by content, and the checksum is stored with every uploaded model
+    pub checksum_property: Option<String>,
+    /// Downloads the models that are missing from the local directory
+    pub download: bool,
+    /// Deletes the models whose source file is no longer in the local directory, as well as the outdated models of
+    /// changed files
+    pub delete: bool,
+    /// Only reports the differences, without changing anything
+    pub dry_run: bool,
+}
+
+/// Compares the local directory with the folder and resolves the differences as selected by the options
+///
+/// Files and models are matched by file name, ignoring the case. The returned report lists every difference and
+/// the outcome of its action. Failing to resolve one difference does not stop the others
+pub fn sync(api: &Api, options: &SyncOptions) -> Result<ListOfSyncChanges, ApiError> {
+    let folders = HashSet::from([options.folder.to_owned()]);
+    let mut remote: BTreeMap<String, Vec<Model>> = BTreeMap::new();
+    for model in api.list_all_models(Some(folders), None)?.models {
+        remote
+            .entry(remote_file_name(&model).to_lowercase())
+            .or_default()
+            .push(model);
+    }
+
+    let mut changes: Vec<SyncChange> = Vec::new();
+    for path in list_files(&options.input, &options.extensions)? {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let checksum = match &options.checksum_property {
+            Some(_) => Some(checksum(&path)?),
+            None => None,
+        };
+
+        let models = match remote.remove(&file.to_lowercase()) {
+            Some(models) => models,
+            None => {
+                changes.push(perform(
+                    api,
+                    options,
+                    &file,
+                    None,
+                    SyncDifference::LocalOnly,
+                    SyncAction::Upload,
+                    || upload(api, options, &path, checksum.as_deref()),
+                ));
+                continue;
+            }
+        };
+
+        let (property, checksum) = match (&options.checksum_property, &checksum) {
+            (Some(property), Some(checksum)) => (property, checksum),
+            _ => continue,
+        };
+        let mut unchanged = false;
+        for model in models.iter() {
+            if stored_checksum(api, &model.uuid, property)?.as_deref() == Some(checksum.as_str()) {
+                unchanged = true;
+                break;
+            }
+        }
+        if unchanged {
+            continue;
+        }
+
+        let change = perform(
+            api,
+            options,
+            &file,
+            None,
+            SyncDifference::Changed,
+            SyncAction::Upload,
+            || upload(api, options, &path, Some(checksum)),
+        );
+        let replaced = matches!(change.status, SyncStatus::Done | SyncStatus::Planned);
+        changes.push(change);
+
+        // the outdated models are only removed once the new version was uploaded
+        if options.delete && replaced {
+            for model in models {
+                changes.push(perform(
+                    api,
+                    options,
+                    &file,
+                    Some(model.uuid),
+                    SyncDifference::Changed,
+                    SyncAction::Delete,
+                    || api.delete_model(&model.uuid).map(|_| None),
+                ));
+            }
+        }
+    }
+
+    for model in remote.into_values().flatten() {
+        let file = remote_file_name(&model);
+        let action = if options.download {
+            SyncAction::Download
+        } else if options.delete {
+            SyncAction::Delete
+        } else {
+            SyncAction::None
+        };
+        changes.push(perform(
+            api,
+            options,
+            &file,
+            Some(model.uuid),
+            SyncDifference::RemoteOnly,
+            action,
+            || match action {
+                SyncAction::Download => api
+                    .download_model_to(&model.uuid, &options.input)
+                    .map(|_| None),
+                SyncAction::Delete => api.delete_model(&model.uuid).map(|_| None),
+                _ => Ok(None),
+            },
+        ));
+    }
+
+    Ok(ListOfSyncChanges::new(changes))
+}
+
+/// Runs the action, unless it is a dry run or the run is out of time, and reports the outcome. The action returns the
+/// UUID of a new model
+fn perform<F>(
+    api: &Api,
+    options: &SyncOptions,
+    file: &str,
+    uuid: Option<Uuid>,
+    difference: SyncDifference,
+    action: SyncAction,
+    run: F,
+) -> SyncChange
+where
+    F: FnOnce() -> Result<Option<Uuid>, ApiError>,
+{
+    let mut change = SyncChange {
+        file: file.to_owned(),
+        uuid,
+        difference,
+        action,
+        status: SyncStatus::Skipped,
+        error: None,
+    };
+
+    if action == SyncAction::None {
+        return change;
+    }
+    if options.dry_run {
+        change.status = SyncStatus::Planned;
+        return change;
+    }
+    if api.deadline().exceeded() {
+        change.status = SyncStatus::NotStarted;
+        return change;
+    }
+
+    log::trace!("Performing {} for {}...", action.as_str(), file);
+    match run() {
+        Ok(new_uuid) => {
+            change.status = SyncStatus::Done;
+            change.uuid = new_uuid.or(uuid);
+        }
+        Err(e) => {
+            log::warn!("Failed to {} {}: {}", action.as_str(), file, e);
+            change.status = SyncStatus::Failed;
+            change.error = Some(e.to_string());
+        }
+    }
+    change
+}
+
+fn upload(
+    api: &Api,
+    options: &SyncOptions,
+    path: &Path,
+    checksum: Option<&str>,
+) -> Result<Option<Uuid>, ApiError> {
+    let model = match api.upload_model(&options.folder, path)? {
+        Some(model) => model,
+        None => return Ok(None),
+    };
+
+    if let (Some(property), Some(checksum)) = (&options.checksum_property, checksum) {
+        let metadata = ModelMetadata::new(vec![ModelMetadataItem::new(
+            0,
+            property.to_owned(),
+            checksum.to_owned(),
+        )]);
+        api.set_model_metadata(&model.uuid, &metadata)?;
+    }
+    Ok(Some(model.uuid))
+}
+
+fn stored_checksum(api: &Api, uuid: &Uuid, property: &str) -> Result<Option<String>, ApiError> {
+    Ok(api.get_model_metadata(uuid)?.and_then(|metadata| {
+        metadata
+            .properties
+            .into_iter()
+            .find(|item| item.name.eq_ignore_ascii_case(property))
+            .map(|item| item.value)
+    }))
+}
+
+/// The name of the source file of the model. Model names may or may not include the extension
+fn remote_file_name(model: &Model) -> String {
+    let extension = model.file_type.trim_start_matches('.');
+    let has_extension = Path::new(&model.name)
+        .extension()
+        .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case(extension));
+    if extension.is_empty() || has_extension {
+        model.name.to_owned()
+    } else {
+        format!("{}.{}", model.name, extension)
+    }
+}
+
+fn list_files(directory: &Path, extensions: &[String]) -> Result<Vec<PathBuf>, ApiError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let accepted = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .is_some_and(|extension| extensions.contains(&extension));
+        if path.is_file() && accepted {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The SHA-256 checksum of the file, in hexadecimal
+fn checksum(path: &Path) -> Result<String, ApiError> {
+    let mut hasher = Sha256::new();
+    hasher.update(fs::read(path)?);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::fake::FakeClient;
+
+    #[test]
+    fn test_sync_dry_run() {
+        let directory = tempfile::tempdir().unwrap();
+        for name in ["bracket.stl", "housing.STEP", "notes.txt"] {
+            fs::write(directory.path().join(name), name).unwrap();
+        }
+
+        let housing = Uuid::new_v4();
+        let cover = Uuid::new_v4();
+        let client = FakeClient::new()
+            .with_folder(1, "parts")
+            .with_model(housing, "housing.step", 1, "finished")
+            .with_model(cover, "cover.stl", 1, "finished")
+            .with_metadata(&housing, "checksum", "outdated");
+        let api = Api::with_client(Box::new(client), None).unwrap();
+
+        let options = SyncOptions {
+            input: directory.path().to_owned(),
+            folder: "parts".to_string(),
+            extensions: vec!["stl".to_string(), "step".to_string()],
+            checksum_property: Some("checksum".to_string()),
+            delete: true,
+            dry_run: true,
+            ..Default::default()
+        };
+        let changes: Vec<(String, SyncDifference, SyncAction, SyncStatus)> = sync(&api, &options)
+            .unwrap()
+            .changes
+            .into_iter()
+            .map(|c| (c.file, c.difference, c.action, c.status))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "bracket.stl".to_string(),
+                    SyncDifference::LocalOnly,
+                    SyncAction::Upload,
+                    SyncStatus::Planned
+                ),
+                (
+                    "housing.STEP".to_string(),
+                    SyncDifference::Changed,
+                    SyncAction::Upload,
+                    SyncStatus::Planned
+                ),
+                (
+                    "housing.STEP".to_string(),
+                    SyncDifference::Changed,
+                    SyncAction::Delete,
+                    SyncStatus::Planned
+                ),
+                (
+                    "cover.stl".to_string(),
+                    SyncDifference::RemoteOnly,
+                    SyncAction::Delete,
+                    SyncStatus::Planned
+                ),
+            ]
+        );
+    }
+}
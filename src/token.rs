@@ -1,14 +1,15 @@
+use crate::configuration::{ClientConfiguration, TokenStore};
+use crate::model::{CachedToken, ListOfCachedTokens};
+use crate::secret::{self, SecretError};
 use base64::engine::general_purpose;
 use base64::Engine;
 use dirs::home_dir;
 use http::StatusCode;
 use jsonwebtoken::decode_header;
-use log;
-use rpassword;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::time::Duration;
+use std::fs::{self, File, OpenOptions};
+use std::io::IsTerminal;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -25,74 +26,216 @@ pub enum TokenError {
     FailedToObtainTokenFromProvider,
     #[error("Unknown tenant {0}")]
     UnknownTenant(String),
+    #[error("Failed to decode token claims")]
+    FailedToDecodeClaims,
+    #[error("Token expired or about to expire")]
+    TokenExpired,
+    #[error("Keyring error")]
+    KeyringError(#[from] keyring::Error),
+    #[error("Cannot determine the home directory")]
+    NoHomeDirectory,
+    #[error("No client secret available. Provide it in the configuration, via the PCLI_CLIENT_SECRET environment variable or with --secret-file")]
+    MissingClientSecret,
+    #[error("Failed to decrypt the client secret")]
+    SecretError(#[from] SecretError),
 }
 
+/// The environment variable that may contain the client secret
+pub const CLIENT_SECRET_VARIABLE: &str = "PCLI_CLIENT_SECRET";
+
+/// The service name under which PCLI stores entries in the operating system's keyring
+pub(crate) const KEYRING_SERVICE: &str = "pcli";
+
+fn token_store(configuration: &ClientConfiguration, tenant: &String) -> TokenStore {
+    configuration
+        .tenants
+        .get(tenant)
+        .map(|t| t.token_store)
+        .unwrap_or_default()
+}
+
+fn keyring_entry(tenant: &String, kind: &str) -> Result<keyring::Entry, TokenError> {
+    Ok(keyring::Entry::new(
+        KEYRING_SERVICE,
+        format!("{}.{}", tenant, kind).as_str(),
+    )?)
+}
+
+/// Reads the cached access token for the tenant from its configured store
+pub(crate) fn read_token(
+    configuration: &ClientConfiguration,
+    tenant: &String,
+) -> Result<String, TokenError> {
+    match token_store(configuration, tenant) {
+        TokenStore::File => read_token_from_file(tenant),
+        TokenStore::Keyring => {
+            log::trace!(
+                "Reading access token for tenant {} from the keyring...",
+                tenant
+            );
+            Ok(keyring_entry(tenant, "token")?.get_password()?)
+        }
+    }
+}
+
+/// Caches the access token for the tenant in its configured store
+pub(crate) fn write_token(
+    configuration: &ClientConfiguration,
+    tenant: &String,
+    token: &String,
+) -> Result<(), TokenError> {
+    match token_store(configuration, tenant) {
+        TokenStore::File => write_token_to_file(tenant, token),
+        TokenStore::Keyring => {
+            log::trace!(
+                "Writing access token for tenant {} to the keyring...",
+                tenant
+            );
+            Ok(keyring_entry(tenant, "token")?.set_password(token)?)
+        }
+    }
+}
+
+/// Returns the cached token if it is still valid, or obtains a new one. Several processes may do this at the same
+/// time for the same tenant (e.g. in parallel pipelines), so only one of them refreshes the token while the others
+/// wait for it and use the token it obtained
 pub fn get_token_for_tenant(
-    configuration: &crate::configuration::ClientConfiguration,
+    configuration: &ClientConfiguration,
     tenant: &String,
 ) -> Result<String, TokenError> {
+    if let Some(token) = read_valid_token(configuration, tenant) {
+        return Ok(token);
+    }
+
+    let _lock = lock_token(tenant)?;
+    // another process may have refreshed the token while this one was waiting for the lock
+    if let Some(token) = read_valid_token(configuration, tenant) {
+        return Ok(token);
+    }
+
     log::trace!("Obtaining new token from the provider...");
-    let token = read_token_from_file(tenant);
+    let token = request_new_token_from_provider(configuration, tenant)?;
+    write_token(configuration, tenant, &token)?;
+    Ok(token)
+}
 
-    match token {
+fn read_valid_token(configuration: &ClientConfiguration, tenant: &String) -> Option<String> {
+    match read_token(configuration, tenant) {
         Ok(token) => {
             log::trace!("Validating previously acquired token...");
-            match validate_token(token) {
+            match validate_token(token, configuration.token_leeway) {
                 Ok(token) => {
                     log::trace!("The current token is still valid");
-                    Ok(token)
+                    Some(token)
                 }
                 Err(_) => {
                     log::trace!("The existing token is no longer valid!");
-                    let token = request_new_token_from_provider(configuration, tenant)?;
-                    write_token_to_file(tenant, &token)?;
-                    Ok(token)
+                    None
                 }
             }
         }
         Err(_e) => {
             log::trace!("No existing token found");
-            let token = request_new_token_from_provider(configuration, tenant)?;
-            write_token_to_file(tenant, &token)?;
-            Ok(token)
+            None
         }
     }
 }
 
-pub fn validate_token(token: String) -> Result<String, TokenError> {
-    match decode_header(&token) {
-        Ok(_header) => Ok(token),
-        Err(_) => Err(TokenError::FailedToDecode),
+/// Locks the token of the tenant against refreshes by other processes until the returned file is dropped. A separate
+/// lock file is used, because the token file itself is replaced when it is written
+fn lock_token(tenant: &String) -> Result<File, TokenError> {
+    let file_name = format!("{}.lock", resolve_file_name(tenant)?);
+    log::trace!("Locking access token for tenant {}...", tenant);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(file_name)?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// Validates the token. A token that expires within the leeway (in seconds) is considered no longer valid
+pub(crate) fn validate_token(token: String, leeway: i64) -> Result<String, TokenError> {
+    if decode_header(&token).is_err() {
+        return Err(TokenError::FailedToDecode);
+    }
+
+    match seconds_to_expiry(&token) {
+        Ok(remaining) if remaining <= leeway => {
+            log::trace!("The token expires in {} second(s)", remaining);
+            Err(TokenError::TokenExpired)
+        }
+        // tokens without an expiry claim are accepted as they are
+        _ => Ok(token),
     }
 }
 
-pub fn resolve_file_name(tenant: &String) -> String {
-    let home_directory = home_dir().unwrap();
-    let home_directory = String::from(home_directory.to_str().unwrap());
+/// Decodes the claims of a JWT token without verifying its signature
+pub fn decode_claims(token: &str) -> Result<serde_json::Value, TokenError> {
+    let payload = match token.split('.').nth(1) {
+        Some(payload) => payload,
+        None => return Err(TokenError::FailedToDecodeClaims),
+    };
+
+    let payload = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|_| TokenError::FailedToDecodeClaims)?;
+    serde_json::from_slice(&payload).map_err(|_| TokenError::FailedToDecodeClaims)
+}
+
+/// Returns the expiry time of the token in seconds since the UNIX epoch
+pub fn expires_at(token: &str) -> Result<i64, TokenError> {
+    let claims = decode_claims(token)?;
+    match claims.get("exp").and_then(|exp| exp.as_i64()) {
+        Some(exp) => Ok(exp),
+        None => Err(TokenError::FailedToDecodeClaims),
+    }
+}
+
+/// Returns the number of seconds until the token expires (negative if already expired)
+pub fn seconds_to_expiry(token: &str) -> Result<i64, TokenError> {
+    let exp = expires_at(token)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    Ok(exp - now)
+}
+
+pub(crate) fn resolve_file_name(tenant: &str) -> Result<String, TokenError> {
+    let home_directory = home_dir().ok_or(TokenError::NoHomeDirectory)?;
+    let home_directory = String::from(home_directory.to_string_lossy());
     let default_token_file_path = home_directory;
 
-    let mut file_name = String::from(default_token_file_path);
+    let mut file_name = default_token_file_path;
     file_name.push_str("/.pcli.");
-    file_name.push_str(tenant.as_str());
+    file_name.push_str(tenant);
     file_name.push_str(".token");
 
-    file_name
+    Ok(file_name)
 }
 
-pub fn write_token_to_file(tenant: &String, token: &String) -> Result<(), TokenError> {
-    let file_name = resolve_file_name(&tenant);
+pub(crate) fn write_token_to_file(tenant: &String, token: &String) -> Result<(), TokenError> {
+    let file_name = resolve_file_name(tenant)?;
     log::trace!(
         "Writing access token for tenant {} from file {}...",
         tenant,
         file_name
     );
-    fs::write(file_name, token)?;
+    // written to a temporary file first and then renamed, so that no process ever reads a partially written token
+    let temporary_file_name = format!("{}.{}.tmp", file_name, std::process::id());
+    fs::write(&temporary_file_name, token)?;
+    if let Err(e) = fs::rename(&temporary_file_name, &file_name) {
+        let _ = fs::remove_file(&temporary_file_name);
+        return Err(TokenError::from(e));
+    }
 
     Ok(())
 }
 
-pub fn read_token_from_file(tenant: &String) -> Result<String, TokenError> {
-    let file_name = resolve_file_name(&tenant);
+pub(crate) fn read_token_from_file(tenant: &String) -> Result<String, TokenError> {
+    let file_name = resolve_file_name(tenant)?;
     log::trace!(
         "Reading access token for tenant {} to file {}...",
         tenant,
@@ -101,21 +244,57 @@ pub fn read_token_from_file(tenant: &String) -> Result<String, TokenError> {
     Ok(fs::read_to_string(file_name)?)
 }
 
-pub fn invalidate_token(tenant: &String) -> Result<(), TokenError> {
-    let file_name = resolve_file_name(&tenant);
+pub fn invalidate_token(
+    configuration: &ClientConfiguration,
+    tenant: &String,
+) -> Result<(), TokenError> {
+    let file_name = resolve_file_name(tenant)?;
     log::trace!(
         "Invalidating access token for tenant {} in file {}...",
         tenant,
         file_name
     );
-    match fs::remove_file(file_name) {
-        // There is nothing we can do if the file does not exist or it is locked.
-        Ok(()) => (),
-        Err(_) => (),
+    // There is nothing we can do if the file does not exist or it is locked.
+    let _ = fs::remove_file(file_name);
+
+    if token_store(configuration, tenant) == TokenStore::Keyring {
+        log::trace!(
+            "Invalidating access token for tenant {} in the keyring...",
+            tenant
+        );
+        match keyring_entry(tenant, "token")?.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => (),
+            Err(e) => return Err(TokenError::from(e)),
+        }
     }
     Ok(())
 }
 
+/// Lists the cached tokens of all configured tenants
+pub fn list_cached_tokens(configuration: &ClientConfiguration) -> ListOfCachedTokens {
+    let mut tenants: Vec<&String> = configuration.tenants.keys().collect();
+    tenants.sort();
+
+    let tokens: Vec<CachedToken> = tenants
+        .into_iter()
+        .map(|tenant| {
+            let store = token_store(configuration, tenant).to_string();
+            match read_token(configuration, tenant) {
+                Ok(token) => CachedToken::new(
+                    tenant.to_owned(),
+                    store,
+                    true,
+                    expires_at(&token).ok(),
+                    seconds_to_expiry(&token).ok(),
+                ),
+                Err(_) => CachedToken::new(tenant.to_owned(), store, false, None, None),
+            }
+        })
+        .collect();
+
+    ListOfCachedTokens::from(tokens)
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct AuthenticationResponse {
     token_type: String, //e.g. "Bearer"
@@ -124,13 +303,59 @@ struct AuthenticationResponse {
     scope: String, //e.g. "tenantApp"
 }
 
-fn read_client_secret_from_console() -> String {
+fn read_client_secret_from_console() -> Result<String, TokenError> {
+    // never block waiting for input when there is no one to enter it (e.g. in a CI pipeline)
+    if !std::io::stdin().is_terminal() {
+        return Err(TokenError::MissingClientSecret);
+    }
+
     log::trace!("User is required to enter the client secret via the console.");
-    rpassword::prompt_password("Enter client secret: ").unwrap()
+    Ok(rpassword::prompt_password("Enter client secret: ")?)
+}
+
+/// Resolves the client secret from the configuration, the environment, the keyring or, as a last resort, the console.
+/// A secret encrypted in the configuration is decrypted here, only when a new token is needed
+fn resolve_client_secret(
+    client_secret: Option<String>,
+    store: TokenStore,
+    tenant: &String,
+) -> Result<String, TokenError> {
+    match client_secret {
+        Some(client_secret) if secret::is_encrypted(&client_secret) => {
+            log::trace!("Decrypting client secret for tenant {}", tenant);
+            return Ok(secret::decrypt(&client_secret)?);
+        }
+        Some(client_secret) => return Ok(client_secret),
+        None => (),
+    }
+
+    match std::env::var(CLIENT_SECRET_VARIABLE) {
+        Ok(client_secret) if !client_secret.is_empty() => {
+            log::trace!("Using client secret from {}", CLIENT_SECRET_VARIABLE);
+            return Ok(client_secret);
+        }
+        _ => (),
+    }
+
+    match store {
+        TokenStore::File => read_client_secret_from_console(),
+        TokenStore::Keyring => {
+            let entry = keyring_entry(tenant, "client_secret")?;
+            match entry.get_password() {
+                Ok(client_secret) => Ok(client_secret),
+                Err(keyring::Error::NoEntry) => {
+                    let client_secret = read_client_secret_from_console()?;
+                    entry.set_password(&client_secret)?;
+                    Ok(client_secret)
+                }
+                Err(e) => Err(TokenError::from(e)),
+            }
+        }
+    }
 }
 
 fn request_new_token_from_provider(
-    configuration: &crate::configuration::ClientConfiguration,
+    configuration: &ClientConfiguration,
     tenant: &String,
 ) -> Result<String, TokenError> {
     log::trace!("Requesting new token...");
@@ -140,19 +365,12 @@ fn request_new_token_from_provider(
         Some(active_tenant) => {
             let client_id = active_tenant.client_id.clone();
             let client_secret = active_tenant.client_secret.clone();
-            let actual_client_secret;
             let security_provider_url = configuration.identity_provider_url.clone();
 
             log::trace!("Requesting for tenant {:?}...", tenant.to_owned());
 
-            match client_secret {
-                Some(client_secret) => {
-                    actual_client_secret = client_secret;
-                }
-                None => {
-                    actual_client_secret = read_client_secret_from_console();
-                }
-            }
+            let actual_client_secret =
+                resolve_client_secret(client_secret, active_tenant.token_store, tenant)?;
 
             if client_id.is_empty() {
                 return Err(TokenError::EmptyClientId);
@@ -171,8 +389,7 @@ fn request_new_token_from_provider(
                 .join(":")
                 .to_owned();
 
-            let encoded_credentials =
-                general_purpose::STANDARD.encode(combined_credentials.to_owned());
+            let encoded_credentials = general_purpose::STANDARD.encode(&combined_credentials);
             //let encoded_credentials = encode(combined_credentials);
 
             let mut authorization_header_value = String::from("Basic ");
@@ -185,9 +402,11 @@ fn request_new_token_from_provider(
 
             // Create the HTTP client instance
             //let client = reqwest::Client::new();
-            let client = reqwest::blocking::Client::builder()
-                .timeout(Duration::from_secs(20))
-                .build()?;
+            let mut builder = reqwest::blocking::Client::builder().timeout(Duration::from_secs(20));
+            if let Some(proxy) = &active_tenant.proxy {
+                builder = builder.proxy(proxy.to_proxy()?);
+            }
+            let client = builder.build()?;
 
             let response = client
                 .post(security_provider_url)
@@ -203,10 +422,11 @@ fn request_new_token_from_provider(
                         let response_text = response.text();
                         match response_text {
                             Ok(response_text) => {
-                                let response: AuthenticationResponse =
-                                    serde_yaml::from_str(&response_text).unwrap();
-                                let token = response.access_token;
-                                Ok(token)
+                                match serde_yaml::from_str::<AuthenticationResponse>(&response_text)
+                                {
+                                    Ok(response) => Ok(response.access_token),
+                                    Err(_) => Err(TokenError::FailedToObtainTokenFromProvider),
+                                }
                             }
                             Err(_) => Err(TokenError::FailedToObtainTokenFromProvider),
                         }
@@ -221,12 +441,18 @@ fn request_new_token_from_provider(
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-struct TokenEnvironment {
-    token: String,
-}
+#[cfg(test)]
+mod tests {
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-struct TokenContainer {
-    environments: HashMap<String, TokenEnvironment>,
+    use super::*;
+
+    #[test]
+    fn test_decode_claims() {
+        // {"alg":"none"}.{"exp":4102444800,"scp":["tenantApp"],"tenant":"mytenant"}.
+        let token = "eyJhbGciOiJub25lIn0.eyJleHAiOjQxMDI0NDQ4MDAsInNjcCI6WyJ0ZW5hbnRBcHAiXSwidGVuYW50IjoibXl0ZW5hbnQifQ.";
+        let claims = decode_claims(token).unwrap();
+        assert_eq!(claims["tenant"], "mytenant");
+        assert!(seconds_to_expiry(token).unwrap() > 0);
+        assert!(decode_claims("invalid").is_err());
+    }
 }
@@ -0,0 +1,244 @@
+use crate::model::{ModelMetadata, ModelMetadataItem};
+use crate::service::Api;
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+#[allow(clippy::enum_variant_names)]
+pub enum WatchError {
+    #[error("I/O error: {0}")]
+    InputOutputError(#[from] std::io::Error),
+    #[error("Failed to watch the directory: {0}")]
+    NotifyError(#[from] notify::Error),
+    #[error("Invalid metadata template: {0}")]
+    TemplateError(#[from] serde_yaml::Error),
+}
+
+/// Metadata applied to every uploaded model. Values may refer to the uploaded file with the placeholders {file}
+/// (file name), {stem} (file name without the extension), {extension} and {path} (relative to the watched directory)
+#[derive(Clone, Debug, Default)]
+pub struct MetadataTemplate(BTreeMap<String, String>);
+
+impl MetadataTemplate {
+    /// Reads the template from a YAML file with one property name and value per line (e.g. "source: PDM")
+    pub fn read(path: &Path) -> Result<MetadataTemplate, WatchError> {
+        let template = fs::read_to_string(path)?;
+        Ok(MetadataTemplate(serde_yaml::from_str(&template)?))
+    }
+
+    fn render(&self, file: &Path, input: &Path) -> ModelMetadata {
+        let text = |value: Option<&std::ffi::OsStr>| {
+            value
+                .map(|value| value.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let placeholders = [
+            ("{file}", text(file.file_name())),
+            ("{stem}", text(file.file_stem())),
+            ("{extension}", text(file.extension())),
+            (
+                "{path}",
+                file.strip_prefix(input)
+                    .unwrap_or(file)
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        ];
+
+        ModelMetadata::new(
+            self.0
+                .iter()
+                .map(|(name, value)| {
+                    let value = placeholders
+                        .iter()
+                        .fold(value.to_owned(), |value, (placeholder, replacement)| {
+                            value.replace(placeholder, replacement)
+                        });
+                    ModelMetadataItem::new(0, name.to_owned(), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// What to watch and how to upload the files
+#[derive(Clone, Debug)]
+pub struct WatchOptions {
+    pub input: PathBuf,
+    pub folder: String,
+    /// The file extensions (in lower case) that are uploaded. Other files are ignored
+    pub extensions: Vec<String>,
+    pub recursive: bool,
+    /// How long a file must remain unchanged before it is uploaded, so that it is not uploaded while still being
+    /// written
+    pub settle: Duration,
+    /// Uploads the files that are already in the directory when the watch starts
+    pub existing: bool,
+    pub metadata: Option<MetadataTemplate>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadStatus {
+    Uploaded,
+    Failed,
+}
+
+/// The result of uploading one file
+#[derive(Clone, Debug, Serialize)]
+pub struct UploadRecord {
+    /// Seconds since the Unix epoch
+    pub time: u64,
+    pub file: String,
+    pub status: UploadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Identifies the content of a file that was uploaded, to tell a changed file from a repeated notification
+type Signature = (u64, Option<SystemTime>);
+
+fn signature(path: &Path) -> Option<Signature> {
+    fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.is_file() && metadata.len() > 0)
+        .map(|metadata| (metadata.len(), metadata.modified().ok()))
+}
+
+/// Monitors the directory and uploads new and changed files into the folder, applying the metadata template to the
+/// new models. Each upload is reported to the callback. Runs until the process is interrupted or the maximum duration of
+/// the run is exceeded
+pub fn watch<F>(api: &Api, options: &WatchOptions, mut report: F) -> Result<(), WatchError>
+where
+    F: FnMut(&UploadRecord),
+{
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let mode = if options.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher.watch(&options.input, mode)?;
+    log::debug!("Watching {}...", options.input.to_string_lossy());
+
+    // the files waiting to settle, with the time they last changed
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    let mut uploaded: HashMap<PathBuf, Signature> = HashMap::new();
+
+    if options.existing {
+        for path in list_files(&options.input, options.recursive)? {
+            pending.insert(path, Instant::now());
+        }
+    }
+
+    loop {
+        if api.deadline().exceeded() {
+            return Ok(());
+        }
+        match receiver.recv_timeout(Duration::from_millis(500)) {
+            Ok(Ok(event)) => {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Any
+                ) {
+                    for path in event.paths {
+                        pending.insert(path, Instant::now());
+                    }
+                }
+            }
+            Ok(Err(e)) => log::warn!("Failed to watch {}: {}", options.input.to_string_lossy(), e),
+            Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, changed)| changed.elapsed() >= options.settle)
+            .map(|(path, _)| path.to_owned())
+            .collect();
+
+        for path in settled {
+            if api.deadline().exceeded() {
+                return Ok(());
+            }
+            pending.remove(&path);
+
+            if !is_accepted(&path, &options.extensions) {
+                continue;
+            }
+            let signature = match signature(&path) {
+                Some(signature) => signature,
+                None => continue,
+            };
+            if uploaded.get(&path) == Some(&signature) {
+                continue;
+            }
+
+            let record = upload(api, options, &path);
+            if record.status == UploadStatus::Uploaded {
+                uploaded.insert(path, signature);
+            }
+            report(&record);
+        }
+    }
+}
+
+fn is_accepted(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| extensions.contains(&extension))
+}
+
+fn list_files(directory: &Path, recursive: bool) -> Result<Vec<PathBuf>, WatchError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                files.extend(list_files(&path, recursive)?);
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn upload(api: &Api, options: &WatchOptions, path: &Path) -> UploadRecord {
+    log::trace!("Uploading file {}...", path.to_string_lossy());
+    let result = api.upload_model(&options.folder, path).and_then(|model| {
+        match (model, &options.metadata) {
+            (Some(model), Some(template)) => {
+                let metadata = template.render(path, &options.input);
+                api.set_model_metadata(&model.uuid, &metadata)?;
+                Ok(Some(model.uuid))
+            }
+            (model, _) => Ok(model.map(|model| model.uuid)),
+        }
+    });
+
+    let (status, uuid, error) = match result {
+        Ok(uuid) => (UploadStatus::Uploaded, uuid, None),
+        Err(e) => (UploadStatus::Failed, None, Some(e.to_string())),
+    };
+    UploadRecord {
+        time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        file: path.to_string_lossy().to_string(),
+        status,
+        uuid,
+        error,
+    }
+}